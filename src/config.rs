@@ -1,4 +1,8 @@
 use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::num::{NonZeroU16, NonZeroUsize};
+use std::path::PathBuf;
+use time::Weekday;
 
 mod deserializers {
     use super::LocaleConfig;
@@ -7,31 +11,83 @@ mod deserializers {
         de::{Deserializer, Error, Unexpected},
         Deserialize,
     };
+    use time::Weekday;
 
+    /// Only accepts absolute http or https URLs with a host since `Url::join` behaves unexpectedly
+    /// with anything else
     pub fn url<'a, D: Deserializer<'a>>(deserializer: D) -> Result<Option<Url>, D::Error> {
         Option::<String>::deserialize(deserializer)?
-            .as_deref()
-            .map(Url::parse)
+            .map(|url| match Url::parse(&url) {
+                Ok(parsed) if matches!(parsed.scheme(), "http" | "https") && parsed.has_host() => {
+                    Ok(parsed)
+                }
+                _ => Err(D::Error::invalid_value(
+                    Unexpected::Str(&url),
+                    &"an absolute http or https URL",
+                )),
+            })
+            .transpose()
+    }
+
+    pub(crate) fn required_url<'a, D: Deserializer<'a>>(deserializer: D) -> Result<Url, D::Error> {
+        url(deserializer)?.ok_or_else(|| D::Error::invalid_type(Unexpected::Unit, &"a URL"))
+    }
+
+    /// Only accepts bare origins (i.e https://comments.example.com) since a preconnect hint ignores
+    /// anything past the port, and stores them in their serialized form
+    pub(crate) fn origins<'a, D: Deserializer<'a>>(
+        deserializer: D,
+    ) -> Result<Vec<String>, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .into_iter()
+            .map(|origin| match Url::parse(&origin) {
+                Ok(parsed)
+                    if matches!(parsed.scheme(), "http" | "https")
+                        && parsed.has_host()
+                        && parsed.username().is_empty()
+                        && parsed.password().is_none()
+                        && parsed.path() == "/"
+                        && parsed.query().is_none()
+                        && parsed.fragment().is_none() =>
+                {
+                    Ok(parsed.origin().ascii_serialization())
+                }
+                _ => Err(D::Error::invalid_value(
+                    Unexpected::Str(&origin),
+                    &"an http or https origin (i.e https://example.com)",
+                )),
+            })
+            .collect()
+    }
+
+    pub(crate) fn weekday<'a, D: Deserializer<'a>>(
+        deserializer: D,
+    ) -> Result<Option<Weekday>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|weekday| match weekday.to_lowercase().as_str() {
+                "monday" => Ok(Weekday::Monday),
+                "tuesday" => Ok(Weekday::Tuesday),
+                "wednesday" => Ok(Weekday::Wednesday),
+                "thursday" => Ok(Weekday::Thursday),
+                "friday" => Ok(Weekday::Friday),
+                "saturday" => Ok(Weekday::Saturday),
+                "sunday" => Ok(Weekday::Sunday),
+                _ => Err(D::Error::invalid_value(
+                    Unexpected::Str(&weekday),
+                    &"a day of the week (i.e monday)",
+                )),
+            })
             .transpose()
-            .map_err(|error| D::Error::custom(error.to_string()))
     }
 
     pub(crate) fn locale<'a, D: Deserializer<'a>>(
         deserializer: D,
     ) -> Result<LocaleConfig, D::Error> {
         let locale = String::deserialize(deserializer)?;
-        let mut locale_iter = locale.split('_');
 
-        match (locale_iter.next(), locale_iter.next()) {
-            (Some(lang), Some(_)) => Ok(LocaleConfig {
-                lang: lang.to_string(),
-                locale,
-            }),
-            _ => Err(D::Error::invalid_value(
-                Unexpected::Str(&locale),
-                &"a valid locale string",
-            )),
-        }
+        LocaleConfig::parse(&locale).ok_or_else(|| {
+            D::Error::invalid_value(Unexpected::Str(&locale), &"a valid locale string")
+        })
     }
 }
 
@@ -40,6 +96,14 @@ mod deserializers {
 pub struct Config {
     pub(crate) name: String,
     pub(crate) description: String,
+    /// The description of the articles page with {articles} replaced by how many articles there
+    /// are (i.e "3 articles"), {count} by just the number and {name} by the name of the diary
+    pub(crate) articles_description: String,
+    /// The description of year pages, with the same replacements as `articles_description` except
+    /// for {entries} instead of {articles} and {year} for the year
+    pub(crate) year_description: String,
+    /// The description of month pages, like `year_description` with {month} for the month's name
+    pub(crate) month_description: String,
     pub(crate) author: Option<Author>,
     pub(crate) icon: Option<String>,
     pub(crate) cover: Option<String>,
@@ -48,11 +112,67 @@ pub struct Config {
     #[serde(deserialize_with = "deserializers::url")]
     pub(crate) url: Option<reqwest::Url>,
     pub(crate) twitter: TwitterConfig,
+    pub(crate) comments: Option<String>,
+    pub(crate) generate_og_images: bool,
+    pub(crate) humans: bool,
+    pub(crate) security: Option<SecurityConfig>,
+    pub(crate) sitemap_max_urls: usize,
+    pub(crate) sitemap_exclude: Vec<String>,
+    pub(crate) untitled: String,
+    pub(crate) meta_description_max: usize,
+    #[serde(deserialize_with = "deserializers::weekday")]
+    pub(crate) first_day_of_week: Option<Weekday>,
+    pub(crate) feed_generator: bool,
+    pub(crate) fonts: Vec<FontConfig>,
+    pub(crate) print_stylesheet: Option<String>,
+    pub(crate) fediverse: Option<String>,
+    pub(crate) newsletter: Option<String>,
+    pub(crate) index_entries_per_year: Option<usize>,
+    pub(crate) rich_descriptions: bool,
+    pub(crate) concurrency: Option<usize>,
+    pub(crate) max_download_bytes: Option<u64>,
+    pub(crate) viewport: Option<String>,
+    pub(crate) theme_color: Option<ThemeColor>,
+    #[serde(deserialize_with = "deserializers::origins")]
+    pub(crate) preconnect: Vec<String>,
+    pub(crate) nav: Vec<NavLink>,
+    pub(crate) headers: Option<HeadersConfig>,
+    pub(crate) feed_content_max_chars: Option<usize>,
+    pub(crate) all_page: bool,
+    pub(crate) max_years_ahead: u16,
+    pub(crate) alternates: Vec<AlternateConfig>,
+    pub(crate) paging_includes_articles: bool,
+    pub(crate) paging: PagingConfig,
+    pub(crate) articles_page: Option<bool>,
+    pub(crate) back_to_top: bool,
+    pub(crate) table_of_contents: bool,
+    pub(crate) summary_separator: Option<SummarySeparator>,
+    pub(crate) katex_link: KatexLink,
+    /// Shown atop cross-posted pages with {} replaced by a link to where they were originally
+    /// published
+    pub(crate) original_banner: Option<String>,
+    pub(crate) feed_sources: FeedSources,
+    pub(crate) archive_order: ArchiveOrder,
+    /// Years before this one share a single archive page instead of getting one each
+    pub(crate) archive_before: Option<i32>,
+    pub(crate) display_date: DisplayDate,
+    pub(crate) latest_entries: usize,
+    /// How the time pages take to read is estimated and worded, it isn't shown if not present
+    pub(crate) reading_time: Option<ReadingTimeConfig>,
+    pub(crate) robots: RobotsConfig,
+    /// A line rendered at the end of every footer with {year} replaced by the current year and
+    /// {author} by the name of the author
+    pub(crate) footer_text: Option<String>,
+    /// Where independent pages are read from, relative to the directory of the diary
+    pub(crate) pages_dir: PathBuf,
+    /// Where the assets copied as-is into the output are, relative to the directory of the diary
+    pub(crate) public_dir: PathBuf,
 }
 
 #[derive(Clone, Deserialize)]
 pub struct Author {
     pub(crate) name: String,
+    pub(crate) email: Option<String>,
     #[serde(deserialize_with = "deserializers::url")]
     pub(crate) url: Option<reqwest::Url>,
 }
@@ -63,17 +183,242 @@ pub struct LocaleConfig {
     pub(crate) lang: String,
 }
 
+impl LocaleConfig {
+    /// Parse a locale following the format `language_TERRITORY` (i.e en_US)
+    pub(crate) fn parse(locale: &str) -> Option<LocaleConfig> {
+        let mut locale_iter = locale.split('_');
+
+        match (locale_iter.next(), locale_iter.next()) {
+            (Some(lang), Some(_)) => Some(LocaleConfig {
+                lang: lang.to_string(),
+                locale: locale.to_string(),
+            }),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Clone, Deserialize)]
 pub struct TwitterConfig {
     pub(crate) site: Option<String>,
     pub(crate) creator: Option<String>,
 }
 
+#[derive(Clone, Deserialize)]
+pub struct FontConfig {
+    /// The path of the font relative to the fonts/ directory
+    pub(crate) href: String,
+    /// The format of the font (i.e woff2)
+    pub(crate) format: String,
+    #[serde(default)]
+    pub(crate) preload: bool,
+}
+
+/// A version of the diary in another language, sharing the same paths
+#[derive(Clone, Deserialize)]
+pub struct AlternateConfig {
+    /// The language of the version (i.e fr)
+    pub(crate) lang: String,
+    /// The name of the language in itself (i.e Français)
+    pub(crate) label: String,
+    #[serde(deserialize_with = "deserializers::required_url")]
+    pub(crate) url: reqwest::Url,
+}
+
+/// The wording of the links to the previous and next entries
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct PagingConfig {
+    pub(crate) yesterday: String,
+    pub(crate) tomorrow: String,
+    /// Used for entries at most `max_gap_days` days before, with {} replaced by how many days
+    pub(crate) days_ago: String,
+    /// Used for entries at most `max_gap_days` days after, with {} replaced by how many days
+    pub(crate) in_days: String,
+    pub(crate) previously: String,
+    pub(crate) next_up: String,
+    pub(crate) max_gap_days: i64,
+}
+
+impl Default for PagingConfig {
+    fn default() -> Self {
+        PagingConfig {
+            yesterday: "Yesterday:".to_string(),
+            tomorrow: "Tomorrow:".to_string(),
+            days_ago: "{} days ago:".to_string(),
+            in_days: "In {} days:".to_string(),
+            previously: "Previously:".to_string(),
+            next_up: "Next up:".to_string(),
+            max_gap_days: 1,
+        }
+    }
+}
+
+/// What marks the end of the summary inside of a page's body
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct SummarySeparator {
+    /// Whether the first divider block ends the summary
+    pub(crate) divider: bool,
+    /// A paragraph consisting of only this text ends the summary, it's left out of the page itself
+    pub(crate) marker: Option<String>,
+}
+
+impl Default for SummarySeparator {
+    fn default() -> Self {
+        SummarySeparator {
+            divider: true,
+            marker: Some("<!-- more -->".to_string()),
+        }
+    }
+}
+
+/// How the time a page takes to read is estimated and worded
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct ReadingTimeConfig {
+    /// How many words a minute readers are assumed to read
+    pub(crate) wpm: NonZeroUsize,
+    /// With {minutes} replaced by the minutes a page takes to read
+    pub(crate) template: String,
+}
+
+impl Default for ReadingTimeConfig {
+    fn default() -> Self {
+        ReadingTimeConfig {
+            wpm: NonZeroUsize::new(200).unwrap(),
+            template: "{minutes} min read".to_string(),
+        }
+    }
+}
+
+/// The content of the <meta name="robots"> of each kind of page, nothing is emitted for kinds
+/// without one which leaves them indexable
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct RobotsConfig {
+    /// Year and month pages
+    pub(crate) archives: Option<String>,
+    pub(crate) index: Option<String>,
+    /// Entry and article pages
+    pub(crate) entries: Option<String>,
+}
+
+/// The order of the entries on year and month pages
+#[derive(Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArchiveOrder {
+    Oldest,
+    Newest,
+}
+
+/// Which property the date shown on pages and cards comes from, falling back to the other one
+/// when it's missing
+#[derive(Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DisplayDate {
+    Date,
+    Published,
+}
+
+/// Which pages go into the Atom feed and the updates feed
+#[derive(Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedSources {
+    Entries,
+    Articles,
+    Both,
+}
+
+/// How the KaTeX stylesheet is linked to from pages
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KatexLink {
+    /// A regular stylesheet link which blocks rendering until it loads
+    Blocking,
+    /// A print stylesheet link that switches to all media once it loads so it doesn't block
+    /// rendering, with a regular link as a fallback when JavaScript is disabled
+    Async,
+    /// The stylesheet itself in a <style> so pages don't need another request before rendering,
+    /// falls back to a regular link when it can't be downloaded
+    Inline,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct NavLink {
+    pub(crate) label: String,
+    pub(crate) href: String,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(untagged)]
+pub enum ThemeColor {
+    /// A single color used regardless of the reader's color scheme
+    Single(String),
+    /// A color for each of the light and dark color schemes
+    PerScheme { light: String, dark: String },
+}
+
+#[derive(Clone, Deserialize)]
+pub struct SecurityConfig {
+    pub(crate) contact: String,
+    #[serde(default = "SecurityConfig::default_expires_in_days")]
+    pub(crate) expires_in_days: NonZeroU16,
+}
+
+impl SecurityConfig {
+    fn default_expires_in_days() -> NonZeroU16 {
+        NonZeroU16::new(365).unwrap()
+    }
+}
+
+#[derive(Clone, Deserialize)]
+pub struct HeadersConfig {
+    #[serde(default = "HeadersConfig::default_rules")]
+    pub(crate) rules: Vec<HeaderRule>,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct HeaderRule {
+    /// The paths the headers apply to (i.e /katex/*)
+    pub(crate) path: String,
+    pub(crate) headers: BTreeMap<String, String>,
+}
+
+impl HeadersConfig {
+    /// KaTeX and downloaded media never change under the same path so they can be cached forever,
+    /// unlike pages which change whenever the diary is regenerated
+    fn default_rules() -> Vec<HeaderRule> {
+        const IMMUTABLE: &str = "public, max-age=31536000, immutable";
+
+        vec![
+            HeaderRule {
+                path: "/katex/*".to_string(),
+                headers: BTreeMap::from([("Cache-Control".to_string(), IMMUTABLE.to_string())]),
+            },
+            HeaderRule {
+                path: "/media/*".to_string(),
+                headers: BTreeMap::from([("Cache-Control".to_string(), IMMUTABLE.to_string())]),
+            },
+            HeaderRule {
+                path: "/*".to_string(),
+                headers: BTreeMap::from([(
+                    "Cache-Control".to_string(),
+                    "public, max-age=600".to_string(),
+                )]),
+            },
+        ]
+    }
+}
+
 impl Default for Config {
     fn default() -> Self {
         Config {
             name: "Diary".to_string(),
             description: "A neat diary".to_string(),
+            articles_description: "{articles} of {name}".to_string(),
+            year_description: "{entries} of {name} from {year}".to_string(),
+            month_description: "{entries} of {name} from {month} {year}".to_string(),
             author: None,
             icon: None,
             cover: None,
@@ -86,6 +431,51 @@ impl Default for Config {
                 site: None,
                 creator: None,
             },
+            comments: None,
+            generate_og_images: false,
+            humans: false,
+            security: None,
+            sitemap_max_urls: crate::sitemap::MAX_URLS,
+            sitemap_exclude: Vec::new(),
+            untitled: "Untitled".to_string(),
+            meta_description_max: 160,
+            first_day_of_week: None,
+            feed_generator: true,
+            fonts: Vec::new(),
+            print_stylesheet: None,
+            fediverse: None,
+            newsletter: None,
+            index_entries_per_year: None,
+            rich_descriptions: false,
+            concurrency: None,
+            max_download_bytes: None,
+            viewport: None,
+            theme_color: None,
+            preconnect: Vec::new(),
+            nav: Vec::new(),
+            headers: None,
+            feed_content_max_chars: None,
+            all_page: false,
+            max_years_ahead: 1,
+            alternates: Vec::new(),
+            paging_includes_articles: false,
+            paging: PagingConfig::default(),
+            articles_page: None,
+            back_to_top: false,
+            table_of_contents: false,
+            summary_separator: None,
+            katex_link: KatexLink::Blocking,
+            original_banner: Some("Originally published at {}".to_string()),
+            feed_sources: FeedSources::Both,
+            archive_order: ArchiveOrder::Oldest,
+            archive_before: None,
+            display_date: DisplayDate::Date,
+            latest_entries: 3,
+            reading_time: Some(ReadingTimeConfig::default()),
+            robots: RobotsConfig::default(),
+            footer_text: None,
+            pages_dir: PathBuf::from("pages"),
+            public_dir: PathBuf::from("public"),
         }
     }
 }
@@ -94,4 +484,16 @@ impl Config {
     pub fn get_atom_id(&self) -> Option<&reqwest::Url> {
         self.url.as_ref()
     }
+
+    /// The content of every page's <meta name="viewport">
+    pub(crate) fn viewport(&self) -> &str {
+        self.viewport
+            .as_deref()
+            .unwrap_or("width=device-width, initial-scale=1")
+    }
+
+    /// The day weeks start on in anything calendar-like, defaults to Sunday
+    pub(crate) fn first_day_of_week(&self) -> Weekday {
+        self.first_day_of_week.unwrap_or(Weekday::Sunday)
+    }
 }
@@ -0,0 +1,158 @@
+use anyhow::{bail, Result};
+
+/// Elements that never have a closing tag
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Elements whose closing tag may be left out, they get closed by their parent closing instead
+const OPTIONAL_END_ELEMENTS: &[&str] = &[
+    "colgroup", "dd", "dt", "li", "optgroup", "option", "p", "rp", "rt", "tbody", "td", "tfoot",
+    "th", "thead", "tr",
+];
+
+/// Elements whose content isn't HTML and so is skipped up until their closing tag
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style", "textarea", "title"];
+
+/// Check that every tag opened in an HTML fragment is closed in the right order
+///
+/// This isn't a full HTML parser, it only catches the mistakes that would leak out of a partial and
+/// into the rest of the page like a missing `</div>`
+pub fn check_balanced(fragment: &str) -> Result<()> {
+    let mut open: Vec<String> = Vec::new();
+    let mut rest = fragment;
+
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start..];
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            match comment.find("-->") {
+                Some(end) => rest = &comment[end + 3..],
+                None => bail!("Unclosed comment"),
+            }
+            continue;
+        }
+
+        if rest.starts_with("<!") || rest.starts_with("<?") {
+            match rest.find('>') {
+                Some(end) => rest = &rest[end + 1..],
+                None => bail!("Unclosed declaration"),
+            }
+            continue;
+        }
+
+        if let Some(closing) = rest.strip_prefix("</") {
+            let end = match closing.find('>') {
+                Some(end) => end,
+                None => bail!("Unfinished closing tag"),
+            };
+            let name = closing[..end].trim().to_ascii_lowercase();
+            rest = &closing[end + 1..];
+
+            while open.last().map_or(false, |last| {
+                *last != name && OPTIONAL_END_ELEMENTS.contains(&last.as_str())
+            }) {
+                open.pop();
+            }
+            match open.pop() {
+                Some(last) if last == name => {}
+                Some(last) => bail!("Found </{}> while <{}> is still open", name, last),
+                None => bail!("Found </{}> without a matching opening tag", name),
+            }
+            continue;
+        }
+
+        let tag = &rest[1..];
+        if !tag.starts_with(|char: char| char.is_ascii_alphabetic()) {
+            // A lone < in text
+            rest = tag;
+            continue;
+        }
+
+        let name_end = tag
+            .find(|char: char| char.is_whitespace() || char == '/' || char == '>')
+            .unwrap_or(tag.len());
+        let name = tag[..name_end].to_ascii_lowercase();
+
+        // Find the end of the tag while skipping over any > inside of quoted attributes
+        let mut quote = None;
+        let end = tag.char_indices().find_map(|(index, char)| {
+            match (quote, char) {
+                (None, '"' | '\'') => quote = Some(char),
+                (Some(current), _) if current == char => quote = None,
+                (None, '>') => return Some(index),
+                _ => {}
+            }
+            None
+        });
+        let end = match end {
+            Some(end) => end,
+            None => bail!("Unfinished <{}> tag", name),
+        };
+        let self_closing = tag[..end].ends_with('/');
+        rest = &tag[end + 1..];
+
+        if self_closing || VOID_ELEMENTS.contains(&name.as_str()) {
+            continue;
+        }
+
+        if RAW_TEXT_ELEMENTS.contains(&name.as_str()) {
+            let closing = format!("</{}", name);
+            match rest.to_ascii_lowercase().find(&closing) {
+                Some(index) => rest = &rest[index..],
+                None => bail!("Unclosed tag <{}>", name),
+            }
+        }
+
+        open.push(name);
+    }
+
+    match open
+        .into_iter()
+        .rev()
+        .find(|name| !OPTIONAL_END_ELEMENTS.contains(&name.as_str()))
+    {
+        Some(name) => bail!("Unclosed tag <{}>", name),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::check_balanced;
+
+    #[test]
+    fn balanced_fragments() {
+        check_balanced("").unwrap();
+        check_balanced(r#"<div class="a>b"><a href="/">Home</a><br><img src="x.png" /></div>"#)
+            .unwrap();
+        check_balanced("<ul><li>One<li>Two</ul><p>Text").unwrap();
+        check_balanced("<!-- <div> --><script>if (a < b) { '<div>' }</script>").unwrap();
+        check_balanced("<!DOCTYPE html><SPAN>1 < 2</span>").unwrap();
+    }
+
+    #[test]
+    fn unbalanced_fragments() {
+        assert_eq!(
+            check_balanced("<div><span></div>").unwrap_err().to_string(),
+            "Found </div> while <span> is still open"
+        );
+        assert_eq!(
+            check_balanced("<nav><div></nav>").unwrap_err().to_string(),
+            "Found </nav> while <div> is still open"
+        );
+        assert_eq!(
+            check_balanced("<header><div>").unwrap_err().to_string(),
+            "Unclosed tag <div>"
+        );
+        assert_eq!(
+            check_balanced("</footer>").unwrap_err().to_string(),
+            "Found </footer> without a matching opening tag"
+        );
+        assert_eq!(
+            check_balanced("<a href=\"/\"").unwrap_err().to_string(),
+            "Unfinished <a> tag"
+        );
+    }
+}
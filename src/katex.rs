@@ -1,61 +1,303 @@
-use crate::{write, EXPORT_DIR};
+use crate::write;
 use anyhow::{bail, Result};
 use futures_util::stream::{FuturesUnordered, TryStreamExt};
-use reqwest::Client;
-use std::path::Path;
+use reqwest::{Client, Response};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
 use tokio::task::JoinHandle;
+use tracing::{info, warn};
 
-pub fn download(client: Client) -> JoinHandle<Result<()>> {
-    const CDN_URL: &str = "https://cdn.jsdelivr.net/npm/katex@0.15.1/dist/";
-    const KATEX_DIR: &str = "katex";
+pub(crate) const CDN_URL: &str = "https://cdn.jsdelivr.net/npm/katex@0.15.1/dist/";
+const KATEX_DIR: &str = "katex";
+const STYLESHEET: &str = "katex.min.css";
+const CACHE_FILE: &str = "cache.json";
 
-    async fn download_file(client: &Client, file: &str) -> Result<()> {
-        let response = client.get(format!("{}{}", CDN_URL, file)).send().await?;
+/// What was last downloaded into the cache's katex/ directory so later builds can skip
+/// downloading KaTeX again
+#[derive(Serialize, Deserialize)]
+struct Cache {
+    /// The URL the files were downloaded from, which changes along with KaTeX's version
+    source: String,
+    /// The size of every downloaded file by its path inside of the katex/ directory
+    files: BTreeMap<String, u64>,
+}
 
-        let status = response.status();
-        if status.is_client_error() || status.is_server_error() {
-            bail!(
-                "Download request for file {} failed with status code {}",
-                file,
-                status
-            )
+impl Cache {
+    async fn read(directory: &Path) -> Option<Cache> {
+        let cache = tokio::fs::read(directory.join(CACHE_FILE)).await.ok()?;
+        serde_json::from_slice(&cache).ok()
+    }
+
+    /// Whether the cache was downloaded from `source` and all of its files are still there
+    /// unchanged
+    async fn is_fresh(&self, directory: &Path, source: &str) -> bool {
+        if self.source != source {
+            return false;
         }
 
-        let bytes = response.bytes().await?;
+        for (file, size) in &self.files {
+            match tokio::fs::metadata(directory.join(file)).await {
+                Ok(metadata) if metadata.len() == *size => {}
+                _ => return false,
+            }
+        }
+
+        true
+    }
+}
+
+/// Read KaTeX's stylesheet that `download` downloaded into `output` for inlining into pages, the
+/// fonts it refers to relative to itself are made to point into the katex/ directory so they're
+/// found from pages at any depth
+pub async fn inline_styles(output: &Path) -> Result<String> {
+    let styles = tokio::fs::read_to_string(output.join(KATEX_DIR).join(STYLESHEET)).await?;
 
-        write(Path::new(EXPORT_DIR).join(KATEX_DIR).join(file), bytes).await?;
+    Ok(absolute_urls(&styles))
+}
 
-        Ok(())
+/// Split the quotes around a `url()` argument from the URL itself
+fn unquote(argument: &str) -> (&str, &str) {
+    match argument.chars().next() {
+        Some(quote @ ('"' | '\'')) => argument.split_at(quote.len_utf8()),
+        _ => ("", argument),
+    }
+}
+
+/// Make the relative URLs in KaTeX's stylesheet point into the katex/ directory
+fn absolute_urls(styles: &str) -> String {
+    let mut parts = styles.split("url(");
+    let mut absolute = parts.next().unwrap_or_default().to_string();
+    for part in parts {
+        let (quote, part) = unquote(part);
+        absolute.push_str("url(");
+        absolute.push_str(quote);
+        if !(part.starts_with('/') || part.starts_with("data:") || part.contains("://")) {
+            absolute.push_str(&format!("/{}/", KATEX_DIR));
+        }
+        absolute.push_str(part);
+    }
+
+    absolute
+}
+
+/// Read the body of a response unless it's bigger than `max_bytes`, in which case the size it got
+/// to is returned instead
+/// The size announced in Content-Length is checked first but the body is still counted as it's
+/// read since servers aren't required to announce it
+pub(crate) async fn read_limited(
+    mut response: Response,
+    max_bytes: Option<u64>,
+) -> Result<Result<Vec<u8>, u64>> {
+    let max_bytes = match max_bytes {
+        Some(max_bytes) => max_bytes,
+        None => return Ok(Ok(response.bytes().await?.to_vec())),
+    };
+
+    if let Some(length) = response
+        .content_length()
+        .filter(|&length| length > max_bytes)
+    {
+        return Ok(Err(length));
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        body.extend_from_slice(&chunk);
+        if body.len() as u64 > max_bytes {
+            return Ok(Err(body.len() as u64));
+        }
+    }
+
+    Ok(Ok(body))
+}
+
+/// Request one of KaTeX's files, failing on error statuses and otherwise reading it with
+/// `read_limited`
+async fn fetch(
+    client: &Client,
+    cdn_url: &str,
+    file: &str,
+    max_bytes: Option<u64>,
+) -> Result<Result<Vec<u8>, u64>> {
+    let response = client.get(format!("{}{}", cdn_url, file)).send().await?;
+
+    let status = response.status();
+    if status.is_client_error() || status.is_server_error() {
+        bail!(
+            "Download request for file {} failed with status code {}",
+            file,
+            status
+        )
+    }
+
+    read_limited(response, max_bytes).await
+}
+
+/// Download KaTeX's stylesheet and the fonts it uses into `cache` and copy them into `output`
+/// Fonts bigger than `max_bytes` fail the download when `strict`, otherwise they're skipped with a
+/// warning, while a stylesheet bigger than it always fails the download since there's nothing to
+/// use without it
+/// Nothing is downloaded if a previous build already downloaded all of the files of the same
+/// version of KaTeX into `cache` and they're still there, which is kept outside of the output so
+/// that it's neither deployed nor deleted by `--clean`
+pub fn download(
+    client: Client,
+    cache: PathBuf,
+    output: PathBuf,
+    max_bytes: Option<u64>,
+    strict: bool,
+) -> JoinHandle<Result<()>> {
+    download_from(
+        client,
+        CDN_URL.to_string(),
+        cache,
+        output,
+        max_bytes,
+        strict,
+    )
+}
+
+/// Like `download` but from another copy of KaTeX's dist/ directory at `cdn_url` (i.e a mirror)
+pub fn download_from(
+    client: Client,
+    cdn_url: String,
+    cache: PathBuf,
+    output: PathBuf,
+    max_bytes: Option<u64>,
+    strict: bool,
+) -> JoinHandle<Result<()>> {
+    /// Download a single file, returning its size or nothing if it was skipped
+    async fn download_file(
+        client: &Client,
+        cdn_url: &str,
+        cache_dir: &Path,
+        file: &str,
+        max_bytes: Option<u64>,
+        strict: bool,
+    ) -> Result<Option<(String, u64)>> {
+        let bytes = match fetch(client, cdn_url, file, max_bytes).await? {
+            Ok(bytes) => bytes,
+            Err(length) if strict => bail!(
+                "KaTeX file {} is at least {} bytes which is over max_download_bytes of {}",
+                file,
+                length,
+                max_bytes.unwrap_or_default()
+            ),
+            Err(length) => {
+                warn!(
+                    msg = "Skipping KaTeX file over max_download_bytes",
+                    file,
+                    bytes = length,
+                    max_bytes
+                );
+                return Ok(None);
+            }
+        };
+
+        let size = bytes.len() as u64;
+        write(cache_dir.join(file), bytes).await?;
+
+        Ok(Some((file.to_string(), size)))
+    }
+
+    /// Copy the downloaded `files` from the cache into the output
+    async fn copy_files<'a, I>(cache_dir: &Path, katex_dir: &Path, files: I) -> Result<()>
+    where
+        I: Iterator<Item = &'a String>,
+    {
+        files
+            .map(|file| async move {
+                let bytes = tokio::fs::read(cache_dir.join(file)).await?;
+                write(katex_dir.join(file), bytes).await
+            })
+            .collect::<FuturesUnordered<_>>()
+            .try_collect()
+            .await
     }
 
     tokio::spawn(async move {
-        let response = client
-            .get(format!("{}{}", CDN_URL, "katex.min.css"))
-            .send()
-            .await?;
+        let cache_dir = cache.join(KATEX_DIR);
+        let katex_dir = output.join(KATEX_DIR);
+        if let Some(cache) = Cache::read(&cache_dir).await {
+            if cache.is_fresh(&cache_dir, &cdn_url).await {
+                info!(msg = "KaTeX is already downloaded", source = cdn_url);
+                return copy_files(&cache_dir, &katex_dir, cache.files.keys()).await;
+            }
+        }
 
-        let katex_styles = response.text().await?;
+        let katex_styles = match fetch(&client, &cdn_url, STYLESHEET, max_bytes).await? {
+            Ok(bytes) => String::from_utf8(bytes)?,
+            Err(length) => bail!(
+                "KaTeX file {} is at least {} bytes which is over max_download_bytes of {}",
+                STYLESHEET,
+                length,
+                max_bytes.unwrap_or_default()
+            ),
+        };
 
         let assets_downloads = katex_styles
             .split("url(")
             .skip(1)
-            .map(|part| part.split(')').next())
+            .map(|part| unquote(part).1.split(&[')', '"', '\''][..]).next())
             .map(|file| {
                 file.ok_or_else(|| {
                     anyhow::format_err!("Failed to parse asset URL from Katex stylesheet")
                 })
             })
-            .map(|result| result.map(|file| download_file(&client, file)))
+            .map(|result| {
+                result.map(|file| {
+                    download_file(&client, &cdn_url, &cache_dir, file, max_bytes, strict)
+                })
+            })
             .collect::<Result<FuturesUnordered<_>>>()?;
 
-        tokio::try_join!(
-            write(
-                Path::new(EXPORT_DIR).join(KATEX_DIR).join("katex.min.css"),
-                &katex_styles
-            ),
-            assets_downloads.try_collect::<()>(),
+        let (_, assets) = tokio::try_join!(
+            write(cache_dir.join(STYLESHEET), &katex_styles),
+            assets_downloads.try_collect::<Vec<_>>(),
         )?;
 
+        let complete = assets.iter().all(Option::is_some);
+        let mut files = assets.into_iter().flatten().collect::<BTreeMap<_, _>>();
+        files.insert(STYLESHEET.to_string(), katex_styles.len() as u64);
+        copy_files(&cache_dir, &katex_dir, files.keys()).await?;
+
+        // Files skipped for being too big leave the cache unwritten so they're tried again
+        if complete {
+            let cache = Cache {
+                source: cdn_url,
+                files,
+            };
+            write(cache_dir.join(CACHE_FILE), serde_json::to_vec(&cache)?).await?;
+        }
+
         Ok(())
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::absolute_urls;
+
+    #[test]
+    fn relative_urls_point_into_katex_dir() {
+        assert_eq!(
+            absolute_urls(
+                "@font-face{src:url(fonts/KaTeX_Main-Bold.woff2) format(\"woff2\"),\
+                url(data:font/woff;base64,AA==)}.a{background:url(https://example.com/a.png)}"
+            ),
+            "@font-face{src:url(/katex/fonts/KaTeX_Main-Bold.woff2) format(\"woff2\"),\
+                url(data:font/woff;base64,AA==)}.a{background:url(https://example.com/a.png)}"
+        );
+        assert_eq!(
+            absolute_urls(
+                "@font-face{src:url(\"fonts/KaTeX_Main-Bold.woff2\"),\
+                url('fonts/KaTeX_Main-Bold.woff')}"
+            ),
+            "@font-face{src:url(\"/katex/fonts/KaTeX_Main-Bold.woff2\"),\
+            url('/katex/fonts/KaTeX_Main-Bold.woff')}"
+        );
+    }
+}
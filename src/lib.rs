@@ -1,38 +1,56 @@
 mod config;
+mod fragment;
 pub mod katex;
 mod months;
+mod og_image;
+mod options;
+mod sitemap;
 mod syndication;
 
-use crate::config::Config;
-use crate::syndication::atom;
+use crate::config::{
+    ArchiveOrder, Config, DisplayDate, FeedSources, KatexLink, LocaleConfig, NavLink, PagingConfig,
+    ReadingTimeConfig, SummarySeparator, ThemeColor,
+};
+pub use crate::options::Options;
+use crate::syndication::{atom, json_feed};
 use anyhow::{bail, Context, Result};
 use either::Either;
 use futures_util::stream::{FuturesUnordered, StreamExt, TryStreamExt};
+use image::io::Reader as ImageReader;
 use itertools::Itertools;
 use maud::{html, Markup, PreEscaped, Render, DOCTYPE};
 use notion_generator::{
-    download::Downloadables,
+    download::{Downloadable, Downloadables},
     options::HeadingAnchors,
     render::{Heading, Title},
     response::{
         properties::{DateProperty, RichTextProperty, TitleProperty},
-        NotionId, Page, PlainText, RichText,
+        Block, BlockType, Emoji, EmojiOrFile, File, NotionDate, NotionId, Page, PlainText,
+        RichText, RichTextType,
     },
     HtmlRenderer,
 };
-use reqwest::Client;
-use serde::Deserialize;
+use reqwest::{header, Client};
+use serde::{Deserialize, Serialize};
 use std::{
+    cmp::Reverse,
     collections::{BTreeMap, HashMap, HashSet},
-    io,
+    fmt,
+    future::Future,
+    io::{self, Cursor},
     ops::{Bound, Not},
     path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
 };
 use time::{
     format_description::{well_known::Rfc3339, FormatItem},
     macros::format_description,
-    Date, Month, OffsetDateTime,
+    Date, Duration, Month, OffsetDateTime, UtcOffset,
 };
+use time_tz::{timezones, Offset, TimeZone};
 use tokio::task::JoinHandle;
 use tokio_stream::wrappers::ReadDirStream;
 use tracing::{info, warn};
@@ -41,6 +59,17 @@ pub const EXPORT_DIR: &str = "output";
 pub const DIARY_GENERATOR: &str = env!("CARGO_PKG_NAME");
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 pub const REPOSITORY: &str = env!("CARGO_PKG_REPOSITORY");
+const DRAFTS_DIR: &str = "_drafts";
+const FONTS_DIR: &str = "fonts";
+const PARTIALS_DIR: &str = "partials";
+const CACHE_DIR: &str = ".cache";
+/// Written into the output directory on every build, `--clean` only deletes directories that have
+/// it so that it can't delete a directory the diary was never generated into
+const OUTPUT_MARKER: &str = ".diary-generator";
+const FEED_FILE: &str = "feed.xml";
+const JSON_FEED_FILE: &str = "feed.json";
+const MAX_RELATED: usize = 3;
+const TAG_WEIGHTS: usize = 5;
 
 #[derive(Deserialize)]
 pub struct Properties {
@@ -49,6 +78,53 @@ pub struct Properties {
     pub url: RichTextProperty,
     pub description: RichTextProperty,
     pub published: DateProperty,
+    #[serde(default)]
+    pub series: Option<RichTextProperty>,
+    #[serde(default)]
+    pub lang: Option<RichTextProperty>,
+    #[serde(default)]
+    pub tags: Option<MultiSelectProperty>,
+    #[serde(default)]
+    pub og_image: Option<FilesProperty>,
+    #[serde(default)]
+    pub unlisted: Option<CheckboxProperty>,
+    #[serde(default)]
+    pub draft: Option<CheckboxProperty>,
+    #[serde(default)]
+    pub canonical: Option<UrlProperty>,
+    #[serde(default)]
+    pub feed_summary: Option<RichTextProperty>,
+}
+
+#[derive(Deserialize)]
+pub struct UrlProperty {
+    pub id: String,
+    pub url: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct CheckboxProperty {
+    pub id: String,
+    pub checkbox: bool,
+}
+
+#[derive(Deserialize)]
+pub struct FilesProperty {
+    pub id: String,
+    pub files: Vec<File>,
+}
+
+#[derive(Deserialize)]
+pub struct MultiSelectProperty {
+    pub id: String,
+    pub multi_select: Vec<SelectOption>,
+}
+
+#[derive(Deserialize)]
+pub struct SelectOption {
+    pub id: String,
+    pub name: String,
+    pub color: String,
 }
 
 impl Title for Properties {
@@ -57,62 +133,626 @@ impl Title for Properties {
     }
 }
 
-fn render_article_time(date: Date) -> Result<Markup> {
-    const HTML_FORMAT: &[FormatItem<'_>] = format_description!("[year]-[month]-[day]");
-    const READABLE_DATE: &[FormatItem<'_>] = format_description!("[month repr:long] [day], [year]");
+/// The offset from UTC of a Notion date's `time_zone` at the moment it starts, dates without a
+/// time zone or with one that isn't in the IANA database are in UTC
+fn utc_offset(date: &NotionDate) -> UtcOffset {
+    date.time_zone
+        .as_deref()
+        .and_then(timezones::get_by_name)
+        .map(|time_zone| time_zone.get_offset_utc(&date.start.datetime()).to_utc())
+        .unwrap_or(UtcOffset::UTC)
+}
+
+/// When a Notion date starts in its own time zone, dates without a time of day start at midnight
+fn start_datetime(date: &NotionDate) -> OffsetDateTime {
+    let offset = utc_offset(date);
+    match date.start.get_date() {
+        Ok(day) => day.midnight().assume_offset(offset),
+        Err(datetime) => datetime.to_offset(offset),
+    }
+}
+
+/// The day a Notion date starts on in its own time zone, so that an entry written late at night
+/// isn't moved to the next day just because Notion gave its time in UTC
+fn start_date(date: &NotionDate) -> Date {
+    start_datetime(date).date()
+}
+
+/// The day a Notion date range ends on in its own time zone
+fn end_date(date: &NotionDate) -> Option<Date> {
+    let end = date.end.as_ref()?;
+    Some(match end.get_date() {
+        Ok(day) => day,
+        Err(datetime) => datetime.to_offset(utc_offset(date)).date(),
+    })
+}
+
+impl Properties {
+    /// The date shown for a page, which is the date of the entry for entries and the date it was
+    /// published for articles
+    fn display_date(&self) -> Option<Date> {
+        self.date
+            .date
+            .as_ref()
+            .or(self.published.date.as_ref())
+            .map(start_date)
+    }
+
+    /// The date shown for a page along with its time of day if it has one, taken from the property
+    /// picked by `source` and falling back to the other one
+    fn shown_date(&self, source: DisplayDate) -> Option<(Date, Option<OffsetDateTime>)> {
+        let published = || {
+            self.published
+                .date
+                .as_ref()
+                .map(|date| (start_date(date), None))
+        };
+
+        match source {
+            DisplayDate::Date => self.display_date().map(|date| (date, self.display_time())),
+            DisplayDate::Published => {
+                published().or_else(|| self.display_date().map(|date| (date, self.display_time())))
+            }
+        }
+    }
+
+    /// The day the date shown for a page ends on when it's a range, taken from the same property
+    /// as `shown_date`
+    fn shown_end(&self, source: DisplayDate) -> Option<Date> {
+        let date = self.date.date.as_ref().or(self.published.date.as_ref());
+        let published = self.published.date.as_ref();
+
+        match source {
+            DisplayDate::Date => date,
+            DisplayDate::Published => published.or(date),
+        }
+        .and_then(end_date)
+    }
+
+    /// The summary to use for the page in feeds instead of its description
+    fn feed_summary(&self) -> Option<String> {
+        self.feed_summary
+            .as_ref()
+            .map(|feed_summary| feed_summary.rich_text.plain_text())
+            .filter(|feed_summary| feed_summary.trim().is_empty().not())
+    }
+
+    /// Whether the page is left out of the index, archives, feeds and sitemap while still being
+    /// reachable through its URL
+    fn unlisted(&self) -> bool {
+        self.unlisted
+            .as_ref()
+            .map_or(false, |unlisted| unlisted.checkbox)
+    }
+
+    /// Whether the page is still being worked on and so treated like it isn't published yet even
+    /// if it has a published date
+    fn draft(&self) -> bool {
+        self.draft.as_ref().map_or(false, |draft| draft.checkbox)
+    }
+
+    /// Where a cross-posted page was originally published
+    fn canonical(&self) -> Option<reqwest::Url> {
+        self.canonical
+            .as_ref()?
+            .url
+            .as_deref()
+            .filter(|url| url.trim().is_empty().not())
+            .and_then(|url| reqwest::Url::parse(url.trim()).ok())
+    }
+
+    /// The date and time of an entry whose date includes a time of day
+    fn display_time(&self) -> Option<OffsetDateTime> {
+        self.date
+            .date
+            .as_ref()
+            .filter(|date| date.start.get_date().is_err())
+            .map(start_datetime)
+    }
+
+    fn tags(&self) -> Vec<&str> {
+        self.tags
+            .iter()
+            .flat_map(|tags| &tags.multi_select)
+            .map(|tag| tag.name.as_str())
+            .collect()
+    }
+
+    fn series(&self) -> Option<String> {
+        self.series
+            .as_ref()
+            .map(|series| series.rich_text.plain_text())
+            .filter(|series| series.trim().is_empty().not())
+    }
+
+    /// The locale of a page that is written in a different language than the rest of the diary
+    /// Accepts either a locale (i.e fr_FR) or just a language (i.e fr)
+    fn locale(&self) -> Option<LocaleConfig> {
+        let lang = self.lang.as_ref()?.rich_text.plain_text();
+        let lang = lang.trim();
+        if lang.is_empty() {
+            return None;
+        }
+
+        Some(LocaleConfig::parse(lang).unwrap_or_else(|| LocaleConfig {
+            locale: lang.to_string(),
+            lang: lang.to_string(),
+        }))
+    }
+}
+
+const HTML_FORMAT: &[FormatItem<'_>] = format_description!("[year]-[month]-[day]");
+const READABLE_DATE: &[FormatItem<'_>] = format_description!("[month repr:long] [day], [year]");
+const READABLE_TIME: &[FormatItem<'_>] = format_description!("[hour]:[minute]");
+const READABLE_MONTH_DAY: &[FormatItem<'_>] = format_description!("[month repr:long] [day]");
+const READABLE_DAY_YEAR: &[FormatItem<'_>] = format_description!("[day], [year]");
+
+/// Render the readable date of an article, along with its time of day if it has one, marking it
+/// as the article's publication date in microdata when `date_published` is set
+fn render_article_time(
+    date: Date,
+    time: Option<OffsetDateTime>,
+    date_published: bool,
+) -> Result<Markup> {
+    let datetime = match time {
+        Some(time) => time.format(&Rfc3339)?,
+        None => date.format(HTML_FORMAT)?,
+    };
+
+    Ok(html! {
+        p {
+            time datetime=(datetime) itemprop=[date_published.then(|| "datePublished")] {
+                (date.format(READABLE_DATE)?)
+                @if let Some(time) = time {
+                    " at "
+                    (time.format(READABLE_TIME)?)
+                }
+            }
+        }
+    })
+}
+
+/// Render the days an article spans from `date` to `end`, leaving out the parts of the end date it
+/// shares with the start (i.e November 07–09, 2021). Articles that start and end on the same day or
+/// don't have an end are rendered with their time like `render_article_time`
+fn render_article_range(
+    date: Date,
+    time: Option<OffsetDateTime>,
+    end: Option<Date>,
+    date_published: bool,
+) -> Result<Markup> {
+    let end = match end.filter(|&end| end > date) {
+        Some(end) => end,
+        None => return render_article_time(date, time, date_published),
+    };
+
+    let (start_format, separator, end_format) = match (date.year(), date.month()) {
+        (year, month) if year == end.year() && month == end.month() => {
+            (READABLE_MONTH_DAY, "–", READABLE_DAY_YEAR)
+        }
+        (year, _) if year == end.year() => (READABLE_MONTH_DAY, " – ", READABLE_DATE),
+        _ => (READABLE_DATE, " – ", READABLE_DATE),
+    };
 
     Ok(html! {
         p {
-            time datetime=(date.format(HTML_FORMAT)?) {
+            time datetime=(date.format(HTML_FORMAT)?) itemprop=[date_published.then(|| "datePublished")] {
+                (date.format(start_format)?)
+            }
+            (separator)
+            time datetime=(end.format(HTML_FORMAT)?) {
+                (end.format(end_format)?)
+            }
+        }
+    })
+}
+
+/// Render when an article was last updated, marking it as the article's modification date in
+/// microdata
+fn render_updated_time(date: Date) -> Result<Markup> {
+    Ok(html! {
+        p class="updated" {
+            "Updated "
+            time datetime=(date.format(HTML_FORMAT)?) itemprop="dateModified" {
                 (date.format(READABLE_DATE)?)
             }
         }
     })
 }
 
+/// Render a card linking to a page, with the page's summary from its body when it has an
+/// `excerpt`, otherwise its description rendered as rich text when `rich_description` is set or
+/// flattened to plain text
+///
+/// Anchored cards get the page's id as their id so they can be linked to, which is left off of
+/// cards that might end up on the same page as the page itself
+fn render_card(
+    renderer: &HtmlRenderer,
+    href: &str,
+    page: &Page<Properties>,
+    excerpt: Option<&[Block]>,
+    rich_description: bool,
+    display_date: DisplayDate,
+    anchored: bool,
+) -> Result<Markup> {
+    let description = &page.properties.description.rich_text;
+    let id = anchored.then(|| page.id.to_string().replace('-', ""));
+
+    Ok(html! {
+        article id=[id] itemscope itemtype="https://schema.org/BlogPosting" {
+            header {
+                h3 itemprop="headline" {
+                    a href=(href) itemprop="url" {
+                        (renderer.render_rich_text(page.properties.title()))
+                    }
+                }
+                @if let Some((date, time)) = page.properties.shown_date(display_date) {
+                    (render_article_time(date, time, true)?)
+                }
+            }
+            @if let Some(excerpt) = excerpt {
+                div itemprop="description" {
+                    @for block in renderer.render_blocks(excerpt, None, 0) {
+                        (block?)
+                    }
+                }
+            } @else {
+                p itemprop="description" {
+                    @if rich_description {
+                        (renderer.render_rich_text(description))
+                    } @else {
+                        (description.plain_text())
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// Append the text of `blocks` and the blocks nested under them to `content`, one line per block
+fn push_plain_text(blocks: &[Block], content: &mut String) {
+    for block in blocks {
+        let (text, children) = match &block.ty {
+            BlockType::HeadingOne { text }
+            | BlockType::HeadingTwo { text }
+            | BlockType::HeadingThree { text } => (text, None),
+            BlockType::Paragraph { text, children }
+            | BlockType::Quote { text, children }
+            | BlockType::BulletedListItem { text, children }
+            | BlockType::NumberedListItem { text, children } => (text, Some(children)),
+            _ => continue,
+        };
+
+        if content.is_empty().not() {
+            content.push('\n');
+        }
+        content.push_str(&text.plain_text());
+        if let Some(children) = children {
+            push_plain_text(children, content);
+        }
+    }
+}
+
+/// How many words are in the text of `blocks` and the blocks nested under them
+fn count_words(blocks: &[Block]) -> usize {
+    let mut content = String::new();
+    push_plain_text(blocks, &mut content);
+    content.split_whitespace().count()
+}
+
+/// Render how many minutes a page takes to read as configured by `reading_time`, rounded up so
+/// that even pages without any text take a minute
+fn render_reading_time(blocks: &[Block], reading_time: &ReadingTimeConfig) -> Markup {
+    let wpm = reading_time.wpm.get();
+    let minutes = ((count_words(blocks) + wpm - 1) / wpm).max(1);
+    let text = reading_time
+        .template
+        .replace("{minutes}", &minutes.to_string());
+
+    html! {
+        p class="reading-time" { (text) }
+    }
+}
+
+/// Where the summary of a page ends in its body according to `separator`, the marker paragraph
+/// itself is removed from the body so that it never gets rendered
+fn split_summary(children: &mut Vec<Block>, separator: &SummarySeparator) -> Option<usize> {
+    let position = children.iter().position(|block| match &block.ty {
+        BlockType::Divider => separator.divider,
+        BlockType::Paragraph { text, .. } => separator
+            .marker
+            .as_deref()
+            .map_or(false, |marker| text.plain_text().trim() == marker),
+        _ => false,
+    })?;
+
+    if let BlockType::Paragraph { .. } = children[position].ty {
+        children.remove(position);
+    }
+
+    Some(position)
+}
+
+/// Render the link to the KaTeX stylesheet, or the stylesheet itself when it's inlined and was
+/// downloaded
+fn render_katex_link(katex_link: KatexLink, styles: Option<&str>) -> Markup {
+    const HREF: &str = "/katex/katex.min.css";
+
+    match (katex_link, styles) {
+        (KatexLink::Inline, Some(styles)) => html! {
+            style { (PreEscaped(styles)) }
+        },
+        (KatexLink::Blocking | KatexLink::Inline, _) => html! {
+            link rel="stylesheet" href=(HREF);
+        },
+        (KatexLink::Async, _) => html! {
+            link rel="stylesheet" href=(HREF) media="print" onload="this.media='all'";
+            noscript {
+                link rel="stylesheet" href=(HREF);
+            }
+        },
+    }
+}
+
+/// Render the navigation menu of the config, marking the link to the page at `path` as current
+fn render_nav(nav: &[NavLink], path: &str) -> Markup {
+    if nav.is_empty() {
+        return PreEscaped(String::new());
+    }
+
+    let current = path.trim_matches('/');
+    html! {
+        nav {
+            ul {
+                @for link in nav {
+                    li {
+                        a href=(link.href)
+                            aria-current=[(link.href.trim_matches('/') == current).then(|| "page")] {
+                            (link.label)
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Render links to the page at `path` in every alternate version of the diary, marking the one in
+/// the diary's own language as current
+fn render_lang_switcher(config: &Config, path: &str) -> Result<Markup> {
+    if config.alternates.is_empty() {
+        return Ok(PreEscaped(String::new()));
+    }
+
+    let path = path.trim_start_matches('/');
+    Ok(html! {
+        nav class="lang-switcher" {
+            ul {
+                @for alternate in &config.alternates {
+                    @let current = alternate.lang == config.locale.lang;
+                    li {
+                        a href=(alternate.url.join(path)?) hreflang=(alternate.lang) lang=(alternate.lang)
+                            class=[current.then(|| "active")] aria-current=[current.then(|| "true")] {
+                            (alternate.label)
+                        }
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// What differs between the `<head>` of one kind of page and another
+struct Head<'a> {
+    title: &'a str,
+    description: Option<&'a str>,
+    /// The path of the page relative to the root of the diary, used for its `og:url`
+    path: &'a str,
+    locale: &'a LocaleConfig,
+    robots: Option<&'a str>,
+    image: Option<&'a str>,
+    canonical: Option<reqwest::Url>,
+    /// Whether the page is about the diary as a whole, like its index, rather than a part of it
+    website: bool,
+    /// Whether the page may contain math that needs KaTeX's styles
+    katex: bool,
+    icon: Option<&'a str>,
+    /// Anything else the page needs, rendered right before the head partial
+    extra: Markup,
+}
+
+impl<'a> Head<'a> {
+    fn new(title: &'a str, path: &'a str, locale: &'a LocaleConfig) -> Self {
+        Head {
+            title,
+            description: None,
+            path,
+            locale,
+            robots: None,
+            image: None,
+            canonical: None,
+            website: false,
+            katex: true,
+            icon: None,
+            extra: PreEscaped(String::new()),
+        }
+    }
+}
+
+/// Render the `<head>` shared by every page of the diary, with `partial` being the user's head
+/// partial
+fn render_head(
+    config: &Config,
+    katex_styles: Option<&str>,
+    partial: &Markup,
+    head: &Head,
+) -> Result<Markup> {
+    Ok(html! {
+        head {
+            meta charset="utf-8";
+            meta name="viewport" content=(config.viewport());
+            @if let Some(description) = head.description {
+                meta name="description" content=(description);
+            }
+            @if let Some(robots) = head.robots {
+                meta name="robots" content=(robots);
+            }
+            @if head.katex {
+                (render_katex_link(config.katex_link, katex_styles))
+            }
+            title { (head.title) }
+            @if let Some(author) = &config.author {
+                meta name="author" content=(author.name);
+                @if let Some(url) = &author.url {
+                    link rel="author" href=(url);
+                }
+            }
+            @if config.get_atom_id().is_some() {
+                link rel="alternate" type="application/atom+xml" href="/feed.xml";
+                link rel="alternate" type="application/feed+json" href="/feed.json";
+            }
+
+            meta property="og:title" content=(head.title);
+            @if let Some(description) = head.description {
+                meta property="og:description" content=(description);
+            }
+            meta property="og:locale" content=(head.locale.locale);
+            @if let Some(image) = head.image {
+                meta property="og:image" content=(image);
+                meta name="twitter:card" content="summary_large_image";
+            }
+            @if let Some(url) = &config.url {
+                meta property="og:url" content=(url.join(head.path)?);
+            }
+            @if let Some(canonical) = &head.canonical {
+                link rel="canonical" href=(canonical);
+            }
+            @if let Some(twitter_site) = &config.twitter.site {
+                meta name="twitter:site" content=(twitter_site);
+            }
+            @if let Some(twitter_creator) = &config.twitter.creator {
+                meta name="twitter:creator" content=(twitter_creator);
+            }
+            @if head.website {
+                meta property="og:type" content="website";
+                meta property="og:site_name" content=(config.name);
+            }
+            (head.extra)
+
+            (partial)
+            // After the head partial so that it wins over the diary's favicon
+            @if let Some(icon) = head.icon {
+                link rel="icon" href=(icon);
+            }
+        }
+    })
+}
+
+fn render_related(
+    renderer: &HtmlRenderer,
+    link_map: &HashMap<NotionId, String>,
+    related: &[&Page<Properties>],
+    rich_descriptions: bool,
+    display_date: DisplayDate,
+) -> Result<Markup> {
+    if related.is_empty() {
+        return Ok(PreEscaped(String::new()));
+    }
+
+    Ok(html! {
+        section class="related" {
+            h2 { "Related" }
+            @for page in related {
+                (render_card(renderer, &link_map[&page.id], page, None, rich_descriptions, display_date, false)?)
+            }
+        }
+    })
+}
+
+fn render_series_links(
+    renderer: &HtmlRenderer,
+    series: &str,
+    position: usize,
+    members: &[(&Date, &Page<Properties>)],
+) -> Markup {
+    let prev_member = position
+        .checked_sub(1)
+        .and_then(|position| members.get(position));
+    let next_member = members.get(position + 1);
+
+    html! {
+        nav class="series-links" {
+            p {
+                "Part " (position + 1) " of "
+                a href=(format!("/{}", format_series(series))) { (series) }
+            }
+            @if let Some((&prev_date, prev_page)) = prev_member {
+                a href=(format_day(prev_date, true)) rel="prev" {
+                    "Previous: " (renderer.render_rich_text(prev_page.properties.title()))
+                }
+            }
+            @if let Some((&next_date, next_page)) = next_member {
+                a href=(format_day(next_date, true)) rel="next" {
+                    "Next: " (renderer.render_rich_text(next_page.properties.title()))
+                }
+            }
+        }
+    }
+}
+
 fn render_paging_links(
     renderer: &HtmlRenderer,
-    current_date: Date,
-    prev_page: Option<(&Date, &Page<Properties>)>,
-    next_page: Option<(&Date, &Page<Properties>)>,
+    wording: &PagingConfig,
+    display_date: DisplayDate,
+    current_date: Option<Date>,
+    prev_page: Option<&PagingLink>,
+    next_page: Option<&PagingLink>,
 ) -> Result<Markup> {
     if next_page.is_none() && prev_page.is_none() {
         return Ok(PreEscaped(String::new()));
     }
 
+    // How many days apart an entry is from the current one, which only makes sense between two
+    // entries
+    let gap = |link: &PagingLink| match (link.entry_date, current_date) {
+        (Some(date), Some(current)) => Some((date - current).whole_days().abs()),
+        _ => None,
+    };
+    let label = |link: &PagingLink, adjacent: &str, near: &str, far: &str| match gap(link) {
+        Some(1) => adjacent.to_string(),
+        Some(gap) if gap <= wording.max_gap_days => near.replace("{}", &gap.to_string()),
+        _ => far.to_string(),
+    };
+
     Ok(html! {
         nav class="paging-links" {
-            @if let Some((&prev_date, prev_page)) = prev_page {
-                a href=(format_day(prev_date, true)) {
+            @if let Some(prev_page) = prev_page {
+                a href=(prev_page.href) {
                     article {
                         p {
-                            @if prev_date.next_day() == Some(current_date) {
-                                "Yesterday:"
-                            } @else {
-                                "Previously:"
-                            }
+                            (label(prev_page, &wording.yesterday, &wording.days_ago, &wording.previously))
                         }
                         header {
-                            h3 { (renderer.render_rich_text(&prev_page.properties.name.title)) }
-                            (render_article_time(prev_date)?)
+                            h3 { (renderer.render_rich_text(&prev_page.page.properties.name.title)) }
+                            @if let Some((date, time)) = prev_page.page.properties.shown_date(display_date) {
+                                (render_article_time(date, time, false)?)
+                            }
                         }
                     }
                 }
             }
 
-            @if let Some((&next_date, next_page)) = next_page {
-                a href=(format_day(next_date, true)) {
+            @if let Some(next_page) = next_page {
+                a href=(next_page.href) {
                     article {
                         p {
-                            @if next_date.previous_day() == Some(current_date) {
-                                "Tomorrow:"
-                            } @else {
-                                "Next up:"
-                            }
+                            (label(next_page, &wording.tomorrow, &wording.in_days, &wording.next_up))
                         }
                         header {
-                            h3 { (renderer.render_rich_text(&next_page.properties.name.title)) }
-                            (render_article_time(next_date)?)
+                            h3 { (renderer.render_rich_text(&next_page.page.properties.name.title)) }
+                            @if let Some((date, time)) = next_page.page.properties.shown_date(display_date) {
+                                (render_article_time(date, time, false)?)
+                            }
                         }
                     }
                 }
@@ -121,79 +761,756 @@ fn render_paging_links(
     })
 }
 
-#[inline]
-fn format_year(year: i32) -> String {
-    format!("{:0>4}", year)
+/// A page the paging links of its neighbours point to
+struct PagingLink<'a> {
+    href: &'a str,
+    /// The date of the page if it's an entry, articles don't have one
+    entry_date: Option<Date>,
+    page: &'a Page<Properties>,
 }
 
-#[inline]
-fn format_month(year: i32, month: Month) -> String {
-    format!("{:0>4}/{:0>2}", year, u8::from(month))
+/// What the entries of an Atom feed are ordered by
+#[derive(Clone, Copy)]
+enum FeedOrder {
+    /// Oldest publication first
+    Published,
+    /// Most recently edited first
+    Updated,
 }
 
-#[inline]
-fn format_day(date: Date, is_link: bool) -> String {
-    format!(
-        "{}{:0>4}/{:0>2}/{:0>2}",
-        if is_link { "/" } else { "" },
-        date.year(),
-        u8::from(date.month()),
-        date.day()
-    )
+/// What the Atom feed and JSON Feed share about one of their entries
+struct FeedItem {
+    url: reqwest::Url,
+    summary: atom::Summary,
+    /// The summary without any markup since JSON Feed summaries are always plain text
+    summary_text: String,
+    /// The absolute URL of the page's cover
+    image: Option<String>,
+    content: Markup,
 }
 
-async fn write<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> Result<()> {
-    let path = path.as_ref();
-    info!(msg = "Writing file", path = %path.display());
-    if let Some(parent) = path.parent() {
-        tokio::fs::create_dir_all(parent)
-            .await
-            .with_context(|| format!("Failed to create parent directory {}", path.display()))?;
+/// The pages before and after a page in the paging chain
+fn neighbours<'a, 'b>(
+    chain: &'a [PagingLink<'b>],
+    page: &Page<Properties>,
+) -> (Option<&'a PagingLink<'b>>, Option<&'a PagingLink<'b>>) {
+    match chain.iter().position(|link| link.page.id == page.id) {
+        Some(position) => (
+            position.checked_sub(1).and_then(|prev| chain.get(prev)),
+            chain.get(position + 1),
+        ),
+        None => (None, None),
     }
-    tokio::fs::write(path, contents.as_ref())
-        .await
-        .with_context(|| format!("Failed to write {} file", path.display()))?;
-    Ok(())
 }
 
-async fn read_partial_file<P: AsRef<Path>>(file: P) -> Result<String> {
-    tokio::fs::read_to_string(file.as_ref())
-        .await
-        .or_else(|error| match error.kind() {
-            io::ErrorKind::NotFound => Ok(String::new()),
-            _ => Err(error),
-        })
-        .with_context(|| format!("Failed to read partial file {}", file.as_ref().display()))
+/// A cover, icon or share image registered by the diary itself, only handed over to be downloaded
+/// once its size was checked
+struct Media {
+    /// The URL the media is downloaded from, which for files uploaded to Notion is signed
+    url: String,
+    downloadable: Downloadable,
 }
 
-pub struct Generator {
-    link_map: HashMap<NotionId, String>,
-    lookup_tree: BTreeMap<Date, Page<Properties>>,
-    article_pages: Vec<(String, Page<Properties>)>,
-    downloadables: Downloadables,
-    head: Markup,
-    header: Markup,
-    footer: Markup,
-    config: Config,
-    directory: PathBuf,
-}
+/// Ask for the size of every cover and icon before downloading them so that a single huge file
+/// can't fill up the disk, returning the sources of the ones over `max_bytes`
+/// Media that doesn't report its size is let through, and images inside of pages' content are
+/// downloaded by notion-generator and so aren't checked
+async fn oversized_media(
+    client: &Client,
+    media: &HashMap<String, Media>,
+    max_bytes: u64,
+    strict: bool,
+) -> Result<HashSet<String>> {
+    let oversized = media
+        .iter()
+        .map(|(source, media)| async move {
+            let length = client
+                .head(&media.url)
+                .send()
+                .await
+                .ok()
+                .filter(|response| response.status().is_success())
+                // Responses to HEAD requests have no body, so the size is read from the header as
+                // is rather than through `content_length` which reports the body's size
+                .and_then(|response| {
+                    response
+                        .headers()
+                        .get(header::CONTENT_LENGTH)?
+                        .to_str()
+                        .ok()?
+                        .parse::<u64>()
+                        .ok()
+                });
+            (source, length)
+        })
+        .collect::<FuturesUnordered<_>>()
+        .filter_map(|(source, length)| async move {
+            length
+                .filter(|&length| length > max_bytes)
+                .map(|length| (source, length))
+        })
+        .collect::<Vec<_>>()
+        .await;
 
-impl Generator {
-    pub async fn new<P: AsRef<Path>>(dir: P, pages: Vec<Page<Properties>>) -> Result<Generator> {
-        let dir = dir.as_ref();
+    oversized
+        .into_iter()
+        .map(|(source, length)| {
+            if strict {
+                bail!(
+                    "Media {} is {} bytes which is over max_download_bytes of {}",
+                    source,
+                    length,
+                    max_bytes
+                );
+            }
+
+            warn!(
+                msg = "Skipping media over max_download_bytes",
+                source = %source,
+                bytes = length,
+                max_bytes
+            );
+            Ok(source.clone())
+        })
+        .collect()
+}
+
+/// The URL a file is downloaded from and the source it's known by, which is the same URL except
+/// for files uploaded to Notion since they're served through signed URLs whose query changes
+fn media_source(file: &File) -> (&str, &str) {
+    match file {
+        File::File { file, .. } => (&file.url, file.url.split('?').next().unwrap_or(&file.url)),
+        File::External { external, .. } => (&external.url, &external.url),
+    }
+}
+
+/// Register a file to be downloaded under `id` followed by `suffix` and return the path it will be
+/// served from, unless the same file was already registered in which case that file's path is
+/// returned instead
+fn register_media(
+    media: &mut HashMap<String, Media>,
+    file: &File,
+    id: NotionId,
+    suffix: &str,
+) -> Result<String> {
+    let (url, source) = media_source(file);
+
+    if let Some(media) = media.get(source) {
+        return Ok(media.downloadable.src_path());
+    }
+
+    let mut downloadable = file.as_downloadable(id)?;
+    if !suffix.is_empty() {
+        // Files are named after the id they're downloaded under followed by their extension
+        let src = downloadable.src_path();
+        let (directory, name) = src.rsplit_once('/').unwrap_or(("", &src));
+        let (stem, extension) = name.find('.').map_or((name, ""), |dot| name.split_at(dot));
+        let path = format!("{}/{}{}{}", directory, stem, suffix, extension);
+        downloadable =
+            Downloadable::new(url.to_string(), PathBuf::from(path.trim_start_matches('/')));
+    }
+    let src = downloadable.src_path();
+    media.insert(
+        source.to_string(),
+        Media {
+            url: url.to_string(),
+            downloadable,
+        },
+    );
+
+    Ok(src)
+}
+
+/// Register the audio and video uploaded to Notion in `blocks` and the blocks nested under them to
+/// be downloaded, pointing the blocks at where they will be served from instead since Notion's
+/// URLs expire
+/// Audio and video hosted anywhere else are left as they are
+fn download_embeds(blocks: &mut [Block], media: &mut HashMap<String, Media>) -> Result<()> {
+    for block in blocks {
+        match &mut block.ty {
+            BlockType::Audio { audio: file } | BlockType::Video { video: file } => {
+                if let File::File { .. } = file {
+                    let src = register_media(media, file, block.id, "")?;
+                    if let File::File { file, .. } = file {
+                        file.url = src;
+                    }
+                }
+            }
+            BlockType::Paragraph { children, .. }
+            | BlockType::Quote { children, .. }
+            | BlockType::BulletedListItem { children, .. }
+            | BlockType::NumberedListItem { children, .. } => download_embeds(children, media)?,
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Percent-encode everything in `text` that isn't allowed as is in a URL, like the UTF-8 bytes of
+/// emojis
+fn percent_encode(text: &str) -> String {
+    text.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' => (byte as char).to_string(),
+            b'-' | b'.' | b'_' | b'~' | b'!' | b'$' | b'&' | b'\'' | b'(' | b')' | b'*' | b'+'
+            | b',' | b';' | b'=' | b':' | b'@' | b'/' | b'?' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// What a file downloaded for a page is used as, each kind is downloaded under its own name so
+/// that a page's cover, icon and share image never overwrite each other
+#[derive(Clone, Copy)]
+enum MediaKind {
+    Cover,
+    Icon,
+    OgImage,
+}
+
+impl MediaKind {
+    /// What's added to the page's id to name media of this kind, covers are named after the id of
+    /// their page as is
+    fn suffix(self) -> &'static str {
+        match self {
+            MediaKind::Cover => "",
+            MediaKind::Icon => "-icon",
+            MediaKind::OgImage => "-og-image",
+        }
+    }
+}
+
+/// Where a page is kept in the generator, entries by their date and articles by their position
+#[derive(Clone, Copy)]
+enum PageKey {
+    Entry(Date),
+    Article(usize),
+}
+
+/// The kinds of files in pages/ that get wrapped in the layout
+enum IndependentPage {
+    Html,
+    Markdown,
+}
+
+impl IndependentPage {
+    /// The name of the page a file in pages/ becomes and its kind, or nothing if the file is copied
+    /// over as is
+    fn from_file_name(file_name: &str) -> Option<(&str, IndependentPage)> {
+        if let Some(name) = file_name.strip_suffix(".html") {
+            Some((name, IndependentPage::Html))
+        } else {
+            file_name
+                .strip_suffix(".md")
+                .map(|name| (name, IndependentPage::Markdown))
+        }
+    }
+
+    /// Independent pages are titled after their name with the first letter uppercase, pages in
+    /// subdirectories only use the last part of their name
+    fn title(name: &str) -> String {
+        let mut title = name.rsplit('/').next().unwrap_or(name).to_string();
+        if let Some(first_char) = title.get_mut(0..1) {
+            first_char.make_ascii_uppercase();
+        }
+        title
+    }
+}
+
+/// Every file inside of the pages/ directory and its subdirectories, along with its path relative
+/// to pages/ joined with `/`
+async fn independent_files(directory: &Path) -> Result<Vec<(PathBuf, String)>> {
+    let mut files = Vec::new();
+    let mut directories = vec![(directory.to_path_buf(), String::new())];
+
+    while let Some((directory, prefix)) = directories.pop() {
+        let mut entries = ReadDirStream::new(tokio::fs::read_dir(&directory).await?);
+        while let Some(entry) = entries
+            .try_next()
+            .await
+            .context("Failed to read file while recursively generating independent pages")?
+        {
+            let path = entry.path();
+            let relative_path = match entry.file_name().to_str() {
+                Some(file_name) => format!("{}{}", prefix, file_name),
+                None => bail!("Not a valid file name {}", path.display()),
+            };
+
+            let file_type = entry.file_type().await?;
+            if file_type.is_dir() {
+                directories.push((path, relative_path + "/"));
+            } else if file_type.is_file() {
+                files.push((path, relative_path));
+            } else {
+                bail!(
+                    "pages/ must only contain files. {} is not a file.",
+                    path.display(),
+                );
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// The optional front matter at the top of an independent page, between two `---` lines
+#[derive(Default)]
+struct FrontMatter {
+    title: Option<String>,
+    description: Option<String>,
+    cover: Option<String>,
+}
+
+impl FrontMatter {
+    /// Split the front matter of a page from the rest of its content, pages without any are left
+    /// as they are
+    /// Only flat `key: value` lines are supported, values can optionally be wrapped in quotes
+    /// Anything else between the two `---` lines means they were Markdown all along (i.e two
+    /// horizontal rules or a heading underline) and so the page is left as it is
+    fn parse(content: &str) -> (FrontMatter, &str) {
+        let rest = match content
+            .strip_prefix("---\n")
+            .or_else(|| content.strip_prefix("---\r\n"))
+        {
+            Some(rest) => rest,
+            None => return (FrontMatter::default(), content),
+        };
+
+        let mut start = 0;
+        let mut closing = None;
+        for line in rest.split_inclusive('\n') {
+            if line.trim() == "---" {
+                closing = Some((start, start + line.len()));
+                break;
+            }
+            start += line.len();
+        }
+        let (lines, rest) = match closing {
+            Some((start, end)) => (&rest[..start], &rest[end..]),
+            // Without a closing --- the first line was a Markdown horizontal rule all along
+            None => return (FrontMatter::default(), content),
+        };
+
+        let mut pairs = Vec::new();
+        for line in lines.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            match line.split_once(':') {
+                Some((key, value))
+                    if !key.is_empty()
+                        && key
+                            .chars()
+                            .all(|char| char.is_ascii_alphanumeric() || char == '_') =>
+                {
+                    pairs.push((key, value.trim()))
+                }
+                _ => return (FrontMatter::default(), content),
+            }
+        }
+        if pairs.is_empty() {
+            return (FrontMatter::default(), content);
+        }
+
+        let mut front_matter = FrontMatter::default();
+        for (key, value) in pairs {
+            let value = ['"', '\'']
+                .iter()
+                .find_map(|&quote| value.strip_prefix(quote)?.strip_suffix(quote))
+                .unwrap_or(value)
+                .to_string();
+            match key {
+                "title" => front_matter.title = Some(value),
+                "description" => front_matter.description = Some(value),
+                "cover" => front_matter.cover = Some(value),
+                _ => warn!(msg = "Ignoring unknown front matter key", key),
+            }
+        }
+
+        (front_matter, rest)
+    }
+}
+
+/// What identifies a publication in the Atom feed, the URL of articles and the date of entries
+enum UrlOrDate {
+    Url(String),
+    Date(Date),
+}
+
+#[inline]
+fn format_year(year: i32) -> String {
+    format!("{:0>4}", year)
+}
+
+/// A page of the archives covering a whole year, or every year before `archive_before` when the
+/// config collapses them into a single page
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum ArchiveYear {
+    Year(i32),
+    Before(i32),
+}
+
+impl ArchiveYear {
+    fn path(self) -> String {
+        match self {
+            ArchiveYear::Year(year) => format_year(year),
+            ArchiveYear::Before(year) => format!("before-{}", format_year(year)),
+        }
+    }
+
+    /// The entries that belong on the page
+    fn range(self) -> (Bound<Date>, Bound<Date>) {
+        let january_first = |year| Date::from_calendar_date(year, Month::January, 1).unwrap();
+
+        match self {
+            ArchiveYear::Year(year) => (
+                Bound::Included(january_first(year)),
+                Bound::Excluded(january_first(year + 1)),
+            ),
+            ArchiveYear::Before(year) => (Bound::Unbounded, Bound::Excluded(january_first(year))),
+        }
+    }
+
+    /// The last year whose entries are on the page
+    fn last_year(self) -> i32 {
+        match self {
+            ArchiveYear::Year(year) => year,
+            ArchiveYear::Before(year) => year - 1,
+        }
+    }
+
+    /// How the year reads in the middle of a sentence, i.e "from before 2000"
+    fn lowercase(self) -> String {
+        match self {
+            ArchiveYear::Year(year) => year.to_string(),
+            ArchiveYear::Before(year) => format!("before {}", year),
+        }
+    }
+}
+
+impl fmt::Display for ArchiveYear {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArchiveYear::Year(year) => write!(f, "{}", year),
+            ArchiveYear::Before(year) => write!(f, "Before {}", year),
+        }
+    }
+}
+
+#[inline]
+fn format_month(year: i32, month: Month) -> String {
+    format!("{:0>4}/{:0>2}", year, u8::from(month))
+}
+
+#[inline]
+fn format_day(date: Date, is_link: bool) -> String {
+    format!(
+        "{}{:0>4}/{:0>2}/{:0>2}",
+        if is_link { "/" } else { "" },
+        date.year(),
+        u8::from(date.month()),
+        date.day()
+    )
+}
+
+/// A count followed by the noun it counts, i.e "1 entry" or "3 entries"
+fn counted(count: usize, singular: &str, plural: &str) -> String {
+    match count {
+        1 => format!("1 {}", singular),
+        count => format!("{} {}", count, plural),
+    }
+}
+
+/// Shortens descriptions longer than `max` characters to fit within it with an ellipsis, cutting at
+/// the last word boundary so that search engines don't truncate them mid-word themselves
+fn truncate_description(description: &str, max: usize) -> String {
+    if description.chars().count() <= max {
+        return description.to_string();
+    }
+
+    let truncated = description
+        .char_indices()
+        .nth(max.saturating_sub(1))
+        .map_or(description, |(index, _)| &description[..index]);
+    let truncated = match truncated.rfind(char::is_whitespace) {
+        Some(index) => &truncated[..index],
+        None => truncated,
+    };
+
+    format!(
+        "{}…",
+        truncated.trim_end_matches(|c: char| c.is_whitespace() || c.is_ascii_punctuation())
+    )
+}
+
+/// A hash of the text that stays the same across builds and Rust versions unlike `DefaultHasher`
+/// (64-bit FNV-1a)
+fn stable_hash(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Lowercases the text and replaces every run of non-alphanumeric characters with a hyphen, text
+/// without any alphanumeric characters (i.e only emoji) is identified by its hash instead
+fn slugify(text: &str) -> String {
+    let slug = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(str::to_lowercase)
+        .join("-");
+
+    if slug.is_empty() {
+        format!("{:016x}", stable_hash(text))
+    } else {
+        slug
+    }
+}
+
+/// Bail if two different names would have their pages at the same path, since one would overwrite
+/// the other (i.e the tags "C++" and "C" both being at tags/c)
+fn check_path_collisions<I>(kind: &str, names: I, format: fn(&str) -> String) -> Result<()>
+where
+    I: Iterator<Item = String>,
+{
+    let mut paths = HashMap::new();
+    for name in names {
+        let path = format(&name);
+        match paths.get(&path) {
+            Some(existing) if *existing != name => bail!(
+                "The {} {:?} and {:?} would both be at /{}, rename one of them",
+                kind,
+                existing,
+                name,
+                path
+            ),
+            Some(_) => {}
+            None => {
+                paths.insert(path, name);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[inline]
+fn format_series(series: &str) -> String {
+    format!("series/{}", slugify(series))
+}
+
+#[inline]
+fn format_tag(tag: &str) -> String {
+    format!("tags/{}", slugify(tag))
+}
+
+/// Buckets how many pages carry a tag into a size class between 1 and `TAG_WEIGHTS`
+/// Counts are spread relative to the least used tag so that tags with similar counts all end up
+/// in the same class instead of being stretched across all of them
+fn tag_weight(count: usize, min: usize, max: usize) -> usize {
+    let spread = (max - min).max(min).max(1);
+    1 + (count - min) * (TAG_WEIGHTS - 1) / spread
+}
+
+async fn write<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> Result<()> {
+    let path = path.as_ref();
+    info!(msg = "Writing file", path = %path.display());
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .with_context(|| format!("Failed to create parent directory {}", path.display()))?;
+    }
+
+    // Writing into a temporary file next to the real one and renaming it over the real one means
+    // readers see either the old or the new file but never a partially written one
+    // Every write gets its own temporary file since writes run concurrently and can target the
+    // same file
+    static WRITES: AtomicU64 = AtomicU64::new(0);
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(format!(
+        ".{}-{}.tmp",
+        std::process::id(),
+        WRITES.fetch_add(1, Ordering::Relaxed)
+    ));
+    let temporary = PathBuf::from(temporary);
+
+    let result = async {
+        tokio::fs::write(&temporary, contents.as_ref()).await?;
+        tokio::fs::rename(&temporary, path).await
+    }
+    .await;
+    if result.is_err() {
+        let _ = tokio::fs::remove_file(&temporary).await;
+    }
+
+    result.with_context(|| format!("Failed to write {} file", path.display()))
+}
+
+/// Tally of everything a dry run would have written and downloaded
+#[derive(Default)]
+pub struct DryRun {
+    files: AtomicUsize,
+    bytes: AtomicU64,
+    media: AtomicUsize,
+}
+
+impl DryRun {
+    /// Log a file that would have been written instead of writing it
+    pub fn record(&self, path: &Path, bytes: u64) {
+        info!(msg = "Would write file", path = %path.display(), bytes);
+        self.files.fetch_add(1, Ordering::Relaxed);
+        self.bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    /// How many files would have been written
+    pub fn files(&self) -> usize {
+        self.files.load(Ordering::Relaxed)
+    }
+
+    /// How many bytes the written files would have added up to
+    pub fn bytes(&self) -> u64 {
+        self.bytes.load(Ordering::Relaxed)
+    }
+
+    /// How many covers, icons and images would have been downloaded
+    pub fn media(&self) -> usize {
+        self.media.load(Ordering::Relaxed)
+    }
+}
+
+/// Writes the generated files into the output directory, or only records them in a dry run
+#[derive(Clone)]
+struct Writer {
+    dry_run: Option<Arc<DryRun>>,
+}
+
+impl Writer {
+    fn write<P, C>(&self, path: P, contents: C) -> impl Future<Output = Result<()>>
+    where
+        P: AsRef<Path>,
+        C: AsRef<[u8]>,
+    {
+        let dry_run = self.dry_run.clone();
+
+        async move {
+            match dry_run {
+                Some(dry_run) => {
+                    dry_run.record(path.as_ref(), contents.as_ref().len() as u64);
+                    Ok(())
+                }
+                None => write(path, contents).await,
+            }
+        }
+    }
+
+    fn copy(&self, source: PathBuf, destination: PathBuf) -> impl Future<Output = Result<()>> {
+        let dry_run = self.dry_run.clone();
+
+        async move {
+            match dry_run {
+                Some(dry_run) => {
+                    let metadata = tokio::fs::metadata(&source)
+                        .await
+                        .with_context(|| format!("Failed to read {}", source.display()))?;
+                    dry_run.record(&destination, metadata.len());
+                }
+                None => {
+                    if let Some(parent) = destination.parent() {
+                        tokio::fs::create_dir_all(parent).await?;
+                    }
+                    tokio::fs::copy(&source, &destination)
+                        .await
+                        .with_context(|| format!("Failed to copy {}", source.display()))?;
+                }
+            }
+
+            Ok(())
+        }
+    }
+}
+
+async fn read_partial_file<P: AsRef<Path>>(file: P) -> Result<String> {
+    tokio::fs::read_to_string(file.as_ref())
+        .await
+        .or_else(|error| match error.kind() {
+            io::ErrorKind::NotFound => Ok(String::new()),
+            _ => Err(error),
+        })
+        .with_context(|| format!("Failed to read partial file {}", file.as_ref().display()))
+}
+
+pub struct Generator {
+    link_map: HashMap<NotionId, String>,
+    lookup_tree: BTreeMap<Date, Page<Properties>>,
+    article_pages: Vec<(String, Page<Properties>)>,
+    drafts: Vec<(String, Page<Properties>)>,
+    downloadables: Downloadables,
+    /// The media registered by the diary itself by its source, so that media shared between pages
+    /// is only downloaded once
+    media: Mutex<HashMap<String, Media>>,
+    /// The sources of the covers `download_covers` already took care of, including the ones it
+    /// skipped for being too big, which `download_all` leaves alone
+    downloaded_covers: HashSet<String>,
+    /// The width and height of every cover downloaded by `download_covers` by their source
+    cover_sizes: HashMap<String, (u32, u32)>,
+    /// How many blocks at the start of a page's body make up its summary, only present for pages
+    /// with a summary separator
+    excerpts: HashMap<NotionId, usize>,
+    /// The rendered content of the entries and articles that `--keep-going` already rendered to
+    /// check which pages fail
+    rendered: HashMap<NotionId, Vec<Markup>>,
+    /// Where every listed entry and article is kept by its id
+    listed_pages: HashMap<NotionId, PageKey>,
+    /// The ids of the listed entries and articles carrying every tag, most recent first
+    tag_index: BTreeMap<String, Vec<NotionId>>,
+    /// The ids of the listed pages sharing the most tags with every page that shares any
+    related_index: HashMap<NotionId, Vec<NotionId>>,
+    writer: Writer,
+    /// KaTeX's stylesheet when it's inlined into pages instead of linked to
+    katex_styles: Option<String>,
+    strict: bool,
+    /// Whether KaTeX and media are left undownloaded since there's no network access
+    offline: bool,
+    keep_going: bool,
+    /// How many pages were skipped because they failed under `--keep-going`
+    failures: AtomicUsize,
+    today: Date,
+    head: Markup,
+    header: Markup,
+    footer: Markup,
+    config: Config,
+    directory: PathBuf,
+    output: PathBuf,
+}
+
+impl Generator {
+    pub async fn new<P: AsRef<Path>>(dir: P, pages: Vec<Page<Properties>>) -> Result<Generator> {
+        Self::with_options(dir, pages, Options::default()).await
+    }
+
+    pub async fn with_options<P: AsRef<Path>>(
+        dir: P,
+        pages: Vec<Page<Properties>>,
+        options: Options,
+    ) -> Result<Generator> {
+        let dir = dir.as_ref();
         let length = pages.len();
 
-        let today = time::OffsetDateTime::now_utc().date();
+        let today = options
+            .today
+            .unwrap_or_else(|| time::OffsetDateTime::now_utc().date());
 
-        let (link_map, lookup_tree, article_pages) = pages
+        // Pages that can't be placed in the diary, only skipped under `--keep-going`
+        let mut skipped = 0;
+        let (mut link_map, mut lookup_tree, mut article_pages, mut drafts) = pages
             .into_iter()
-            .filter(|page| {
-                page.properties
-                    .published
-                    .date
-                    .as_ref()
-                    .map(|date| date.start <= today)
-                    .unwrap_or(false)
+            .filter(|page| match page.properties.published.date.as_ref() {
+                _ if page.properties.draft() => options.drafts,
+                Some(date) => start_date(date) <= today,
+                None => options.drafts,
             })
             .map(|page| {
                 let date = page
@@ -201,19 +1518,15 @@ impl Generator {
                     .date
                     .date
                     .as_ref()
-                    .map(|date| date.start.get_date());
+                    .map(start_date);
                 let url = page.properties.url.rich_text.plain_text();
                 let url = Some(url).filter(|url| url.is_empty().not());
 
+                // Entries dated with a time are still routed by the day they are on
                 let (path, identifier) = match (date, url) {
-                    (Some(Err(datetime)), _) => bail!(
-                        "Diary dates must not contain time but page {} has datetime {}",
-                        page.id,
-                        datetime
-                    ),
-                    (Some(Ok(date)), Some(url)) => bail!("Diary currently doesn't support rendering a page with both a date and a URL but page {} has date {} and URL {}", page.id, date, url),
+                    (Some(date), Some(url)) => bail!("Diary currently doesn't support rendering a page with both a date and a URL but page {} has date {} and URL {}", page.id, date, url),
                     (None, None) => bail!("Diary pages must have either a date or a URL"),
-                    (Some(Ok(date)), None) => {
+                    (Some(date), None) => {
                         (format_day(date, true), Either::Left(date))
                     }
                     (None, Some(url)) => (format!("/{}", url), Either::Right(url)),
@@ -221,26 +1534,115 @@ impl Generator {
 
                 Ok((page, path, identifier))
             })
+            .filter_map(|result| match result {
+                Err(error) if options.keep_going => {
+                    warn!(msg = "Skipping page", error = %format!("{:#}", error));
+                    skipped += 1;
+                    None
+                }
+                result => Some(result),
+            })
             .fold::<Result<_>, _>(
-                Ok((HashMap::with_capacity(length), BTreeMap::new(), Vec::new())),
+                Ok((
+                    HashMap::with_capacity(length),
+                    BTreeMap::<Date, Page<Properties>>::new(),
+                    Vec::<(String, Page<Properties>)>::new(),
+                    Vec::new(),
+                )),
                 |acc, result: Result<_>| {
-                    let (mut link_map, mut lookup_tree, mut article_pages) = acc?;
+                    let (mut link_map, mut lookup_tree, mut article_pages, mut drafts) = acc?;
                     let (page, path, identifier) = result?;
 
-                    link_map.insert(page.id, path);
+                    // Pages without a published date or marked as drafts only make it this far in
+                    // drafts mode, where they are rendered into their own directory instead of with
+                    // the rest
+                    if page.properties.published.date.is_none() || page.properties.draft() {
+                        let path = format!("{}{}", DRAFTS_DIR, path);
+                        link_map.insert(page.id, format!("/{}", path));
+                        drafts.push((path, page));
+
+                        return Ok((link_map, lookup_tree, article_pages, drafts));
+                    }
+
+                    // Pages can come from several databases so nothing guarantees that their dates
+                    // and URLs are unique, and silently dropping one of them would lose an entry
                     match identifier {
                         Either::Left(date) => {
+                            if let Some(existing) = lookup_tree.get(&date) {
+                                bail!(
+                                    "Pages {} and {} both have the date {}",
+                                    existing.id,
+                                    page.id,
+                                    date
+                                );
+                            }
+                            link_map.insert(page.id, path);
                             lookup_tree.insert(date, page);
                         }
                         Either::Right(url) => {
+                            if let Some((_, existing)) =
+                                article_pages.iter().find(|(existing, _)| *existing == url)
+                            {
+                                bail!(
+                                    "Pages {} and {} both have the URL {}",
+                                    existing.id,
+                                    page.id,
+                                    url
+                                );
+                            }
+                            link_map.insert(page.id, path);
                             article_pages.push((url, page));
                         }
                     };
 
-                    Ok((link_map, lookup_tree, article_pages))
+                    Ok((link_map, lookup_tree, article_pages, drafts))
                 },
             )?;
 
+        // Tags and series only differing in case or punctuation would share a page otherwise
+        check_path_collisions(
+            "tags",
+            lookup_tree
+                .values()
+                .chain(article_pages.iter().map(|(_, page)| page))
+                .flat_map(|page| page.properties.tags())
+                .map(String::from),
+            format_tag,
+        )?;
+        check_path_collisions(
+            "series",
+            lookup_tree
+                .values()
+                .filter_map(|page| page.properties.series()),
+            format_series,
+        )?;
+
+        let missing_descriptions = lookup_tree
+            .values()
+            .chain(article_pages.iter().map(|(_, page)| page))
+            .filter(|page| {
+                page.properties
+                    .description
+                    .rich_text
+                    .plain_text()
+                    .trim()
+                    .is_empty()
+            })
+            .inspect(|page| {
+                warn!(
+                    msg = "Page is missing a description",
+                    id = %page.id,
+                    title = %page.properties.title().plain_text()
+                )
+            })
+            .count();
+        if options.strict && missing_descriptions > 0 {
+            bail!(
+                "{} page(s) are missing a description, see the warnings above",
+                missing_descriptions
+            );
+        }
+
         let read_config_file = async {
             tokio::fs::File::open(dir.join("config.json"))
                 .await
@@ -253,33 +1655,514 @@ impl Generator {
         };
 
         let (head, header, footer, config_file) = tokio::try_join!(
-            read_partial_file(dir.join("partials/head.html")),
-            read_partial_file(dir.join("partials/header.html")),
-            read_partial_file(dir.join("partials/footer.html")),
+            read_partial_file(dir.join(PARTIALS_DIR).join("head.html")),
+            read_partial_file(dir.join(PARTIALS_DIR).join("header.html")),
+            read_partial_file(dir.join(PARTIALS_DIR).join("footer.html")),
             read_config_file,
         )?;
-        let head = PreEscaped(head);
+        // Partials are copied verbatim into every page so a tag left open in one of them breaks all
+        // of the pages
+        for (path, partial) in [
+            ("partials/head.html", &head),
+            ("partials/header.html", &header),
+            ("partials/footer.html", &footer),
+        ] {
+            if let Err(error) = fragment::check_balanced(partial) {
+                if options.strict {
+                    return Err(error.context(format!("Partial file {} is malformed", path)));
+                }
+
+                warn!(msg = "Partial file is malformed", path, error = %error);
+            }
+        }
         let header = PreEscaped(header);
-        let footer = PreEscaped(footer);
         let config = match config_file {
             Some(file) => serde_json::from_reader::<_, Config>(file.into_std().await)
                 .context("Failed to parse config.json")?,
             None => Default::default(),
         };
+        let footer = html! {
+            (PreEscaped(footer))
+            @if let Some(footer_text) = &config.footer_text {
+                p class="footer-text" {
+                    (footer_text
+                        .replace("{year}", &today.year().to_string())
+                        .replace("{author}", config.author.as_ref().map_or("", |author| &author.name)))
+                }
+            }
+        };
+        let head = html! {
+            @for font in config.fonts.iter().filter(|font| font.preload) {
+                link rel="preload" as="font" type=(format!("font/{}", font.format))
+                    href=(format!("/{}/{}", FONTS_DIR, font.href)) crossorigin;
+            }
+            @if let Some(print_stylesheet) = &config.print_stylesheet {
+                link rel="stylesheet" media="print" href=(print_stylesheet);
+            }
+            @match &config.theme_color {
+                Some(ThemeColor::Single(color)) => {
+                    meta name="theme-color" content=(color);
+                }
+                Some(ThemeColor::PerScheme { light, dark }) => {
+                    meta name="theme-color" media="(prefers-color-scheme: light)" content=(light);
+                    meta name="theme-color" media="(prefers-color-scheme: dark)" content=(dark);
+                }
+                None => {}
+            }
+            @for origin in &config.preconnect {
+                link rel="preconnect" href=(origin);
+                link rel="dns-prefetch" href=(origin);
+            }
+            (PreEscaped(head))
+        };
+
+        // An empty title would otherwise silently render as a blank <title> and index card
+        let all_pages = lookup_tree
+            .values_mut()
+            .chain(article_pages.iter_mut().map(|(_, page)| page))
+            .chain(drafts.iter_mut().map(|(_, page)| page));
+        for page in all_pages {
+            if page.properties.title().plain_text().trim().is_empty().not() {
+                continue;
+            }
+            if options.strict {
+                bail!("Page {} has an empty title", page.id);
+            }
+
+            warn!(
+                msg = "Page has an empty title, using a placeholder instead",
+                id = %page.id,
+                placeholder = %config.untitled
+            );
+            page.properties.name.title = vec![RichText {
+                plain_text: config.untitled.clone(),
+                href: None,
+                annotations: Default::default(),
+                ty: RichTextType::Text {
+                    content: config.untitled.clone(),
+                    link: None,
+                },
+            }];
+        }
+
+        // A mistyped year would otherwise create pages for every month up until it
+        let latest_reasonable = today
+            .year()
+            .checked_add(config.max_years_ahead.into())
+            .and_then(|year| {
+                // February 29th of a year that isn't a leap year falls back to the 28th
+                Date::from_calendar_date(year, today.month(), today.day())
+                    .or_else(|_| Date::from_calendar_date(year, Month::February, 28))
+                    .ok()
+            })
+            .with_context(|| {
+                format!(
+                    "max_years_ahead of {} is past the latest supported date",
+                    config.max_years_ahead
+                )
+            })?;
+        for (date, page) in
+            lookup_tree.range((Bound::Excluded(latest_reasonable), Bound::Unbounded))
+        {
+            if options.strict {
+                bail!(
+                    "Page {} has the date {} which is more than {} year(s) from now",
+                    page.id,
+                    date,
+                    config.max_years_ahead
+                );
+            }
+
+            warn!(
+                msg = "Page's date is unreasonably far in the future",
+                id = %page.id,
+                date = %date,
+                max_years_ahead = config.max_years_ahead
+            );
+        }
+
+        let mut excerpts = HashMap::new();
+        if let Some(separator) = &config.summary_separator {
+            let all_pages = lookup_tree
+                .values_mut()
+                .chain(article_pages.iter_mut().map(|(_, page)| page))
+                .chain(drafts.iter_mut().map(|(_, page)| page));
+            for page in all_pages {
+                if let Some(position) = split_summary(&mut page.children, separator) {
+                    excerpts.insert(page.id, position);
+                }
+            }
+        }
+
+        let mut media = HashMap::new();
+        for page in lookup_tree
+            .values_mut()
+            .chain(article_pages.iter_mut().map(|(_, page)| page))
+            .chain(drafts.iter_mut().map(|(_, page)| page))
+        {
+            download_embeds(&mut page.children, &mut media)?;
+        }
+
+        // Under `--keep-going` pages whose content fails to render are dropped before anything
+        // lists them, otherwise the index, feeds, sitemap and neighbouring pages would all link to
+        // a page that's never written
+        // Their content is rendered just like their own pages render it so that it's kept for when
+        // they're generated instead of being rendered again
+        let mut downloadables = Downloadables::new();
+        let mut rendered = HashMap::new();
+        if options.keep_going {
+            let mut failed = HashSet::new();
+            for page in lookup_tree
+                .values()
+                .chain(article_pages.iter().map(|(_, page)| page))
+                .chain(drafts.iter().map(|(_, page)| page))
+            {
+                let renderer = HtmlRenderer {
+                    heading_anchors: HeadingAnchors::After("#"),
+                    current_pages: HashSet::from([page.id]),
+                    link_map: &link_map,
+                    downloadables: &downloadables,
+                };
+                match renderer
+                    .render_blocks(&page.children, None, 1)
+                    .collect::<Result<Vec<_>>>()
+                {
+                    Ok(blocks) => {
+                        rendered.insert(page.id, blocks);
+                    }
+                    Err(error) => {
+                        warn!(
+                            msg = "Skipping page that failed to render",
+                            id = %page.id,
+                            error = %format!("{:#}", error)
+                        );
+                        failed.insert(page.id);
+                    }
+                }
+            }
+
+            // Pages linking to the skipped pages have to be rendered again without them, and the
+            // images of the skipped pages shouldn't be downloaded
+            if !failed.is_empty() {
+                rendered.clear();
+                downloadables = Downloadables::new();
+            }
 
-        let downloadables = Downloadables::new();
-
-        Ok(Generator {
+            skipped += failed.len();
+            link_map.retain(|id, _| !failed.contains(id));
+            lookup_tree.retain(|_, page| !failed.contains(&page.id));
+            article_pages.retain(|(_, page)| !failed.contains(&page.id));
+            drafts.retain(|(_, page)| !failed.contains(&page.id));
+        }
+
+        let mut generator = Generator {
             downloadables,
+            rendered,
+            media: Mutex::new(media),
+            downloaded_covers: HashSet::new(),
+            cover_sizes: HashMap::new(),
+            writer: Writer {
+                dry_run: options.dry_run.then(Default::default),
+            },
+            katex_styles: None,
+            strict: options.strict,
+            offline: options.offline,
+            keep_going: options.keep_going,
+            failures: AtomicUsize::new(skipped),
+            today,
+            excerpts,
+            listed_pages: HashMap::new(),
+            tag_index: BTreeMap::new(),
+            related_index: HashMap::new(),
             link_map,
             lookup_tree,
             article_pages,
+            drafts,
             head,
             header,
             footer,
             config,
             directory: dir.to_owned(),
-        })
+            output: options.output_dir.unwrap_or_else(|| dir.join(EXPORT_DIR)),
+        };
+        generator.index_tags();
+
+        Ok(generator)
+    }
+
+    /// Index the listed pages by their id and tags and find the related pages of every page once,
+    /// instead of going through every page again for each page that's rendered
+    fn index_tags(&mut self) {
+        let listed = self
+            .listed_entries()
+            .map(|(&date, page)| (PageKey::Entry(date), page))
+            .chain(
+                self.article_pages
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (_, page))| !page.properties.unlisted())
+                    .map(|(index, (_, page))| (PageKey::Article(index), page)),
+            )
+            .sorted_by(|(_, a), (_, b)| {
+                b.properties
+                    .display_date()
+                    .cmp(&a.properties.display_date())
+            })
+            .collect::<Vec<_>>();
+
+        let mut tag_index = BTreeMap::<String, Vec<NotionId>>::new();
+        for (_, page) in &listed {
+            for tag in page.properties.tags() {
+                tag_index.entry(tag.to_string()).or_default().push(page.id);
+            }
+        }
+
+        // Pages sharing as many tags are ordered by how recent they are
+        let recency = listed
+            .iter()
+            .enumerate()
+            .map(|(rank, (_, page))| (page.id, rank))
+            .collect::<HashMap<_, _>>();
+        let related_index = self
+            .lookup_tree
+            .values()
+            .chain(self.article_pages.iter().map(|(_, page)| page))
+            .chain(self.drafts.iter().map(|(_, page)| page))
+            .filter_map(|page| {
+                let mut shared = HashMap::new();
+                for tag in page.properties.tags() {
+                    for &other in tag_index.get(tag).into_iter().flatten() {
+                        if other != page.id {
+                            *shared.entry(other).or_insert(0) += 1;
+                        }
+                    }
+                }
+                if shared.is_empty() {
+                    return None;
+                }
+
+                let related = shared
+                    .into_iter()
+                    .sorted_by_key(|&(other, count)| (Reverse(count), recency[&other]))
+                    .take(MAX_RELATED)
+                    .map(|(other, _)| other)
+                    .collect();
+                Some((page.id, related))
+            })
+            .collect();
+
+        let listed_pages = listed
+            .into_iter()
+            .map(|(key, page)| (page.id, key))
+            .collect();
+        self.listed_pages = listed_pages;
+        self.tag_index = tag_index;
+        self.related_index = related_index;
+    }
+
+    /// A listed entry or article by its id
+    fn listed_page(&self, id: &NotionId) -> &Page<Properties> {
+        match self.listed_pages[id] {
+            PageKey::Entry(date) => &self.lookup_tree[&date],
+            PageKey::Article(index) => &self.article_pages[index].1,
+        }
+    }
+
+    /// Whether the articles page is generated, which by default is only when there are articles
+    fn has_articles_page(&self) -> bool {
+        self.config
+            .articles_page
+            .unwrap_or(self.listed_articles().next().is_some())
+    }
+
+    /// Whether the subscribe page is generated, which is only when there's a way to subscribe
+    fn has_subscribe_page(&self) -> bool {
+        self.config.get_atom_id().is_some()
+            || self.config.fediverse.is_some()
+            || self.config.newsletter.is_some()
+    }
+
+    /// The blocks of a page's body before its summary separator, if it has one
+    fn excerpt<'a>(&self, page: &'a Page<Properties>) -> Option<&'a [Block]> {
+        self.excerpts
+            .get(&page.id)
+            // A separator at the very start of a page leaves nothing to show so the description is
+            // used instead
+            .filter(|&&position| position > 0)
+            .map(|&position| &page.children[..position])
+    }
+
+    /// Every entry except the unlisted ones, which are left out of anything listing entries
+    fn listed_entries(&self) -> impl DoubleEndedIterator<Item = (&Date, &Page<Properties>)> {
+        self.lookup_tree
+            .iter()
+            .filter(|(_, page)| !page.properties.unlisted())
+    }
+
+    /// Every article except the unlisted ones, which are left out of anything listing articles
+    fn listed_articles(&self) -> impl Iterator<Item = &(String, Page<Properties>)> {
+        self.article_pages
+            .iter()
+            .filter(|(_, page)| !page.properties.unlisted())
+    }
+
+    /// The directory the diary is generated into
+    pub fn output_dir(&self) -> &Path {
+        &self.output
+    }
+
+    /// The directory downloads are kept in between builds, outside of the output
+    pub fn cache_dir(&self) -> PathBuf {
+        self.directory.join(CACHE_DIR)
+    }
+
+    /// The directory whose content is copied as-is into the output
+    pub fn public_dir(&self) -> PathBuf {
+        self.directory.join(&self.config.public_dir)
+    }
+
+    /// Delete the output directory along with everything in it
+    /// Refuses to if the output directory is or contains the diary's directory or any of the
+    /// sources the diary is generated from, or if it wasn't generated into before since it could
+    /// be any directory passed to `--output` by mistake
+    /// Leaves the output directory alone when there are no entries since nothing would replace it
+    pub async fn clean_output(&self) -> Result<()> {
+        if self.lookup_tree.is_empty() {
+            warn!(
+                msg = "Not cleaning the output directory since there are no entries to generate",
+                path = %self.output.display()
+            );
+            return Ok(());
+        }
+
+        let output = match tokio::fs::canonicalize(&self.output).await {
+            Ok(output) => output,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(error) => {
+                return Err(error).with_context(|| {
+                    format!(
+                        "Failed to resolve output directory {}",
+                        self.output.display()
+                    )
+                })
+            }
+        };
+
+        let sources = [
+            self.directory.clone(),
+            self.directory.join("config.json"),
+            self.directory.join(PARTIALS_DIR),
+            self.directory.join(FONTS_DIR),
+            self.directory.join(&self.config.pages_dir),
+            self.public_dir(),
+        ];
+        for source in sources {
+            let source = match tokio::fs::canonicalize(&source).await {
+                Ok(source) => source,
+                // Sources that don't exist can't be deleted
+                Err(error) if error.kind() == io::ErrorKind::NotFound => continue,
+                Err(error) => return Err(error.into()),
+            };
+
+            if source.starts_with(&output) {
+                bail!(
+                    "Refusing to clean {} since it contains {} which the diary is generated from",
+                    output.display(),
+                    source.display()
+                );
+            }
+        }
+
+        if !tokio::fs::metadata(output.join(OUTPUT_MARKER))
+            .await
+            .map_or(false, |metadata| metadata.is_file())
+        {
+            bail!(
+                "Refusing to clean {} since the diary wasn't generated into it before, delete it \
+                yourself if nothing else is in there",
+                output.display()
+            );
+        }
+
+        tokio::fs::remove_dir_all(&output)
+            .await
+            .with_context(|| format!("Failed to clean output directory {}", output.display()))
+    }
+
+    /// Mark the output directory as generated by the diary so that `--clean` is allowed to delete
+    /// it
+    pub fn generate_output_marker(&self) -> JoinHandle<Result<()>> {
+        let path = self.output.join(OUTPUT_MARKER);
+        tokio::spawn(self.writer.write(
+            path,
+            format!("Generated by {} {}\n", DIARY_GENERATOR, VERSION),
+        ))
+    }
+
+    /// What the build would have written so far, only present when doing a dry run
+    pub fn dry_run(&self) -> Option<&Arc<DryRun>> {
+        self.writer.dry_run.as_ref()
+    }
+
+    /// How many generators are allowed to run at once, unbounded if not present
+    pub fn concurrency(&self) -> Option<usize> {
+        self.config.concurrency
+    }
+
+    /// Whether KaTeX's stylesheet is inlined into pages, in which case it needs to be downloaded
+    /// with `katex::inline_styles` and given to `inline_katex` before generating
+    pub fn inlines_katex(&self) -> bool {
+        matches!(self.config.katex_link, KatexLink::Inline)
+    }
+
+    /// Inline KaTeX's stylesheet into every page generated from now on
+    pub fn inline_katex(&mut self, styles: String) {
+        self.katex_styles = Some(styles);
+    }
+
+    /// The archive page the entries of a year are on
+    fn archive_year(&self, year: i32) -> ArchiveYear {
+        match self.config.archive_before {
+            Some(before) if year < before => ArchiveYear::Before(before),
+            _ => ArchiveYear::Year(year),
+        }
+    }
+
+    /// How many pages were skipped because they failed to be generated under `--keep-going`
+    pub fn failures(&self) -> usize {
+        self.failures.load(Ordering::Relaxed)
+    }
+
+    /// The most bytes a single downloaded file is allowed to be, unbounded if not present
+    pub fn max_download_bytes(&self) -> Option<u64> {
+        self.config.max_download_bytes
+    }
+
+    /// Download KaTeX into the output, which is skipped with a warning in offline mode and only
+    /// logged when doing a dry run
+    pub fn download_katex(&self, client: Client) -> JoinHandle<Result<()>> {
+        self.download_katex_from(client, katex::CDN_URL.to_string())
+    }
+
+    /// Like `download_katex` but from another copy of KaTeX's dist/ directory at `cdn_url`
+    pub fn download_katex_from(&self, client: Client, cdn_url: String) -> JoinHandle<Result<()>> {
+        if self.offline {
+            warn!("Skipping downloading KaTeX in offline mode, math will be unstyled");
+            return tokio::spawn(async { Ok(()) });
+        }
+        if self.writer.dry_run.is_some() {
+            info!("Would download KaTeX");
+            return tokio::spawn(async { Ok(()) });
+        }
+
+        katex::download_from(
+            client,
+            cdn_url,
+            self.cache_dir(),
+            self.output.clone(),
+            self.config.max_download_bytes,
+            self.strict,
+        )
     }
 
     pub fn get_first_and_last_dates(&self) -> Option<(Date, Date)> {
@@ -294,10 +2177,61 @@ impl Generator {
         }
     }
 
-    async fn write_if_not_empty(option: Option<(PathBuf, Markup)>) -> Result<()> {
-        match option {
-            Some((path, markup)) => write(path, markup.into_string()).await,
-            None => Ok(()),
+    /// How many entries the diary has
+    pub fn entry_count(&self) -> usize {
+        self.lookup_tree.len()
+    }
+
+    /// How many articles the diary has
+    pub fn article_count(&self) -> usize {
+        self.article_pages.len()
+    }
+
+    /// Render a single page, under `--keep-going` a page that fails to render is reported and left
+    /// out instead of failing the whole build
+    fn render_page<T>(
+        &self,
+        page: &Page<Properties>,
+        render: impl FnOnce() -> Result<Option<T>>,
+    ) -> Result<Option<T>> {
+        match render() {
+            Err(error) if self.keep_going => {
+                warn!(
+                    msg = "Skipping page that failed to render",
+                    id = %page.id,
+                    error = %format!("{:#}", error)
+                );
+                self.failures.fetch_add(1, Ordering::Relaxed);
+                Ok(None)
+            }
+            result => result,
+        }
+    }
+
+    /// Render the content of an entry or article for its own page, unless `--keep-going` already
+    /// did
+    fn render_content<'a>(
+        &'a self,
+        renderer: &'a HtmlRenderer<'a>,
+        page: &'a Page<Properties>,
+    ) -> impl Iterator<Item = Result<Markup>> + 'a {
+        match self.rendered.get(&page.id) {
+            Some(blocks) => Either::Left(blocks.iter().cloned().map(Ok)),
+            None => Either::Right(renderer.render_blocks(&page.children, None, 1)),
+        }
+    }
+
+    fn write_if_not_empty(
+        &self,
+        option: Option<(PathBuf, Markup)>,
+    ) -> impl Future<Output = Result<()>> {
+        let write = option.map(|(path, markup)| self.writer.write(path, markup.into_string()));
+
+        async move {
+            match write {
+                Some(write) => write.await,
+                None => Ok(()),
+            }
         }
     }
 
@@ -310,33 +2244,74 @@ impl Generator {
     where
         I: Iterator<Item = Result<Markup>>,
     {
-        let date = page
-            .properties
-            .date
-            .date
-            .as_ref()
-            .map(|date| date.start.date())
-            .or_else(|| {
-                page.properties
-                    .published
-                    .date
-                    .as_ref()
-                    .map(|date| date.start.date())
+        let date = page.properties.shown_date(self.config.display_date);
+        let end = page.properties.shown_end(self.config.display_date);
+        // Only mention updates that happened after the day the page was published since pages are
+        // often touched up shortly after
+        let published = page.properties.published.date.as_ref().map(start_date);
+        let updated = OffsetDateTime::parse(&page.last_edited_time, &Rfc3339)
+            .ok()
+            .map(|time| time.date())
+            .filter(|&updated| {
+                published.map_or(false, |published| updated - published > Duration::days(1))
             });
 
-        let cover = self.download_cover(page)?;
+        let tags = page.properties.tags();
+
+        let cover = self.render_cover(page, renderer)?;
+        let table_of_contents = self.render_table_of_contents(page);
+
+        // Self-canonical pages are the original so there's nothing to point readers to
+        let original = page.properties.canonical().filter(|canonical| {
+            self.config
+                .url
+                .as_ref()
+                .map_or(true, |url| canonical.host_str() != url.host_str())
+        });
 
         Ok(html! {
-            article {
+            article itemscope itemtype="https://schema.org/BlogPosting" {
+                @if let (Some(original), Some(wording)) = (original, &self.config.original_banner) {
+                    @let (before, after) = wording.split_once("{}").unwrap_or((wording, ""));
+                    p class="original" {
+                        (before)
+                        a href=(original) { (original.host_str().unwrap_or_else(|| original.as_str())) }
+                        (after)
+                    }
+                }
                 header {
                     (renderer.render_heading(page.id, None, Heading::H1, page.properties.title()))
-                    @if let Some(date) = date {
-                        (render_article_time(date)?)
+                    @if let Some((date, time)) = date {
+                        (render_article_range(date, time, end, true)?)
+                    }
+                    @if let Some(updated) = updated {
+                        (render_updated_time(updated)?)
+                    }
+                    @if let Some(reading_time) = &self.config.reading_time {
+                        (render_reading_time(&page.children, reading_time))
+                    }
+                    @if tags.is_empty().not() {
+                        ul class="tags" {
+                            @for tag in &tags {
+                                li {
+                                    // Tags only used by drafts or unlisted pages don't get a page
+                                    // to link to
+                                    @if self.tag_index.contains_key(*tag) {
+                                        a rel="tag" href={ "/" (format_tag(tag)) } { (tag) }
+                                    } @else {
+                                        (tag)
+                                    }
+                                }
+                            }
+                        }
                     }
                     @if let Some(cover) = cover {
-                        img alt=(format!("{} cover", page.properties.title().plain_text())) src=(cover);
+                        (cover)
                     }
                 }
+                @if let Some(table_of_contents) = table_of_contents {
+                    (table_of_contents)
+                }
                 @for block in blocks {
                     (block?)
                 }
@@ -344,10 +2319,181 @@ impl Generator {
         })
     }
 
+    /// A list of links to every heading of a page, only for pages with enough headings for it to
+    /// be worth it and when enabled
+    fn render_table_of_contents(&self, page: &Page<Properties>) -> Option<Markup> {
+        if !self.config.table_of_contents {
+            return None;
+        }
+
+        let headings = page
+            .children
+            .iter()
+            .filter_map(|block| match &block.ty {
+                BlockType::HeadingOne { text } => Some((block, 1, text)),
+                BlockType::HeadingTwo { text } => Some((block, 2, text)),
+                BlockType::HeadingThree { text } => Some((block, 3, text)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if headings.len() < 2 {
+            return None;
+        }
+
+        Some(html! {
+            nav class="toc" {
+                ul {
+                    @for (block, level, text) in headings {
+                        // Headings are given their block's id without the hyphens as their anchor
+                        li class={ "toc-h" (level) } {
+                            a href={ "#" (block.id.to_string().replace('-', "")) } {
+                                (text.plain_text())
+                            }
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    /// Download the cover of every page before any page is generated so that covers can be
+    /// rendered with their width and height, which keeps the page from shifting once they load
+    /// Covers bigger than `max_download_bytes` fail the download when strict, otherwise they're
+    /// skipped with a warning. Nothing is downloaded in offline mode or in a dry run, which leaves
+    /// covers to `download_all` and without a size
+    pub async fn download_covers(&mut self, client: Client) -> Result<()> {
+        if self.offline || self.writer.dry_run.is_some() {
+            return Ok(());
+        }
+
+        let mut covers = HashMap::new();
+        {
+            let mut media = self.media.lock().expect("media lock to not be poisoned");
+            let pages = self
+                .lookup_tree
+                .values()
+                .chain(self.article_pages.iter().map(|(_, page)| page))
+                .chain(self.drafts.iter().map(|(_, page)| page));
+            for page in pages {
+                if let Some(file) = &page.cover {
+                    let src = register_media(&mut media, file, page.id, MediaKind::Cover.suffix())?;
+                    let (url, source) = media_source(file);
+                    covers
+                        .entry(source.to_string())
+                        .or_insert_with(|| (url.to_string(), src));
+                }
+            }
+        }
+
+        let max_bytes = self.config.max_download_bytes;
+        let strict = self.strict;
+        let output = &self.output;
+        let client = &client;
+        let downloaded = covers
+            .into_iter()
+            .map(|(source, (url, src))| async move {
+                let response = client.get(&url).send().await?;
+                let status = response.status();
+                if !status.is_success() {
+                    bail!(
+                        "Download request for cover {} failed with status code {}",
+                        source,
+                        status
+                    );
+                }
+
+                let bytes = match katex::read_limited(response, max_bytes).await? {
+                    Ok(bytes) => bytes,
+                    Err(length) if strict => bail!(
+                        "Media {} is at least {} bytes which is over max_download_bytes of {}",
+                        source,
+                        length,
+                        max_bytes.unwrap_or_default()
+                    ),
+                    Err(length) => {
+                        warn!(
+                            msg = "Skipping media over max_download_bytes",
+                            source = %source,
+                            bytes = length,
+                            max_bytes
+                        );
+                        return Ok((source, None));
+                    }
+                };
+
+                let size = ImageReader::new(Cursor::new(&bytes))
+                    .with_guessed_format()
+                    .map_err(anyhow::Error::from)
+                    .and_then(|reader| Ok(reader.into_dimensions()?));
+                let size = match size {
+                    Ok(size) => Some(size),
+                    Err(error) => {
+                        warn!(
+                            msg = "Rendering cover without its size since it couldn't be read",
+                            source = %source,
+                            error = %error
+                        );
+                        None
+                    }
+                };
+
+                write(output.join(src.trim_start_matches('/')), bytes).await?;
+                Ok((source, size))
+            })
+            .collect::<FuturesUnordered<_>>()
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        for (source, size) in downloaded {
+            if let Some(size) = size {
+                self.cover_sizes.insert(source.clone(), size);
+            }
+            self.downloaded_covers.insert(source);
+        }
+
+        Ok(())
+    }
+
+    /// Download every cover, icon and image the generated pages point to
+    /// In a dry run the media registered by the diary itself is only listed instead, images inside
+    /// of pages' content aren't listed since only notion-generator knows about them
+    /// Nothing is downloaded in offline mode
     pub async fn download_all(self, client: Client) -> Result<()> {
-        self.downloadables
-            .download_all(client, Path::new(EXPORT_DIR))
-            .await
+        let mut media = self
+            .media
+            .into_inner()
+            .expect("media lock to not be poisoned");
+        media.retain(|source, _| !self.downloaded_covers.contains(source));
+
+        if self.offline {
+            warn!("Skipping downloading covers, icons and images in offline mode, they will be missing");
+            return Ok(());
+        }
+
+        if let Some(dry_run) = &self.writer.dry_run {
+            for (source, media) in media.iter() {
+                info!(
+                    msg = "Would download media",
+                    source = %source,
+                    path = %media.downloadable.src_path()
+                );
+            }
+            dry_run.media.fetch_add(media.len(), Ordering::Relaxed);
+
+            return Ok(());
+        }
+
+        let oversized = match self.config.max_download_bytes {
+            Some(max_bytes) => oversized_media(&client, &media, max_bytes, self.strict).await?,
+            None => HashSet::new(),
+        };
+        for (source, media) in media {
+            if !oversized.contains(&source) {
+                self.downloadables.insert(media.downloadable);
+            }
+        }
+
+        self.downloadables.download_all(client, &self.output).await
     }
 
     pub fn generate_years(
@@ -355,21 +2501,34 @@ impl Generator {
         first_date: Date,
         last_date: Date,
     ) -> Result<JoinHandle<Result<()>>> {
+        info!(
+            msg = "Generating years",
+            count = self
+                .lookup_tree
+                .keys()
+                .map(|date| date.year())
+                .dedup()
+                .count()
+        );
+
         let years = (first_date.year()..=last_date.year())
+            .map(|year| self.archive_year(year))
+            .dedup()
             .map(|year| {
-                let first_day = Date::from_calendar_date(year, Month::January, 1).unwrap();
-                let next_year = Date::from_calendar_date(year + 1, Month::January, 1).unwrap();
+                let range = self.lookup_tree.range(year.range());
 
-                let range = self.lookup_tree.range(first_day..next_year);
-
-                let (current_pages, pages) = range
+                let (current_pages, mut pages) = range
                     .map(|(_, page)| page)
+                    .filter(|page| !page.properties.unlisted())
                     .map(|page| (page.id, page))
                     .unzip::<_, _, HashSet<_>, Vec<_>>();
 
                 if pages.is_empty() {
                     return Ok(None);
                 }
+                if self.config.archive_order == ArchiveOrder::Newest {
+                    pages.reverse();
+                }
 
                 let renderer = HtmlRenderer {
                     heading_anchors: HeadingAnchors::After("#"),
@@ -378,49 +2537,45 @@ impl Generator {
                     downloadables: &self.downloadables,
                 };
 
+                let description = truncate_description(
+                    &self
+                        .config
+                        .year_description
+                        .replace("{name}", &self.config.name)
+                        .replace("{year}", &year.lowercase())
+                        .replace("{entries}", &counted(pages.len(), "entry", "entries"))
+                        .replace("{count}", &pages.len().to_string()),
+                    self.config.meta_description_max,
+                );
                 let rendered_pages = pages
                     .into_iter()
                     .map(|page| (page, renderer.render_blocks(&page.children, None, 1)));
 
                 let title = format!("{} - {}", year, self.config.name);
-                let path = format_year(year);
+                let path = year.path();
+
+                // TODO: Should we use the first cover in the year as an image?
+                // Would be cool to generate some custom covers here
+                let head = Head {
+                    description: Some(description.as_str()),
+                    robots: self.config.robots.archives.as_deref(),
+                    // Only whole years have a feed of their own
+                    extra: match year {
+                        ArchiveYear::Year(_) if self.config.get_atom_id().is_some() => html! {
+                            link rel="alternate" type="application/atom+xml" href=(format!("/{}/{}", path, FEED_FILE));
+                        },
+                        _ => PreEscaped(String::new()),
+                    },
+                    ..Head::new(&title, &path, &self.config.locale)
+                };
 
                 let markup = html! {
                     (DOCTYPE)
                     html lang=(self.config.locale.lang) {
-                        head {
-                            meta charset="utf-8";
-                            meta name="viewport" content="width=device-width, initial-scale=1";
-                            link rel="stylesheet" href="/katex/katex.min.css";
-                            title { (title) }
-                            @if let Some(author) = &self.config.author {
-                                meta name="author" content=(author.name);
-                            }
-                            @if self.config.get_atom_id().is_some() {
-                                link rel="alternate" type="application/atom+xml" href="/feed.xml";
-                            }
-
-                            meta property="og:title" content=(title);
-                            // TODO: What's a good description for years? Should we just say
-                            // something like "All entries for year 2021 from Diary"?
-                            meta property="og:locale" content=(self.config.locale.locale);
-                            // TODO: Should we use the first cover in the year as an image?
-                            // Would be cool to generate some custom covers here
-                            @if let Some(url) = &self.config.url {
-                                meta property="og:url" content=(url.join(&path)?);
-                            }
-                            @if let Some(twitter_site) = &self.config.twitter.site {
-                                meta name="twitter:site" content=(twitter_site);
-                            }
-                            @if let Some(twitter_creator) = &self.config.twitter.creator {
-                                meta name="twitter:creator" content=(twitter_creator);
-                            }
-
-                            (self.head)
-                        }
+                        (self.render_head(&head)?)
                         body {
                             header {
-                                (self.header)
+                                (self.render_header(&path)?)
                             }
                             main {
                                 @for (page, blocks) in rendered_pages {
@@ -434,11 +2589,11 @@ impl Generator {
                     }
                 };
 
-                let mut path = self.directory.join(EXPORT_DIR).join(path);
+                let mut path = self.output.join(path);
                 path.set_extension("html");
                 Ok(Some((path, markup)))
             })
-            .map_ok(Self::write_if_not_empty)
+            .map_ok(|option| self.write_if_not_empty(option))
             .collect::<Result<FuturesUnordered<_>>>()?;
 
         Ok(tokio::spawn(years.try_collect::<()>()))
@@ -449,6 +2604,16 @@ impl Generator {
         first_date: Date,
         last_date: Date,
     ) -> Result<JoinHandle<Result<()>>> {
+        info!(
+            msg = "Generating months",
+            count = self
+                .lookup_tree
+                .keys()
+                .map(|date| (date.year(), date.month()))
+                .dedup()
+                .count()
+        );
+
         let months = (first_date.year()..=last_date.year())
             .cartesian_product(months::all())
             .map(|(year, &month)| {
@@ -463,14 +2628,18 @@ impl Generator {
 
                 let range = self.lookup_tree.range(first_day..next_month);
 
-                let (current_pages, pages) = range
+                let (current_pages, mut pages) = range
                     .map(|(_, page)| page)
+                    .filter(|page| !page.properties.unlisted())
                     .map(|page| (page.id, page))
                     .unzip::<_, _, HashSet<_>, Vec<_>>();
 
                 if pages.is_empty() {
                     return Ok(None);
                 }
+                if self.config.archive_order == ArchiveOrder::Newest {
+                    pages.reverse();
+                }
 
                 let renderer = HtmlRenderer {
                     heading_anchors: HeadingAnchors::After("#"),
@@ -479,158 +2648,1224 @@ impl Generator {
                     downloadables: &self.downloadables,
                 };
 
+                let month_name = months::name(month, &self.config.locale.lang);
+                let description = truncate_description(
+                    &self
+                        .config
+                        .month_description
+                        .replace("{name}", &self.config.name)
+                        .replace("{month}", month_name)
+                        .replace("{year}", &year.to_string())
+                        .replace("{entries}", &counted(pages.len(), "entry", "entries"))
+                        .replace("{count}", &pages.len().to_string()),
+                    self.config.meta_description_max,
+                );
                 let rendered_pages = pages
                     .into_iter()
                     .map(|page| (page, renderer.render_blocks(&page.children, None, 1)));
 
-                let title = format!("{} {} - {}", month, year, self.config.name);
+                let title = format!("{} {} - {}", month_name, year, self.config.name);
                 let path = format_month(year, month);
 
+                // TODO: Should we use the first cover in the months as an image?
+                // Would be cool to generate some custom covers here
+                let head = Head {
+                    description: Some(description.as_str()),
+                    robots: self.config.robots.archives.as_deref(),
+                    extra: match self.config.get_atom_id() {
+                        Some(_) => html! {
+                            link rel="alternate" type="application/atom+xml" href=(format!("/{}/{}", path, FEED_FILE));
+                        },
+                        None => PreEscaped(String::new()),
+                    },
+                    ..Head::new(&title, &path, &self.config.locale)
+                };
+
                 let markup = html! {
                     (DOCTYPE)
                     html lang=(self.config.locale.lang) {
-                        head {
-                            meta charset="utf-8";
-                            meta name="viewport" content="width=device-width, initial-scale=1";
-                            link rel="stylesheet" href="/katex/katex.min.css";
-                            title { (title) }
-                            @if let Some(author) = &self.config.author {
-                                meta name="author" content=(author.name);
-                            }
-                            @if self.config.get_atom_id().is_some() {
-                                link rel="alternate" type="application/atom+xml" href="/feed.xml";
-                            }
-
-                            meta property="og:title" content=(title);
-                            // TODO: What's a good description for months? Should we just say
-                            // something like "All entries for Nov 2021 from Diary"?
-                            meta property="og:locale" content=(self.config.locale.locale);
-                            // TODO: Should we use the first cover in the months as an image?
-                            // Would be cool to generate some custom covers here
-                            @if let Some(url) = &self.config.url {
-                                meta property="og:url" content=(url.join(&path)?);
+                        (self.render_head(&head)?)
+                        body {
+                            header {
+                                (self.render_header(&path)?)
                             }
-                            @if let Some(twitter_site) = &self.config.twitter.site {
-                                meta name="twitter:site" content=(twitter_site);
+                            main {
+                                @for (page, blocks) in rendered_pages {
+                                    (self.render_article(&renderer, page, blocks)?)
+                                }
                             }
-                            @if let Some(twitter_creator) = &self.config.twitter.creator {
-                                meta name="twitter:creator" content=(twitter_creator);
+                            footer {
+                                (self.footer)
                             }
-
-                            (self.head)
                         }
-                        body {
-                            header {
-                                (self.header)
+                    }
+                };
+
+                let mut path = self.output.join(path);
+                path.set_extension("html");
+                Ok(Some((path, markup)))
+            })
+            .map_ok(|option| self.write_if_not_empty(option))
+            .collect::<Result<FuturesUnordered<_>>>()?;
+
+        Ok(tokio::spawn(months.try_collect::<()>()))
+    }
+
+    /// The Open Graph properties describing an entry or article as an article of the diary
+    fn render_article_og(&self, page: &Page<Properties>) -> Markup {
+        let tags = page.properties.tags();
+
+        html! {
+            meta property="og:type" content="article";
+            meta property="og:site_name" content=(self.config.name);
+            @if let Some(author) = &self.config.author {
+                @match &author.url {
+                    Some(url) => meta property="article:author" content=(url);,
+                    None => meta property="article:author" content=(author.name);,
+                }
+            }
+            @if let Some(section) = tags.first() {
+                meta property="article:section" content=(section);
+            }
+            @for tag in &tags {
+                meta property="article:tag" content=(tag);
+            }
+        }
+    }
+
+    /// The `<head>` of a page of the diary, with the KaTeX styles and head partial of the generator
+    fn render_head(&self, head: &Head) -> Result<Markup> {
+        render_head(&self.config, self.katex_styles.as_deref(), &self.head, head)
+    }
+
+    /// The site header of the page at `path`, with the navigation menu before the header partial
+    fn render_header(&self, path: &str) -> Result<Markup> {
+        Ok(html! {
+            (render_nav(&self.config.nav, path))
+            (render_lang_switcher(&self.config, path)?)
+            (self.header)
+        })
+    }
+
+    /// The published pages sharing the most tags with a page, with the most recent first among
+    /// pages sharing as many tags
+    fn related(&self, page: &Page<Properties>) -> Vec<&Page<Properties>> {
+        self.related_index
+            .get(&page.id)
+            .into_iter()
+            .flatten()
+            .map(|id| self.listed_page(id))
+            .collect()
+    }
+
+    /// Collect the published entries and articles carrying every tag, most recent first
+    fn tags(&self) -> BTreeMap<&str, Vec<&Page<Properties>>> {
+        self.tag_index
+            .iter()
+            .map(|(tag, ids)| {
+                let pages = ids.iter().map(|id| self.listed_page(id)).collect();
+                (tag.as_str(), pages)
+            })
+            .collect()
+    }
+
+    /// Every page that is linked to from the paging links of its neighbours in order, the dated
+    /// entries and, if `paging_includes_articles` is enabled, the articles by their published date
+    fn paging_chain(&self) -> Vec<PagingLink<'_>> {
+        let entries = self.listed_entries().map(|(&date, page)| PagingLink {
+            href: &self.link_map[&page.id],
+            entry_date: Some(date),
+            page,
+        });
+        let articles = self
+            .listed_articles()
+            .filter(|_| self.config.paging_includes_articles)
+            .map(|(_, page)| PagingLink {
+                href: &self.link_map[&page.id],
+                entry_date: None,
+                page,
+            });
+
+        entries
+            .chain(articles)
+            .sorted_by_key(|link| link.page.properties.display_date())
+            .collect()
+    }
+
+    /// Collect the dated entries of every series in chronological order
+    fn series(&self) -> BTreeMap<String, Vec<(&Date, &Page<Properties>)>> {
+        self.listed_entries()
+            .filter_map(|(date, page)| page.properties.series().map(|series| (series, date, page)))
+            .fold(BTreeMap::new(), |mut series_map, (series, date, page)| {
+                series_map
+                    .entry(series)
+                    .or_insert_with(Vec::new)
+                    .push((date, page));
+                series_map
+            })
+    }
+
+    pub fn generate_days(&self) -> Result<JoinHandle<Result<()>>> {
+        info!(msg = "Generating days", count = self.lookup_tree.len());
+
+        let series = self.series();
+        let paging_chain = self.paging_chain();
+
+        let days = self
+            .lookup_tree
+            .iter()
+            .map(|(date, page)| {
+                self.render_page(page, || {
+                    let renderer = HtmlRenderer {
+                        heading_anchors: HeadingAnchors::After("#"),
+                        current_pages: HashSet::from([page.id]),
+                        link_map: &self.link_map,
+                        downloadables: &self.downloadables,
+                    };
+
+                    let blocks = self.render_content(&renderer, page);
+
+                    let title = format!(
+                        "{} - {}",
+                        page.properties.title().plain_text(),
+                        self.config.name
+                    );
+                    let description = truncate_description(
+                        &page.properties.description.rich_text.plain_text(),
+                        self.config.meta_description_max,
+                    );
+                    // Unlisted pages are never indexed regardless of the config
+                    let robots = if page.properties.unlisted() {
+                        Some("noindex")
+                    } else {
+                        self.config.robots.entries.as_deref()
+                    };
+
+                    let (prev_page, next_page) = neighbours(&paging_chain, page);
+
+                    let series_links = page.properties.series().and_then(|name| {
+                        let members = series.get(&name)?;
+                        let position = members
+                            .iter()
+                            .position(|(member_date, _)| *member_date == date)?;
+
+                        Some(render_series_links(&renderer, &name, position, members))
+                    });
+
+                    let cover = self.download_cover(page)?;
+                    let icon = self.page_icon(page)?;
+                    let path = format_day(*date, false);
+                    let og_image = self.og_image(page, cover, &path)?;
+                    let locale = page.properties.locale();
+                    let locale = locale.as_ref().unwrap_or(&self.config.locale);
+
+                    let head = Head {
+                        description: (!description.is_empty()).then(|| description.as_str()),
+                        robots,
+                        image: og_image.as_deref(),
+                        canonical: page.properties.canonical(),
+                        icon: icon.as_deref(),
+                        extra: self.render_article_og(page),
+                        ..Head::new(&title, &path, locale)
+                    };
+
+                    let markup = html! {
+                        (DOCTYPE)
+                        html lang=(locale.lang) {
+                            (self.render_head(&head)?)
+                            body {
+                                header {
+                                    (self.render_header(&path)?)
+                                }
+                                main id="content" {
+                                    (self.render_article(&renderer, page, blocks)?)
+                                    @if let Some(series_links) = series_links {
+                                        (series_links)
+                                    }
+                                    (render_related(&renderer, &self.link_map, &self.related(page), self.config.rich_descriptions, self.config.display_date)?)
+                                    (render_paging_links(&renderer, &self.config.paging, self.config.display_date, Some(*date), prev_page, next_page)?)
+                                    @if let Some(comments) = &self.config.comments {
+                                        (PreEscaped(comments))
+                                    }
+                                    @if self.config.back_to_top {
+                                        a class="back-to-top" href="#content" { "Back to top" }
+                                    }
+                                }
+                                footer {
+                                    (self.footer)
+                                }
+                            }
+                        }
+                    };
+
+                    let mut path = self.output.join(path);
+                    path.set_extension("html");
+                    Ok(Some((path, markup)))
+                })
+            })
+            .map_ok(|option| self.write_if_not_empty(option))
+            .collect::<Result<FuturesUnordered<_>>>()?;
+
+        Ok(tokio::spawn(days.try_collect::<()>()))
+    }
+
+    pub fn generate_index_page(&self) -> Result<JoinHandle<Result<()>>> {
+        struct IndexMonth {
+            month: (i32, Month),
+            markup: String,
+        }
+
+        struct IndexYear {
+            year: ArchiveYear,
+            markup: String,
+        }
+
+        let renderer = HtmlRenderer {
+            heading_anchors: HeadingAnchors::After("#"),
+            current_pages: HashSet::new(),
+            link_map: &self.link_map,
+            downloadables: &self.downloadables,
+        };
+
+        let entries_per_year = self
+            .listed_entries()
+            .counts_by(|(date, _)| self.archive_year(date.year()));
+        let limit = self.config.index_entries_per_year;
+
+        let years = self
+            .listed_entries()
+            .rev()
+            // Only keep the most recent entries of each year when the index is limited
+            .filter({
+                let mut current_year = None;
+                let mut shown = 0;
+                move |(date, _)| {
+                    let year = self.archive_year(date.year());
+                    if current_year != Some(year) {
+                        current_year = Some(year);
+                        shown = 0;
+                    }
+                    shown += 1;
+                    limit.map_or(true, |limit| shown <= limit)
+                }
+            })
+            .map(|(&date, page)| {
+                Ok(IndexMonth {
+                    month: (date.year(), date.month()),
+                    markup: render_card(
+                        &renderer,
+                        &format_day(date, true),
+                        page,
+                        self.excerpt(page),
+                        self.config.rich_descriptions,
+                        self.config.display_date,
+                        true,
+                    )?
+                    .into_string(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .coalesce(|a, b| {
+                if a.month == b.month {
+                    Ok(IndexMonth {
+                        month: a.month,
+                        markup: a.markup + &b.markup,
+                    })
+                } else {
+                    Err((a, b))
+                }
+            })
+            .map(
+                |IndexMonth {
+                     month: (year, month),
+                     markup,
+                 }| IndexYear {
+                    year: self.archive_year(year),
+                    markup: (html! {
+                        section {
+                            h2 {
+                                a href=(format_month(year, month)) {
+                                    (months::name(month, &self.config.locale.lang))
+                                    // Months of several years share the section of collapsed years
+                                    @if let ArchiveYear::Before(_) = self.archive_year(year) {
+                                        " " (year)
+                                    }
+                                }
+                            }
+                            (PreEscaped(markup))
+                        }
+                    })
+                    .into_string(),
+                },
+            )
+            .coalesce(|a, b| {
+                if a.year == b.year {
+                    Ok(IndexYear {
+                        year: a.year,
+                        markup: a.markup + &b.markup,
+                    })
+                } else {
+                    Err((a, b))
+                }
+            })
+            .map(|IndexYear { year, markup }| {
+                let entries = entries_per_year.get(&year).copied().unwrap_or_default();
+
+                html! {
+                    section {
+                        h1 {
+                            a href=(year.path()) {
+                                (year)
+                            }
+                        }
+                        (PreEscaped(markup))
+                        @if limit.map_or(false, |limit| entries > limit) {
+                            a class="see-all" href=(year.path()) {
+                                "See all " (entries) " entries "
+                                @match year {
+                                    ArchiveYear::Year(year) => { "from " (year) }
+                                    ArchiveYear::Before(year) => { "before " (year) }
+                                }
+                            }
+                        }
+                    }
+                }
+            });
+
+        let description =
+            truncate_description(&self.config.description, self.config.meta_description_max);
+
+        // Describes the diary as a whole to search engines, a SearchAction would go here once the
+        // diary has a search page to point it to
+        let mut website = serde_json::json!({
+            "@context": "https://schema.org",
+            "@type": "WebSite",
+            "name": self.config.name,
+            "description": self.config.description,
+        });
+        if let Some(url) = &self.config.url {
+            website["url"] = url.as_str().into();
+        }
+        // A </script> inside of any of the strings would end the script early
+        let website = serde_json::to_string(&website)?.replace("</", "<\\/");
+
+        let head = Head {
+            description: Some(description.as_str()),
+            robots: self.config.robots.index.as_deref(),
+            image: self.config.cover.as_deref(),
+            website: true,
+            extra: html! {
+                script type="application/ld+json" { (PreEscaped(website)) }
+            },
+            ..Head::new(&self.config.name, "", &self.config.locale)
+        };
+
+        let markup = html! {
+            (DOCTYPE)
+            html lang=(self.config.locale.lang) {
+                (self.render_head(&head)?)
+                body {
+                    header {
+                        (self.render_header("")?)
+                    }
+                    main {
+                        @for year in years {
+                            (year)
+                        }
+                    }
+                    footer {
+                        (self.footer)
+                    }
+                }
+            }
+        };
+
+        let mut path = self.output.join("index");
+        path.set_extension("html");
+
+        Ok(tokio::spawn(self.writer.write(path, markup.into_string())))
+    }
+
+    pub fn generate_atom_feed(&self) -> Result<JoinHandle<Result<()>>> {
+        let url = if let Some(url) = self.config.get_atom_id() {
+            url
+        } else {
+            warn!("Cannot generate Atom feed without a unique URL to identify it");
+            return Ok(tokio::spawn(async { Ok(()) }));
+        };
+
+        let publications = self.feed_publications();
+
+        let feed = match self.render_atom_feed(
+            url,
+            &self.config.name,
+            url,
+            FEED_FILE,
+            FeedOrder::Published,
+            publications,
+        )? {
+            Some(feed) => feed,
+            None => return Ok(tokio::spawn(async { Ok(()) })),
+        };
+
+        let path = self.output.join(FEED_FILE);
+        Ok(tokio::spawn(self.writer.write(path, feed)))
+    }
+
+    /// Generate a JSON Feed of the same pages as the main Atom feed for readers that only support
+    /// JSON Feed, newest first
+    pub fn generate_json_feed(&self) -> Result<JoinHandle<Result<()>>> {
+        let url = if let Some(url) = self.config.get_atom_id() {
+            url
+        } else {
+            warn!("Cannot generate JSON Feed without a unique URL to identify it");
+            return Ok(tokio::spawn(async { Ok(()) }));
+        };
+
+        let publications = self
+            .feed_publications()
+            .filter_map(|(id, page)| {
+                page.properties
+                    .published
+                    .date
+                    .as_ref()
+                    .map(|date| (start_datetime(date), id, page))
+            })
+            .sorted_unstable_by_key(|(published, _, _)| Reverse(*published))
+            .collect::<Vec<_>>();
+        info!(
+            msg = "Generating JSON Feed",
+            path = JSON_FEED_FILE,
+            count = publications.len()
+        );
+        // Like the Atom feed there's no feed until something is published
+        if publications.is_empty() {
+            return Ok(tokio::spawn(async { Ok(()) }));
+        }
+
+        let renderer = HtmlRenderer {
+            heading_anchors: HeadingAnchors::None,
+            current_pages: publications.iter().map(|(_, _, page)| page.id).collect(),
+            link_map: &self.link_map,
+            downloadables: &self.downloadables,
+        };
+
+        let items = publications
+            .into_iter()
+            .map(|(published, id, page)| {
+                let updated = OffsetDateTime::parse(&page.last_edited_time, &Rfc3339)?;
+                let item = self.render_feed_item(url, id, page, &renderer)?;
+
+                Ok(json_feed::Item {
+                    id: item.url.to_string(),
+                    url: item.url.into(),
+                    title: page.properties.title().plain_text(),
+                    content_html: item.content.into_string(),
+                    summary: item.summary_text,
+                    tags: page
+                        .properties
+                        .tags()
+                        .into_iter()
+                        .map(String::from)
+                        .collect(),
+                    image: item.image,
+                    date_published: published.format(&Rfc3339)?,
+                    date_modified: updated.format(&Rfc3339)?,
+                    language: page.properties.locale().map(|locale| locale.lang),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // Unlike Atom, JSON Feed requires its images to be absolute URLs
+        let absolute = |path: &Option<String>| {
+            path.as_deref()
+                .map(|path| url.join(path).map(String::from))
+                .transpose()
+        };
+        let feed = json_feed::Feed {
+            version: json_feed::VERSION,
+            title: &self.config.name,
+            home_page_url: url.as_str(),
+            feed_url: url.join(JSON_FEED_FILE)?.into(),
+            description: &self.config.description,
+            icon: absolute(&self.config.cover)?,
+            favicon: absolute(&self.config.icon)?,
+            authors: self
+                .config
+                .author
+                .iter()
+                .map(|author| json_feed::Author {
+                    name: &author.name,
+                    url: author.url.as_ref().map(|url| url.to_string()),
+                })
+                .collect(),
+            language: &self.config.locale.lang,
+            items,
+        };
+
+        let path = self.output.join(JSON_FEED_FILE);
+        Ok(tokio::spawn(
+            self.writer.write(path, serde_json::to_string(&feed)?),
+        ))
+    }
+
+    /// The pages that go into the main Atom feeds according to `feed_sources`
+    fn feed_publications(&self) -> impl Iterator<Item = (UrlOrDate, &Page<Properties>)> {
+        let sources = self.config.feed_sources;
+
+        self.listed_articles()
+            .filter(move |_| sources != FeedSources::Entries)
+            .map(|(url, page)| (UrlOrDate::Url(url.to_owned()), page))
+            .chain(
+                self.listed_entries()
+                    .filter(move |_| sources != FeedSources::Articles)
+                    .map(|(date, page)| (UrlOrDate::Date(*date), page)),
+            )
+    }
+
+    /// Generate an Atom feed for every year containing only the entries of that year, years
+    /// before archive_before are left without one
+    pub fn generate_year_feeds(&self) -> Result<JoinHandle<Result<()>>> {
+        let url = if let Some(url) = self.config.get_atom_id() {
+            url
+        } else {
+            warn!("Cannot generate year Atom feeds without a unique URL to identify them");
+            return Ok(tokio::spawn(async { Ok(()) }));
+        };
+
+        let feeds = self
+            .listed_entries()
+            .group_by(|(date, _)| self.archive_year(date.year()))
+            .into_iter()
+            // Years collapsed by archive_before share a page without a feed of its own
+            .filter_map(|(year, entries)| match year {
+                ArchiveYear::Year(year) => Some((year, entries)),
+                ArchiveYear::Before(_) => None,
+            })
+            .map(|(year, entries)| {
+                let year_path = format_year(year);
+                let feed_path = format!("{}/{}", year_path, FEED_FILE);
+                let title = format!("{} - {}", self.config.name, year);
+
+                let publications = entries.map(|(date, page)| (UrlOrDate::Date(*date), page));
+                let feed = self.render_atom_feed(
+                    &url.join(&year_path)?,
+                    &title,
+                    url,
+                    &feed_path,
+                    FeedOrder::Published,
+                    publications,
+                )?;
+
+                Ok(feed.map(|feed| (self.output.join(feed_path), feed)))
+            })
+            .filter_map(Result::transpose)
+            .map_ok(|(path, feed)| self.writer.write(path, feed))
+            .collect::<Result<FuturesUnordered<_>>>()?;
+
+        Ok(tokio::spawn(feeds.try_collect::<()>()))
+    }
+
+    /// Generate an Atom feed for every month containing only the entries of that month, months
+    /// without entries don't get a feed
+    pub fn generate_month_feeds(&self) -> Result<JoinHandle<Result<()>>> {
+        let url = if let Some(url) = self.config.get_atom_id() {
+            url
+        } else {
+            warn!("Cannot generate month Atom feeds without a unique URL to identify them");
+            return Ok(tokio::spawn(async { Ok(()) }));
+        };
+
+        let feeds = self
+            .listed_entries()
+            .group_by(|(date, _)| (date.year(), date.month()))
+            .into_iter()
+            .map(|((year, month), entries)| {
+                let month_path = format_month(year, month);
+                let feed_path = format!("{}/{}", month_path, FEED_FILE);
+                let title = format!(
+                    "{} - {} {}",
+                    self.config.name,
+                    months::name(month, &self.config.locale.lang),
+                    year
+                );
+
+                let publications = entries.map(|(date, page)| (UrlOrDate::Date(*date), page));
+                let feed = self.render_atom_feed(
+                    &url.join(&month_path)?,
+                    &title,
+                    url,
+                    &feed_path,
+                    FeedOrder::Published,
+                    publications,
+                )?;
+
+                Ok(feed.map(|feed| (self.output.join(feed_path), feed)))
+            })
+            .filter_map(Result::transpose)
+            .map_ok(|(path, feed)| self.writer.write(path, feed))
+            .collect::<Result<FuturesUnordered<_>>>()?;
+
+        Ok(tokio::spawn(feeds.try_collect::<()>()))
+    }
+
+    /// Generate an Atom feed of every entry and article ordered by when they were last edited for
+    /// readers who want to follow revisions rather than new publications
+    pub fn generate_updates_feed(&self) -> Result<JoinHandle<Result<()>>> {
+        const UPDATES_FILE: &str = "updates.xml";
+
+        let url = if let Some(url) = self.config.get_atom_id() {
+            url
+        } else {
+            warn!("Cannot generate updates Atom feed without a unique URL to identify it");
+            return Ok(tokio::spawn(async { Ok(()) }));
+        };
+
+        let publications = self.feed_publications();
+
+        let feed = match self.render_atom_feed(
+            &url.join(UPDATES_FILE)?,
+            &format!("Updates - {}", self.config.name),
+            url,
+            UPDATES_FILE,
+            FeedOrder::Updated,
+            publications,
+        )? {
+            Some(feed) => feed,
+            None => return Ok(tokio::spawn(async { Ok(()) })),
+        };
+
+        Ok(tokio::spawn(
+            self.writer.write(self.output.join(UPDATES_FILE), feed),
+        ))
+    }
+
+    /// Render what the Atom feed and JSON Feed show of a published page so that both cut its
+    /// content and pick its summary the same way
+    fn render_feed_item(
+        &self,
+        url: &reqwest::Url,
+        id: UrlOrDate,
+        page: &Page<Properties>,
+        renderer: &HtmlRenderer,
+    ) -> Result<FeedItem> {
+        let blocks = renderer
+            .render_blocks(&page.children, None, 0)
+            .collect::<Result<Vec<_>>>()?;
+
+        let path = match id {
+            UrlOrDate::Url(path) => path,
+            UrlOrDate::Date(date) => format_day(date, true),
+        };
+        let item_url = url.join(&path)?;
+
+        // Cutting between blocks instead of inside of them keeps the content balanced
+        let mut chars = 0;
+        let kept = match self.config.feed_content_max_chars {
+            Some(max) => blocks
+                .iter()
+                .take_while(|block| {
+                    chars += block.0.chars().count();
+                    chars <= max
+                })
+                .count(),
+            None => blocks.len(),
+        };
+        let truncated = kept < blocks.len();
+        let cover = self.render_cover(page, renderer)?;
+        let image = self
+            .download_cover(page)?
+            .map(|src| url.join(&src))
+            .transpose()?
+            .map(String::from);
+
+        let (summary, summary_text) = match (page.properties.feed_summary(), self.excerpt(page)) {
+            (Some(feed_summary), _) => (atom::Summary::Text(feed_summary.clone()), feed_summary),
+            (None, Some(excerpt)) => {
+                let mut text = String::new();
+                push_plain_text(excerpt, &mut text);
+                let summary = html! {
+                    @for block in renderer.render_blocks(excerpt, None, 0) {
+                        (block?)
+                    }
+                };
+
+                (atom::Summary::Html(summary), text.trim().to_string())
+            }
+            (None, None) => {
+                let description = page.properties.description.rich_text.plain_text();
+                (atom::Summary::Text(description.clone()), description)
+            }
+        };
+
+        Ok(FeedItem {
+            summary,
+            summary_text,
+            image,
+            content: html! {
+                @if let Some(cover) = cover {
+                    (cover)
+                }
+                @for block in blocks.into_iter().take(kept) {
+                    (block)
+                }
+                @if truncated {
+                    p {
+                        a href=(item_url) { "Read more" }
+                    }
+                }
+            },
+            url: item_url,
+        })
+    }
+
+    /// Render an Atom feed of the published pages out of `publications` identified by `id`, or
+    /// nothing if none of them are published
+    fn render_atom_feed<'a, I>(
+        &self,
+        id: &reqwest::Url,
+        title: &str,
+        url: &reqwest::Url,
+        feed_path: &str,
+        order: FeedOrder,
+        publications: I,
+    ) -> Result<Option<String>>
+    where
+        I: Iterator<Item = (UrlOrDate, &'a Page<Properties>)>,
+    {
+        let authors = if let Some(author) = &self.config.author {
+            vec![atom::Person {
+                name: &author.name,
+                email: author.email.as_deref(),
+                url: author.url.clone(),
+            }]
+        } else {
+            Vec::new()
+        };
+
+        let publications_ordered = publications
+            .filter_map(|(id, page)| {
+                page.properties
+                    .published
+                    .date
+                    .as_ref()
+                    .map(|date| (start_datetime(date), id, page))
+            })
+            .map(|(published, id, page)| {
+                let updated = OffsetDateTime::parse(&page.last_edited_time, &Rfc3339)?;
+                Ok((published, updated, id, page))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let publications_ordered = match order {
+            FeedOrder::Published => publications_ordered
+                .into_iter()
+                .sorted_unstable_by_key(|page| page.0)
+                .collect::<Vec<_>>(),
+            FeedOrder::Updated => publications_ordered
+                .into_iter()
+                .sorted_unstable_by_key(|page| Reverse(page.1))
+                .collect::<Vec<_>>(),
+        };
+        info!(
+            msg = "Generating Atom feed",
+            path = feed_path,
+            count = publications_ordered.len()
+        );
+
+        let last_change = publications_ordered
+            .iter()
+            .map(|(published, updated, _, _)| match order {
+                FeedOrder::Published => *published,
+                FeedOrder::Updated => *updated,
+            })
+            .max();
+        let last_change = if let Some(time) = last_change {
+            time
+        } else {
+            return Ok(None);
+        };
+
+        let renderer = HtmlRenderer {
+            heading_anchors: HeadingAnchors::None,
+            current_pages: publications_ordered
+                .iter()
+                .map(|(_, _, _, page)| page.id)
+                .collect(),
+            link_map: &self.link_map,
+            downloadables: &self.downloadables,
+        };
+
+        let entries = publications_ordered
+            .into_iter()
+            .map(|(time, updated, id, page)| {
+                let item = self.render_feed_item(url, id, page, &renderer)?;
+
+                Ok(atom::Entry {
+                    title: page.properties.name.title.plain_text(),
+                    updated,
+                    published: time,
+                    summary: item.summary,
+                    categories: page
+                        .properties
+                        .tags()
+                        .into_iter()
+                        .map(String::from)
+                        .collect(),
+                    lang: page.properties.locale().map(|locale| locale.lang),
+                    enclosure: item.image,
+                    content: item.content,
+                    url: item.url.into(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let feed = atom::Feed {
+            title,
+            url: id,
+            feed_url: url.join(feed_path)?,
+            last_changed: last_change,
+            authors,
+            generator: self.config.feed_generator.then(|| atom::Generator {
+                value: DIARY_GENERATOR,
+                uri: REPOSITORY,
+                version: VERSION,
+            }),
+            icon: self.config.icon.as_deref(),
+            cover: self.config.cover.as_deref(),
+            lang: &self.config.locale.lang,
+            entries,
+        };
+
+        Ok(Some(feed.render().into_string()))
+    }
+
+    /// Generate a page for every article as well as for every draft when building in drafts mode
+    pub fn generate_article_pages(&self) -> Result<JoinHandle<Result<()>>> {
+        info!(
+            msg = "Generating articles",
+            count = self.article_pages.len(),
+            drafts = self.drafts.len()
+        );
+
+        let paging_chain = self.paging_chain();
+
+        let articles = self
+            .article_pages
+            .iter()
+            .map(|(url, page)| (url, page, false))
+            .chain(self.drafts.iter().map(|(path, page)| (path, page, true)))
+            .map(|(url, page, is_draft)| {
+                self.render_page(page, || {
+                    let renderer = HtmlRenderer {
+                        heading_anchors: HeadingAnchors::After("#"),
+                        current_pages: HashSet::from([page.id]),
+                        link_map: &self.link_map,
+                        downloadables: &self.downloadables,
+                    };
+
+                    let blocks = self.render_content(&renderer, page);
+                    let (prev_page, next_page) = neighbours(&paging_chain, page);
+
+                    let title = format!(
+                        "{} - {}",
+                        page.properties.title().plain_text(),
+                        self.config.name
+                    );
+                    let description = truncate_description(
+                        &page.properties.description.rich_text.plain_text(),
+                        self.config.meta_description_max,
+                    );
+                    // Drafts and unlisted pages are never indexed regardless of the config
+                    let robots = if is_draft || page.properties.unlisted() {
+                        Some("noindex")
+                    } else {
+                        self.config.robots.entries.as_deref()
+                    };
+
+                    let cover = self.download_cover(page)?;
+                    let icon = self.page_icon(page)?;
+                    let og_image = self.og_image(page, cover, url)?;
+                    let locale = page.properties.locale();
+                    let locale = locale.as_ref().unwrap_or(&self.config.locale);
+
+                    let head = Head {
+                        description: (!description.is_empty()).then(|| description.as_str()),
+                        robots,
+                        image: og_image.as_deref(),
+                        canonical: page.properties.canonical(),
+                        icon: icon.as_deref(),
+                        extra: self.render_article_og(page),
+                        ..Head::new(&title, url, locale)
+                    };
+
+                    let markup = html! {
+                        (DOCTYPE)
+                        html lang=(locale.lang) {
+                            (self.render_head(&head)?)
+                            body {
+                                header {
+                                    (self.render_header(url)?)
+                                }
+                                main id="content" {
+                                    (self.render_article(&renderer, page, blocks)?)
+                                    (render_related(&renderer, &self.link_map, &self.related(page), self.config.rich_descriptions, self.config.display_date)?)
+                                    (render_paging_links(&renderer, &self.config.paging, self.config.display_date, None, prev_page, next_page)?)
+                                    @if let Some(comments) = &self.config.comments {
+                                        (PreEscaped(comments))
+                                    }
+                                    @if self.config.back_to_top {
+                                        a class="back-to-top" href="#content" { "Back to top" }
+                                    }
+                                }
+                                footer {
+                                    (self.footer)
+                                }
+                            }
+                        }
+                    };
+
+                    let mut path = self.output.join(url);
+                    path.set_extension("html");
+                    Ok(Some((path, markup)))
+                })
+            })
+            .map_ok(|option| self.write_if_not_empty(option))
+            .collect::<Result<FuturesUnordered<_>>>()?;
+
+        Ok(tokio::spawn(articles.try_collect::<()>()))
+    }
+
+    /// Only generated if there are articles unless `articles_page` in the config says otherwise
+    pub fn generate_articles_page(&self) -> Result<JoinHandle<Result<()>>> {
+        if !self.has_articles_page() {
+            return Ok(tokio::spawn(async { Ok(()) }));
+        }
+
+        let renderer = HtmlRenderer {
+            heading_anchors: HeadingAnchors::After("#"),
+            current_pages: HashSet::from([]),
+            link_map: &self.link_map,
+            downloadables: &self.downloadables,
+        };
+
+        let articles = self
+            .listed_articles()
+            .map(|(url, page)| {
+                render_card(
+                    &renderer,
+                    url,
+                    page,
+                    self.excerpt(page),
+                    self.config.rich_descriptions,
+                    self.config.display_date,
+                    true,
+                )
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let title = format!("Articles - {}", self.config.name);
+        let count = self.listed_articles().count();
+        let description = truncate_description(
+            &self
+                .config
+                .articles_description
+                .replace("{name}", &self.config.name)
+                .replace("{articles}", &counted(count, "article", "articles"))
+                .replace("{count}", &count.to_string()),
+            self.config.meta_description_max,
+        );
+
+        // TODO: One could generate a custom image for this page once
+        let head = Head {
+            description: Some(description.as_str()),
+            website: true,
+            ..Head::new(&title, "articles", &self.config.locale)
+        };
+
+        let markup = html! {
+            (DOCTYPE)
+            html lang=(self.config.locale.lang) {
+                (self.render_head(&head)?)
+                body {
+                    header {
+                        (self.render_header("articles")?)
+                    }
+                    main {
+                        @for article in articles {
+                            (article)
+                        }
+                    }
+                    footer {
+                        (self.footer)
+                    }
+                }
+            }
+        };
+
+        let mut path = self.output.join("articles");
+        path.set_extension("html");
+        Ok(tokio::spawn(self.writer.write(path, markup.into_string())))
+    }
+
+    /// Generate a flat list of every entry in chronological order at /all, meant to be searched
+    /// through and scraped rather than browsed
+    /// Only generated if `all_page` is enabled in the config
+    pub fn generate_all_page(&self) -> Result<JoinHandle<Result<()>>> {
+        if !self.config.all_page {
+            return Ok(tokio::spawn(async { Ok(()) }));
+        }
+
+        let title = format!("All entries - {}", self.config.name);
+
+        let head = Head {
+            katex: false,
+            ..Head::new(&title, "all", &self.config.locale)
+        };
+
+        let markup = html! {
+            (DOCTYPE)
+            html lang=(self.config.locale.lang) {
+                (self.render_head(&head)?)
+                body {
+                    header {
+                        (self.render_header("all")?)
+                    }
+                    main {
+                        h1 { "All entries" }
+                        ul class="all-entries" {
+                            @for (&date, page) in self.listed_entries() {
+                                li {
+                                    time datetime=(date.format(HTML_FORMAT)?) {
+                                        (date.format(HTML_FORMAT)?)
+                                    }
+                                    " "
+                                    a href=(format_day(date, true)) {
+                                        (page.properties.title().plain_text())
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    footer {
+                        (self.footer)
+                    }
+                }
+            }
+        };
+
+        let mut path = self.output.join("all");
+        path.set_extension("html");
+        Ok(tokio::spawn(self.writer.write(path, markup.into_string())))
+    }
+
+    /// Generate a page listing all the ways to follow the diary: its feed and any fediverse or
+    /// newsletter links in the config
+    /// Skipped if there's no way to follow the diary
+    pub fn generate_subscribe_page(&self) -> Result<JoinHandle<Result<()>>> {
+        let feed_url = self
+            .config
+            .get_atom_id()
+            .map(|url| url.join(FEED_FILE))
+            .transpose()?;
+        let json_feed_url = self
+            .config
+            .get_atom_id()
+            .map(|url| url.join(JSON_FEED_FILE))
+            .transpose()?;
+
+        if !self.has_subscribe_page() {
+            return Ok(tokio::spawn(async { Ok(()) }));
+        }
+
+        let title = format!("Subscribe - {}", self.config.name);
+
+        let head = Head::new(&title, "subscribe", &self.config.locale);
+
+        let markup = html! {
+            (DOCTYPE)
+            html lang=(self.config.locale.lang) {
+                (self.render_head(&head)?)
+                body {
+                    header {
+                        (self.render_header("subscribe")?)
+                    }
+                    main {
+                        h1 { "Subscribe" }
+                        ul {
+                            @if let Some(feed_url) = &feed_url {
+                                li {
+                                    a href=(feed_url) type="application/atom+xml" { "Atom feed" }
+                                }
                             }
-                            main {
-                                @for (page, blocks) in rendered_pages {
-                                    (self.render_article(&renderer, page, blocks)?)
+                            @if let Some(json_feed_url) = &json_feed_url {
+                                li {
+                                    a href=(json_feed_url) type="application/feed+json" { "JSON Feed" }
                                 }
                             }
-                            footer {
-                                (self.footer)
+                            @if let Some(fediverse) = &self.config.fediverse {
+                                li {
+                                    a href=(fediverse) rel="me" { "Fediverse" }
+                                }
+                            }
+                            @if let Some(newsletter) = &self.config.newsletter {
+                                li {
+                                    a href=(newsletter) { "Newsletter" }
+                                }
                             }
                         }
                     }
-                };
-
-                let mut path = self.directory.join(EXPORT_DIR).join(path);
-                path.set_extension("html");
-                Ok(Some((path, markup)))
-            })
-            .map_ok(Self::write_if_not_empty)
-            .collect::<Result<FuturesUnordered<_>>>()?;
+                    footer {
+                        (self.footer)
+                    }
+                }
+            }
+        };
 
-        Ok(tokio::spawn(months.try_collect::<()>()))
+        let mut path = self.output.join("subscribe");
+        path.set_extension("html");
+        Ok(tokio::spawn(self.writer.write(path, markup.into_string())))
     }
 
-    pub fn generate_days(&self) -> Result<JoinHandle<Result<()>>> {
-        let days = self
-            .lookup_tree
-            .iter()
-            .map(|(date, page)| {
-                let renderer = HtmlRenderer {
-                    heading_anchors: HeadingAnchors::After("#"),
-                    current_pages: HashSet::from([page.id]),
-                    link_map: &self.link_map,
-                    downloadables: &self.downloadables,
-                };
+    /// Generate a page for every series listing its entries in chronological order
+    pub fn generate_series_pages(&self) -> Result<JoinHandle<Result<()>>> {
+        let renderer = HtmlRenderer {
+            heading_anchors: HeadingAnchors::After("#"),
+            current_pages: HashSet::new(),
+            link_map: &self.link_map,
+            downloadables: &self.downloadables,
+        };
 
-                let blocks = renderer.render_blocks(&page.children, None, 1);
+        let series_pages = self
+            .series()
+            .into_iter()
+            .map(|(series, members)| {
+                let title = format!("{} - {}", series, self.config.name);
+                let path = format_series(&series);
 
-                let title = format!(
-                    "{} - {}",
-                    page.properties.title().plain_text(),
-                    self.config.name
-                );
-                let description = page
-                    .properties
-                    .description
-                    .rich_text
-                    .as_slice()
-                    .plain_text();
-
-                let prev_page = self
-                    .lookup_tree
-                    .range((Bound::Unbounded, Bound::Excluded(date)))
-                    .rev()
-                    .next();
-                let next_page = self
-                    .lookup_tree
-                    .range((Bound::Excluded(date), Bound::Unbounded))
-                    .next();
-
-                let cover = self.download_cover(page)?;
-                let path = format_day(*date, false);
+                let head = Head::new(&title, &path, &self.config.locale);
 
                 let markup = html! {
                     (DOCTYPE)
                     html lang=(self.config.locale.lang) {
-                        head {
-                            meta charset="utf-8";
-                            meta name="viewport" content="width=device-width, initial-scale=1";
-                            link rel="stylesheet" href="/katex/katex.min.css";
-                            title { (title) }
-                            @if !description.is_empty() {
-                                meta name="description" content=(description);
-                            }
-                            @if let Some(author) = &self.config.author {
-                                meta name="author" content=(author.name);
-                            }
-                            @if self.config.get_atom_id().is_some() {
-                                link rel="alternate" type="application/atom+xml" href="/feed.xml";
-                            }
-
-                            meta property="og:title" content=(title);
-                            @if !description.is_empty() {
-                                meta property="og:description" content=(description);
-                            }
-                            meta property="og:locale" content=(self.config.locale.locale);
-                            @if let Some(cover) = cover {
-                                meta property="og:image" content=(cover);
-                                meta name="twitter:card" content="summary_large_image";
-                            }
-                            @if let Some(url) = &self.config.url {
-                                meta property="og:url" content=(url.join(&path)?);
-                            }
-                            @if let Some(twitter_site) = &self.config.twitter.site {
-                                meta name="twitter:site" content=(twitter_site);
-                            }
-                            @if let Some(twitter_creator) = &self.config.twitter.creator {
-                                meta name="twitter:creator" content=(twitter_creator);
-                            }
-                            // TODO: Rest of OG meta properties
-
-                            (self.head)
-                        }
+                        (self.render_head(&head)?)
                         body {
                             header {
-                                (self.header)
+                                (self.render_header(&path)?)
                             }
                             main {
-                                (self.render_article(&renderer, page, blocks)?)
-                                (render_paging_links(&renderer, *date, prev_page, next_page)?)
+                                h1 { (series) }
+                                @for (&date, page) in members {
+                                    (render_card(
+                                        &renderer,
+                                        &format_day(date, true),
+                                        page,
+                                        self.excerpt(page),
+                                        self.config.rich_descriptions,
+                                        self.config.display_date,
+                                        true,
+                                    )?)
+                                }
                             }
                             footer {
                                 (self.footer)
@@ -639,27 +3874,19 @@ impl Generator {
                     }
                 };
 
-                let mut path = self.directory.join(EXPORT_DIR).join(path);
+                let mut path = self.output.join(path);
                 path.set_extension("html");
                 Ok(Some((path, markup)))
             })
-            .map_ok(Self::write_if_not_empty)
+            .map_ok(|option| self.write_if_not_empty(option))
             .collect::<Result<FuturesUnordered<_>>>()?;
 
-        Ok(tokio::spawn(days.try_collect::<()>()))
+        Ok(tokio::spawn(series_pages.try_collect::<()>()))
     }
 
-    pub fn generate_index_page(&self) -> Result<JoinHandle<Result<()>>> {
-        struct IndexMonth {
-            month: (i32, Month),
-            markup: String,
-        }
-
-        struct IndexYear {
-            year: i32,
-            markup: String,
-        }
-
+    /// Generate a page for every tag listing the entries and articles carrying it, and a tag cloud
+    /// at tags.html sized by and showing how many of them carry each tag
+    pub fn generate_tag_pages(&self) -> Result<JoinHandle<Result<()>>> {
         let renderer = HtmlRenderer {
             heading_anchors: HeadingAnchors::After("#"),
             current_pages: HashSet::new(),
@@ -667,124 +3894,46 @@ impl Generator {
             downloadables: &self.downloadables,
         };
 
-        let years = self
-            .lookup_tree
-            .iter()
-            .rev()
-            .map(|(&date, page)| IndexMonth {
-                month: (date.year(), date.month()),
-                markup: (html! {
-                    article {
-                        header {
-                            h3 {
-                                a href=(format_day(date, true)) {
-                                    (renderer.render_rich_text(page.properties.title()))
-                                }
-                            }
-                            (render_article_time(date).unwrap())
-                        }
-                        p {
-                            (page.properties.description.rich_text.plain_text())
-                        }
-                    }
-                })
-                .into_string(),
-            })
-            .coalesce(|a, b| {
-                if a.month == b.month {
-                    Ok(IndexMonth {
-                        month: a.month,
-                        markup: a.markup + &b.markup,
-                    })
-                } else {
-                    Err((a, b))
-                }
-            })
-            .map(
-                |IndexMonth {
-                     month: (year, month),
-                     markup,
-                 }| IndexYear {
-                    year,
-                    markup: (html! {
-                        section {
-                            h2 {
-                                a href=(format_month(year, month)) {
-                                    (month)
-                                }
-                            }
-                            (PreEscaped(markup))
-                        }
-                    })
-                    .into_string(),
-                },
-            )
-            .coalesce(|a, b| {
-                if a.year == b.year {
-                    Ok(IndexYear {
-                        year: a.year,
-                        markup: a.markup + &b.markup,
-                    })
-                } else {
-                    Err((a, b))
-                }
-            })
-            .map(|IndexYear { year, markup }| {
-                html! {
-                    section {
-                        h1 {
-                            a href=(format_year(year)) {
-                                (year)
-                            }
-                        }
-                        (PreEscaped(markup))
-                    }
-                }
-            });
+        let tags = self.tags();
+        if tags.is_empty() {
+            return Ok(tokio::spawn(async { Ok(()) }));
+        }
 
-        let markup = html! {
-            (DOCTYPE)
-            html lang=(self.config.locale.lang) {
-                head {
-                    meta charset="utf-8";
-                    meta name="viewport" content="width=device-width, initial-scale=1";
-                    meta name="description" content=(self.config.description);
-                    link rel="stylesheet" href="/katex/katex.min.css";
-                    title { (self.config.name) }
-                    @if let Some(author) = &self.config.author {
-                        meta name="author" content=(author.name);
-                    }
-                    @if self.config.get_atom_id().is_some() {
-                        link rel="alternate" type="application/atom+xml" href="/feed.xml";
-                    }
+        let (min, max) = tags
+            .values()
+            .map(Vec::len)
+            .minmax()
+            .into_option()
+            .unwrap_or_default();
 
-                    meta property="og:title" content=(self.config.name);
-                    meta property="og:description" content=(self.config.description);
-                    meta property="og:locale" content=(self.config.locale.locale);
-                    @if let Some(cover) = &self.config.cover {
-                        meta property="og:image" content=(cover);
-                        meta name="twitter:card" content="summary_large_image";
-                    }
-                    @if let Some(url) = &self.config.url {
-                        meta property="og:url" content=(url);
-                    }
-                    @if let Some(twitter_site) = &self.config.twitter.site {
-                        meta name="twitter:site" content=(twitter_site);
-                    }
-                    @if let Some(twitter_creator) = &self.config.twitter.creator {
-                        meta name="twitter:creator" content=(twitter_creator);
-                    }
-                    // TODO: Rest of OG meta properties
+        let head = |title: &str, path: &str| {
+            self.render_head(&Head::new(title, path, &self.config.locale))
+        };
 
-                    (self.head)
-                }
+        let title = format!("Tags - {}", self.config.name);
+        let cloud = html! {
+            (DOCTYPE)
+            html lang=(self.config.locale.lang) {
+                (head(&title, "tags")?)
                 body {
                     header {
-                        (self.header)
+                        (self.render_header("tags")?)
                     }
                     main {
-                        @for year in years {
-                            (year)
+                        h1 { "Tags" }
+                        div class="tag-cloud" {
+                            @for (tag, pages) in &tags {
+                                @let weight = tag_weight(pages.len(), min, max);
+                                span
+                                    class={ "tag-weight-" (weight) }
+                                    data-weight=(weight)
+                                    style={ "font-size: " (1.0 + 0.25 * (weight - 1) as f32) "em" } {
+                                    a href={ "/" (format_tag(tag)) } { (tag) }
+                                    " "
+                                    span class="tag-count" { (pages.len()) }
+                                }
+                                " "
+                            }
                         }
                     }
                     footer {
@@ -794,305 +3943,704 @@ impl Generator {
             }
         };
 
-        let mut path = self.directory.join(EXPORT_DIR).join("index");
-        path.set_extension("html");
+        let tag_pages = tags
+            .iter()
+            .map(|(tag, pages)| {
+                let title = format!("{} - {}", tag, self.config.name);
+                let path = format_tag(tag);
+
+                let markup = html! {
+                    (DOCTYPE)
+                    html lang=(self.config.locale.lang) {
+                        (head(&title, &path)?)
+                        body {
+                            header {
+                                (self.render_header(&path)?)
+                            }
+                            main {
+                                h1 { (tag) }
+                                @for page in pages {
+                                    (render_card(
+                                        &renderer,
+                                        &self.link_map[&page.id],
+                                        page,
+                                        self.excerpt(page),
+                                        self.config.rich_descriptions,
+                                        self.config.display_date,
+                                        true,
+                                    )?)
+                                }
+                            }
+                            footer {
+                                (self.footer)
+                            }
+                        }
+                    }
+                };
+
+                let mut path = self.output.join(path);
+                path.set_extension("html");
+                Ok(Some((path, markup)))
+            })
+            .chain(std::iter::once(Ok(Some((
+                self.output.join("tags.html"),
+                cloud,
+            )))))
+            .map_ok(|option| self.write_if_not_empty(option))
+            .collect::<Result<FuturesUnordered<_>>>()?;
 
-        Ok(tokio::spawn(write(path, markup.into_string())))
+        Ok(tokio::spawn(tag_pages.try_collect::<()>()))
     }
 
-    pub fn generate_atom_feed(&self) -> Result<JoinHandle<Result<()>>> {
-        const FEED_FILE: &str = "feed.xml";
+    /// Copy every font listed in the config from the fonts/ directory
+    pub fn generate_fonts(&self) -> JoinHandle<Result<()>> {
+        let source_dir = self.directory.join(FONTS_DIR);
+        let export_dir = self.output.join(FONTS_DIR);
 
-        let url = if let Some(url) = self.config.get_atom_id() {
+        let fonts = self
+            .config
+            .fonts
+            .iter()
+            .map(|font| {
+                let source = source_dir.join(&font.href);
+                let destination = export_dir.join(&font.href);
+
+                let copy = self.writer.copy(source.clone(), destination.clone());
+
+                async move {
+                    info!(msg = "Copying font", path = %destination.display());
+                    copy.await.with_context(|| {
+                        format!(
+                            "Failed to copy font {} listed in config.json",
+                            source.display()
+                        )
+                    })
+                }
+            })
+            .collect::<FuturesUnordered<_>>();
+
+        tokio::spawn(fonts.try_collect::<()>())
+    }
+
+    /// Generate a sitemap.xml listing every generated page, from Notion or from pages/, split into
+    /// multiple sitemaps referenced by a sitemap index when there are more URLs than a sitemap can
+    /// hold
+    /// Each page's lastmod is the latest time it or any of the entries it lists was edited
+    pub fn generate_sitemap(&self) -> Result<JoinHandle<Result<()>>> {
+        use sitemap::ChangeFrequency;
+
+        const SITEMAP_FILE: &str = "sitemap.xml";
+
+        let url = if let Some(url) = &self.config.url {
             url
         } else {
-            warn!("Cannot generate Atom feed without a unique URL to identify it");
+            warn!("Cannot generate sitemap without the URL of the diary");
             return Ok(tokio::spawn(async { Ok(()) }));
         };
 
-        let authors = if let Some(author) = &self.config.author {
-            vec![atom::Person {
-                name: &author.name,
-                email: None,
-                url: author.url.clone(),
-            }]
-        } else {
-            Vec::new()
+        fn last_edited(page: &Page<Properties>) -> Option<Date> {
+            OffsetDateTime::parse(&page.last_edited_time, &Rfc3339)
+                .ok()
+                .map(|time| time.date())
+        }
+
+        // Archives of years and months that come before the latest entry can no longer change
+        let latest_entry = self.listed_entries().next_back().map(|(&date, _)| date);
+        let archive = |path: String, is_old: bool, pages: Vec<&Page<Properties>>| {
+            Ok::<_, anyhow::Error>(sitemap::Url {
+                loc: url.join(&path)?,
+                lastmod: pages.into_iter().filter_map(last_edited).max(),
+                changefreq: if is_old {
+                    ChangeFrequency::Yearly
+                } else {
+                    ChangeFrequency::Monthly
+                },
+                priority: if is_old { 0.3 } else { 0.5 },
+            })
         };
 
-        enum UrlOrDate {
-            Url(String),
-            Date(Date),
+        let mut urls = vec![sitemap::Url {
+            loc: url.clone(),
+            lastmod: self
+                .listed_entries()
+                .filter_map(|(_, page)| last_edited(page))
+                .max(),
+            changefreq: ChangeFrequency::Daily,
+            priority: 1.0,
+        }];
+        if self.has_articles_page() {
+            urls.push(sitemap::Url {
+                loc: url.join("articles")?,
+                lastmod: self
+                    .listed_articles()
+                    .filter_map(|(_, page)| last_edited(page))
+                    .max(),
+                changefreq: ChangeFrequency::Weekly,
+                priority: 0.8,
+            });
         }
 
-        let publications_ordered = self
-            .article_pages
-            .iter()
-            .map(|(url, page)| (UrlOrDate::Url(url.to_owned()), page))
-            .chain(
-                self.lookup_tree
-                    .iter()
-                    .map(|(date, page)| (UrlOrDate::Date(*date), page)),
-            )
-            .filter_map(|(id, page)| {
-                page.properties.published.date.as_ref().map(|date| {
-                    let datetime = date.start.datetime();
-                    (datetime, id, page)
-                })
-            })
-            .sorted_unstable_by_key(|page| page.0)
-            .collect::<Vec<_>>();
+        let years = self
+            .listed_entries()
+            .group_by(|(date, _)| self.archive_year(date.year()));
+        for (year, entries) in &years {
+            let is_old = latest_entry.map_or(true, |latest| latest.year() > year.last_year());
+            let pages = entries.map(|(_, page)| page).collect();
+            urls.push(archive(year.path(), is_old, pages)?);
+        }
 
-        let last_publication = if let Some((time, _, _)) = publications_ordered.last() {
-            *time
-        } else {
-            return Ok(tokio::spawn(async { Ok(()) }));
-        };
+        let months = self
+            .listed_entries()
+            .group_by(|(date, _)| (date.year(), date.month()));
+        for ((year, month), entries) in &months {
+            let is_old = latest_entry.map_or(true, |latest| {
+                (latest.year(), u8::from(latest.month())) > (year, u8::from(month))
+            });
+            let pages = entries.map(|(_, page)| page).collect();
+            urls.push(archive(format_month(year, month), is_old, pages)?);
+        }
 
-        let renderer = HtmlRenderer {
-            heading_anchors: HeadingAnchors::None,
-            current_pages: publications_ordered
-                .iter()
-                .map(|(_, _, page)| page.id)
-                .collect(),
-            link_map: &self.link_map,
-            downloadables: &self.downloadables,
-        };
+        for (&date, page) in self.listed_entries() {
+            urls.push(sitemap::Url {
+                loc: url.join(&format_day(date, false))?,
+                lastmod: last_edited(page),
+                changefreq: ChangeFrequency::Monthly,
+                priority: 0.7,
+            });
+        }
+
+        for (path, page) in self.listed_articles() {
+            urls.push(sitemap::Url {
+                loc: url.join(path)?,
+                lastmod: last_edited(page),
+                changefreq: ChangeFrequency::Monthly,
+                priority: 0.7,
+            });
+        }
+
+        for (series, entries) in self.series() {
+            urls.push(sitemap::Url {
+                loc: url.join(&format_series(&series))?,
+                lastmod: entries
+                    .into_iter()
+                    .filter_map(|(_, page)| last_edited(page))
+                    .max(),
+                changefreq: ChangeFrequency::Weekly,
+                priority: 0.5,
+            });
+        }
+
+        let tags = self.tags();
+        if !tags.is_empty() {
+            urls.push(sitemap::Url {
+                loc: url.join("tags")?,
+                lastmod: tags
+                    .values()
+                    .flatten()
+                    .copied()
+                    .filter_map(last_edited)
+                    .max(),
+                changefreq: ChangeFrequency::Weekly,
+                priority: 0.3,
+            });
+        }
+        for (tag, pages) in tags {
+            urls.push(sitemap::Url {
+                loc: url.join(&format_tag(tag))?,
+                lastmod: pages.into_iter().filter_map(last_edited).max(),
+                changefreq: ChangeFrequency::Weekly,
+                priority: 0.5,
+            });
+        }
+
+        if self.config.all_page {
+            urls.push(sitemap::Url {
+                loc: url.join("all")?,
+                lastmod: self
+                    .listed_entries()
+                    .filter_map(|(_, page)| last_edited(page))
+                    .max(),
+                changefreq: ChangeFrequency::Weekly,
+                priority: 0.3,
+            });
+        }
+        if self.has_subscribe_page() {
+            urls.push(sitemap::Url {
+                loc: url.join("subscribe")?,
+                lastmod: None,
+                changefreq: ChangeFrequency::Yearly,
+                priority: 0.3,
+            });
+        }
+
+        let url = url.clone();
+        let pages_dir = self.directory.join(&self.config.pages_dir);
+        let sitemap_exclude = self.config.sitemap_exclude.clone();
+        let sitemap_max_urls = self.config.sitemap_max_urls;
+        let export_dir = self.output.clone();
+        let writer = self.writer.clone();
+        Ok(tokio::spawn(async move {
+            // Independent pages only exist as files in pages/ and nothing tells when they changed
+            match independent_files(&pages_dir).await {
+                Ok(files) => {
+                    for (_, relative_path) in files {
+                        if let Some((name, _)) = IndependentPage::from_file_name(&relative_path) {
+                            urls.push(sitemap::Url {
+                                loc: url.join(name)?,
+                                lastmod: None,
+                                changefreq: ChangeFrequency::Monthly,
+                                priority: 0.3,
+                            });
+                        }
+                    }
+                }
+                Err(error)
+                    if error
+                        .downcast_ref::<io::Error>()
+                        .map_or(false, |error| error.kind() == io::ErrorKind::NotFound) => {}
+                Err(error) => return Err(error),
+            }
+
+            // Unlisted pages and drafts never make it this far but anything else can be excluded
+            // too
+            urls.retain(|url| {
+                sitemap_exclude
+                    .iter()
+                    .all(|glob| !sitemap::glob_matches(glob, url.loc.path()))
+            });
+
+            info!(msg = "Generating sitemap", count = urls.len());
+            if urls.len() <= sitemap_max_urls {
+                let sitemap = sitemap::UrlSet { urls: &urls };
+                return writer
+                    .write(
+                        export_dir.join(SITEMAP_FILE),
+                        sitemap.render().into_string(),
+                    )
+                    .await;
+            }
 
-        let entries = publications_ordered
-            .into_iter()
-            .map(|(time, id, page)| {
-                let blocks = renderer.render_blocks(&page.children, None, 0);
+            // Sites too large for a single sitemap get split into multiple sitemaps with
+            // sitemap.xml becoming an index of them
+            let mut sitemaps = Vec::new();
+            let writes = FuturesUnordered::new();
+            for (index, urls) in urls.chunks(sitemap_max_urls.max(1)).enumerate() {
+                let file = format!("sitemap-{}.xml", index + 1);
+                sitemaps.push(sitemap::Sitemap {
+                    loc: url.join(&file)?,
+                    lastmod: urls.iter().filter_map(|url| url.lastmod).max(),
+                });
+                writes.push(writer.write(
+                    export_dir.join(file),
+                    sitemap::UrlSet { urls }.render().into_string(),
+                ));
+            }
 
-                let path = match id {
-                    UrlOrDate::Url(path) => path,
-                    UrlOrDate::Date(date) => format_day(date, true),
-                };
-                let url = url.join(&path)?.into();
+            let index = sitemap::SitemapIndex { sitemaps };
+            writes.push(writer.write(export_dir.join(SITEMAP_FILE), index.render().into_string()));
 
-                Ok(atom::Entry {
-                    title: page.properties.name.title.plain_text(),
-                    url,
-                    updated: OffsetDateTime::parse(&page.last_edited_time, &Rfc3339)?,
-                    published: time,
-                    summary: page.properties.description.rich_text.plain_text(),
-                    content: html! {
-                        @for block in blocks {
-                            (block?)
-                        }
-                    },
+            writes.try_collect::<()>().await
+        }))
+    }
+
+    /// Generate calendar.json containing the number of entries of every day that has any, which
+    /// is useful for rendering a heatmap of the diary's activity
+    /// Every day also carries the date its week starts on according to `first_day_of_week` so
+    /// heatmaps can lay days out in weeks without needing to know the config
+    pub fn generate_calendar_data(&self) -> Result<JoinHandle<Result<()>>> {
+        #[derive(Serialize)]
+        struct CalendarDay {
+            date: String,
+            week: String,
+            count: usize,
+            url: String,
+        }
+
+        let first_day_of_week = self.config.first_day_of_week().number_days_from_monday();
+        let days = self
+            .listed_entries()
+            .map(|(&date, _)| {
+                let days_into_week =
+                    (date.weekday().number_days_from_monday() + 7 - first_day_of_week) % 7;
+
+                Ok(CalendarDay {
+                    date: date.format(HTML_FORMAT)?,
+                    week: (date - Duration::days(days_into_week.into())).format(HTML_FORMAT)?,
+                    // The diary currently only supports a single entry per day
+                    count: 1,
+                    url: format_day(date, true),
                 })
             })
             .collect::<Result<Vec<_>>>()?;
 
-        let feed = atom::Feed {
-            title: &self.config.name,
-            url,
-            feed_url: url.join(FEED_FILE)?,
-            last_changed: last_publication,
-            authors,
-            generator: atom::Generator {
-                value: DIARY_GENERATOR,
-                uri: REPOSITORY,
-                version: VERSION,
-            },
-            icon: self.config.icon.as_deref(),
-            cover: self.config.cover.as_deref(),
-            lang: &self.config.locale.lang,
-            entries,
-        };
-
-        let path = self.directory.join(EXPORT_DIR).join(FEED_FILE);
-        Ok(tokio::spawn(write(path, feed.render().into_string())))
+        let path = self.output.join("calendar.json");
+        Ok(tokio::spawn(
+            self.writer.write(path, serde_json::to_string(&days)?),
+        ))
     }
 
-    pub fn generate_article_pages(&self) -> Result<JoinHandle<Result<()>>> {
-        let articles = self
-            .article_pages
-            .iter()
-            .map(|(url, page)| {
-                let renderer = HtmlRenderer {
-                    heading_anchors: HeadingAnchors::After("#"),
-                    current_pages: HashSet::from([page.id]),
-                    link_map: &self.link_map,
-                    downloadables: &self.downloadables,
-                };
-
-                let blocks = renderer.render_blocks(&page.children, None, 1);
+    /// Generate latest.json listing the most recent entries with their title, URL, date and
+    /// description, small enough to be fetched by other websites to show what's new in the diary
+    pub fn generate_latest_json(&self) -> Result<JoinHandle<Result<()>>> {
+        #[derive(Serialize)]
+        struct LatestEntry {
+            title: String,
+            url: String,
+            date: String,
+            description: String,
+        }
 
-                let title = format!(
-                    "{} - {}",
-                    page.properties.title().plain_text(),
-                    self.config.name
-                );
-                let description = page
-                    .properties
-                    .description
-                    .rich_text
-                    .as_slice()
-                    .plain_text();
+        let latest = self
+            .listed_entries()
+            .rev()
+            .take(self.config.latest_entries)
+            .map(|(&date, page)| {
+                let path = format_day(date, true);
 
-                let cover = self.download_cover(page)?;
+                Ok(LatestEntry {
+                    title: page.properties.title().plain_text(),
+                    // Other websites need an absolute URL to link to the entry
+                    url: match &self.config.url {
+                        Some(url) => url.join(&path)?.into(),
+                        None => path,
+                    },
+                    date: date.format(HTML_FORMAT)?,
+                    description: page.properties.description.rich_text.plain_text(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
 
-                let markup = html! {
-                    (DOCTYPE)
-                    html lang=(self.config.locale.lang) {
-                        head {
-                            meta charset="utf-8";
-                            meta name="viewport" content="width=device-width, initial-scale=1";
-                            link rel="stylesheet" href="/katex/katex.min.css";
-                            title { (title) }
-                            @if !description.is_empty() {
-                                meta name="description" content=(description);
-                            }
-                            @if let Some(author) = &self.config.author {
-                                meta name="author" content=(author.name);
-                            }
-                            @if self.config.get_atom_id().is_some() {
-                                link rel="alternate" type="application/atom+xml" href="/feed.xml";
-                            }
+        let path = self.output.join("latest.json");
+        Ok(tokio::spawn(
+            self.writer.write(path, serde_json::to_string(&latest)?),
+        ))
+    }
 
-                            meta property="og:title" content=(title);
-                            @if !description.is_empty() {
-                                meta property="og:description" content=(description);
-                            }
-                            meta property="og:locale" content=(self.config.locale.locale);
-                            @if let Some(cover) = cover {
-                                meta property="og:image" content=(cover);
-                                meta name="twitter:card" content="summary_large_image";
-                            }
-                            @if let Some(site_url) = &self.config.url {
-                                meta property="og:url" content=(site_url.join(url)?);
-                            }
-                            @if let Some(twitter_site) = &self.config.twitter.site {
-                                meta name="twitter:site" content=(twitter_site);
-                            }
-                            @if let Some(twitter_creator) = &self.config.twitter.creator {
-                                meta name="twitter:creator" content=(twitter_creator);
-                            }
-                            // TODO: Rest of OG meta properties
+    /// Generate search-index.json with the title, URL, description and text of every entry and
+    /// article for client-side search libraries to index
+    pub fn generate_search_index(&self) -> Result<JoinHandle<Result<()>>> {
+        #[derive(Serialize)]
+        struct SearchEntry<'a> {
+            title: String,
+            url: &'a str,
+            description: String,
+            content: String,
+        }
 
-                            (self.head)
-                        }
-                        body {
-                            header {
-                                (self.header)
-                            }
-                            main {
-                                (self.render_article(&renderer, page, blocks)?)
-                            }
-                            footer {
-                                (self.footer)
-                            }
-                        }
-                    }
-                };
+        let index = self
+            .listed_entries()
+            .map(|(_, page)| page)
+            .chain(self.listed_articles().map(|(_, page)| page))
+            .map(|page| {
+                let mut content = String::new();
+                push_plain_text(&page.children, &mut content);
 
-                let mut path = self.directory.join(EXPORT_DIR).join(url);
-                path.set_extension("html");
-                Ok(Some((path, markup)))
+                SearchEntry {
+                    title: page.properties.title().plain_text(),
+                    url: &self.link_map[&page.id],
+                    description: page.properties.description.rich_text.plain_text(),
+                    content,
+                }
             })
-            .map_ok(Self::write_if_not_empty)
-            .collect::<Result<FuturesUnordered<_>>>()?;
+            .collect::<Vec<_>>();
+        info!(msg = "Generating search index", count = index.len());
 
-        Ok(tokio::spawn(articles.try_collect::<()>()))
+        let path = self.output.join("search-index.json");
+        Ok(tokio::spawn(
+            self.writer.write(path, serde_json::to_string(&index)?),
+        ))
     }
 
-    pub fn generate_articles_page(&self) -> Result<JoinHandle<Result<()>>> {
-        let renderer = HtmlRenderer {
-            heading_anchors: HeadingAnchors::After("#"),
-            current_pages: HashSet::from([]),
-            link_map: &self.link_map,
-            downloadables: &self.downloadables,
-        };
-
-        let articles = self.article_pages.iter().filter_map(|(url, page)| {
-            let published_date = page
-                .properties
-                .published
-                .date
-                .as_ref()
-                .map(|date| date.start.date());
+    /// Generate a pages.json listing every page of the diary along with the file it's written to for
+    /// tooling like link checkers and deploy diffing
+    /// Meant to be generated after everything else so that it describes what the other generators
+    /// produced
+    pub fn generate_manifest_json(&self) -> Result<JoinHandle<Result<()>>> {
+        #[derive(Serialize)]
+        struct ManifestPage {
+            path: String,
+            url: Option<String>,
+            #[serde(rename = "type")]
+            ty: &'static str,
+            title: String,
+            published: Option<String>,
+            last_edited: Option<String>,
+        }
 
-            let published_date = match published_date {
-                Some(published_date) => published_date,
-                _ => return None,
+        fn manifest_page<'a>(
+            url: Option<&reqwest::Url>,
+            link: &str,
+            ty: &'static str,
+            title: String,
+            pages: impl IntoIterator<Item = &'a Page<Properties>>,
+        ) -> Result<ManifestPage> {
+            let pages = pages.into_iter().collect::<Vec<_>>();
+            // Only a page on its own has a publication date, lists of pages were never published
+            let published = match pages.as_slice() {
+                [page] if matches!(ty, "entry" | "article") => page
+                    .properties
+                    .published
+                    .date
+                    .as_ref()
+                    .map(|date| start_date(date).format(HTML_FORMAT))
+                    .transpose()?,
+                _ => None,
             };
 
-            Some(html! {
-                article {
-                    header {
-                        h3 {
-                            a href=(url) {
-                                (renderer.render_rich_text(page.properties.title()))
-                            }
-                        }
-                        (render_article_time(published_date).unwrap())
-                    }
-                    p {
-                        (page.properties.description.rich_text.plain_text())
-                    }
-                }
+            Ok(ManifestPage {
+                path: if link.is_empty() || link.ends_with('/') {
+                    format!("{}index.html", link)
+                } else {
+                    format!("{}.html", link)
+                },
+                url: url.map(|url| url.join(link)).transpose()?.map(String::from),
+                ty,
+                title,
+                published,
+                last_edited: pages
+                    .into_iter()
+                    .map(|page| page.last_edited_time.as_str())
+                    .max()
+                    .map(str::to_string),
             })
-        });
+        }
 
-        let title = format!("Articles - {}", self.config.name);
+        let url = self.config.url.as_ref();
+        let listed_pages = || {
+            self.listed_entries()
+                .map(|(_, page)| page)
+                .chain(self.listed_articles().map(|(_, page)| page))
+        };
 
-        let markup = html! {
-            (DOCTYPE)
-            html lang=(self.config.locale.lang) {
-                head {
-                    meta charset="utf-8";
-                    meta name="viewport" content="width=device-width, initial-scale=1";
-                    link rel="stylesheet" href="/katex/katex.min.css";
-                    title { (title) }
-                    @if let Some(author) = &self.config.author {
-                        meta name="author" content=(author.name);
-                    }
-                    @if self.config.get_atom_id().is_some() {
-                        link rel="alternate" type="application/atom+xml" href="/feed.xml";
-                    }
+        let mut manifest = vec![manifest_page(
+            url,
+            "",
+            "index",
+            self.config.name.clone(),
+            self.listed_entries().map(|(_, page)| page),
+        )?];
+        if self.has_articles_page() {
+            manifest.push(manifest_page(
+                url,
+                "articles",
+                "articles",
+                "Articles".to_string(),
+                self.listed_articles().map(|(_, page)| page),
+            )?);
+        }
+        if self.config.all_page {
+            manifest.push(manifest_page(
+                url,
+                "all",
+                "all",
+                "All entries".to_string(),
+                self.listed_entries().map(|(_, page)| page),
+            )?);
+        }
+        if self.has_subscribe_page() {
+            manifest.push(manifest_page(
+                url,
+                "subscribe",
+                "subscribe",
+                "Subscribe".to_string(),
+                None,
+            )?);
+        }
 
-                    meta property="og:title" content=(title);
-                    // TODO: What's a good description for the articles page?
-                    // TODO: Rest of OG meta properties
-                    meta property="og:locale" content=(self.config.locale.locale);
-                    // TODO: One could generate a custom image for this page once
-                    @if let Some(url) = &self.config.url {
-                        meta property="og:url" content=(url.join("articles")?);
-                    }
-                    @if let Some(twitter_site) = &self.config.twitter.site {
-                        meta name="twitter:site" content=(twitter_site);
-                    }
-                    @if let Some(twitter_creator) = &self.config.twitter.creator {
-                        meta name="twitter:creator" content=(twitter_creator);
-                    }
+        let years = self
+            .listed_entries()
+            .group_by(|(date, _)| self.archive_year(date.year()));
+        for (year, entries) in &years {
+            manifest.push(manifest_page(
+                url,
+                &year.path(),
+                "year",
+                year.to_string(),
+                entries.map(|(_, page)| page),
+            )?);
+        }
+        let months = self
+            .listed_entries()
+            .group_by(|(date, _)| (date.year(), date.month()));
+        for ((year, month), entries) in &months {
+            manifest.push(manifest_page(
+                url,
+                &format_month(year, month),
+                "month",
+                format!("{} {}", months::name(month, &self.config.locale.lang), year),
+                entries.map(|(_, page)| page),
+            )?);
+        }
 
-                    (self.head)
-                }
-                body {
-                    header {
-                        (self.header)
-                    }
-                    main {
-                        @for article in articles {
-                            (article)
+        for (&date, page) in &self.lookup_tree {
+            manifest.push(manifest_page(
+                url,
+                &format_day(date, false),
+                "entry",
+                page.properties.title().plain_text(),
+                Some(page),
+            )?);
+        }
+        for (path, page) in &self.article_pages {
+            manifest.push(manifest_page(
+                url,
+                path,
+                "article",
+                page.properties.title().plain_text(),
+                Some(page),
+            )?);
+        }
+
+        for (series, entries) in self.series() {
+            manifest.push(manifest_page(
+                url,
+                &format_series(&series),
+                "series",
+                series,
+                entries.into_iter().map(|(_, page)| page),
+            )?);
+        }
+        let tags = self.tags();
+        if !tags.is_empty() {
+            manifest.push(manifest_page(
+                url,
+                "tags",
+                "tags",
+                "Tags".to_string(),
+                listed_pages().filter(|page| !page.properties.tags().is_empty()),
+            )?);
+        }
+        for (tag, pages) in tags {
+            manifest.push(manifest_page(
+                url,
+                &format_tag(tag),
+                "tag",
+                tag.to_string(),
+                pages,
+            )?);
+        }
+
+        let url = self.config.url.clone();
+        let pages_dir = self.directory.join(&self.config.pages_dir);
+        let path = self.output.join("pages.json");
+        let writer = self.writer.clone();
+        Ok(tokio::spawn(async move {
+            match independent_files(&pages_dir).await {
+                Ok(files) => {
+                    for (path, relative_path) in files {
+                        if let Some((name, _)) = IndependentPage::from_file_name(&relative_path) {
+                            let content = tokio::fs::read_to_string(&path).await?;
+                            let (front_matter, _) = FrontMatter::parse(&content);
+                            let title = front_matter
+                                .title
+                                .unwrap_or_else(|| IndependentPage::title(name));
+                            manifest.push(manifest_page(url.as_ref(), name, "page", title, None)?);
                         }
                     }
-                    footer {
-                        (self.footer)
-                    }
                 }
+                Err(error)
+                    if error
+                        .downcast_ref::<io::Error>()
+                        .map_or(false, |error| error.kind() == io::ErrorKind::NotFound) => {}
+                Err(error) => return Err(error),
+            }
+
+            writer.write(path, serde_json::to_string(&manifest)?).await
+        }))
+    }
+
+    /// Generate a humans.txt crediting the author of the diary
+    /// Only generated if `humans` is enabled in the config
+    pub fn generate_humans(&self) -> JoinHandle<Result<()>> {
+        if !self.config.humans {
+            return tokio::spawn(async { Ok(()) });
+        }
+
+        let mut humans = String::new();
+        if let Some(author) = &self.config.author {
+            humans.push_str("/* TEAM */\n");
+            humans.push_str(&format!("Author: {}\n", author.name));
+            if let Some(url) = &author.url {
+                humans.push_str(&format!("Site: {}\n", url));
             }
+            humans.push('\n');
+        }
+        humans.push_str("/* SITE */\n");
+        humans.push_str(&format!("Language: {}\n", self.config.locale.lang));
+        humans.push_str(&format!("Software: {} {}\n", DIARY_GENERATOR, VERSION));
+
+        let path = self.output.join("humans.txt");
+        tokio::spawn(self.writer.write(path, humans))
+    }
+
+    /// Generate a _headers file with the configured headers for hosts like Netlify and Cloudflare
+    /// Pages to send alongside the files of the diary
+    /// Only generated if `headers` is present in the config
+    pub fn generate_headers_file(&self) -> JoinHandle<Result<()>> {
+        const HEADERS_FILE: &str = "_headers";
+
+        let headers = if let Some(headers) = &self.config.headers {
+            headers
+        } else {
+            return tokio::spawn(async { Ok(()) });
         };
 
-        let mut path = self.directory.join(EXPORT_DIR).join("articles");
-        path.set_extension("html");
-        Ok(tokio::spawn(write(path, markup.into_string())))
+        let mut file = String::new();
+        for rule in &headers.rules {
+            file.push_str(&rule.path);
+            file.push('\n');
+            for (name, value) in &rule.headers {
+                file.push_str(&format!("  {}: {}\n", name, value));
+            }
+        }
+
+        tokio::spawn(self.writer.write(self.output.join(HEADERS_FILE), file))
+    }
+
+    /// Generate a .well-known/security.txt with the configured contact that expires after the
+    /// configured number of days from the time of the build
+    /// Only generated if `security` is present in the config
+    pub fn generate_security_txt(&self) -> Result<JoinHandle<Result<()>>> {
+        const SECURITY_FILE: &str = ".well-known/security.txt";
+
+        let security = if let Some(security) = &self.config.security {
+            security
+        } else {
+            return Ok(tokio::spawn(async { Ok(()) }));
+        };
+
+        let expires = self
+            .today
+            .checked_add(Duration::days(security.expires_in_days.get().into()))
+            .with_context(|| {
+                format!(
+                    "security.expires_in_days of {} is past the latest supported date",
+                    security.expires_in_days
+                )
+            })?
+            .midnight()
+            .assume_utc();
+
+        let mut security_txt = format!(
+            "Contact: {}\nExpires: {}\nPreferred-Languages: {}\n",
+            security.contact,
+            expires.format(&Rfc3339)?,
+            self.config.locale.lang
+        );
+        if let Some(url) = &self.config.url {
+            security_txt.push_str(&format!("Canonical: {}\n", url.join(SECURITY_FILE)?));
+        }
+
+        let path = self.output.join(SECURITY_FILE);
+        Ok(tokio::spawn(self.writer.write(path, security_txt)))
     }
 
     /// Generate independent pages by reading the pages/ directory and using each of the file in it
     /// as partial content for a page
+    /// Subdirectories are walked as well and keep their path, so `pages/about/team.html` becomes
+    /// `about/team.html`
+    /// Files ending in `.html` are wrapped in the layout as is, files ending in `.md` are rendered
+    /// from Markdown first and then wrapped, and every other file is copied over unchanged
     /// The pages titles currently depend on the file name as well
     /// These pages are called independent as they don't depend on Notion
     pub fn generate_independent_pages(&self) -> JoinHandle<Result<()>> {
@@ -1102,10 +4650,12 @@ impl Generator {
         let header = self.header.clone();
         let footer = self.footer.clone();
         let config = self.config.clone();
-        let directory = self.directory.clone();
+        let output = self.output.clone();
+        let writer = self.writer.clone();
+        let source_dir = self.directory.join(&self.config.pages_dir);
 
         tokio::spawn(async move {
-            let files = ReadDirStream::new(tokio::fs::read_dir("pages").await?);
+            let files = independent_files(&source_dir).await?;
 
             // We do this so that the inner futures in `.and_then` don't take ownership of these
             // causing them to be unusable by subsequent calls to `.and_then`
@@ -1113,82 +4663,66 @@ impl Generator {
             let header_ref = &header;
             let footer_ref = &footer;
             let config_ref = &config;
-            let directory_ref = &directory;
-
-            files
-                .map(|result| {
-                    result.context(
-                        "Failed to read file while recursively generating independent pages",
-                    )
-                })
-                .and_then(|entry| async move {
-                    let file_type = entry.file_type().await?;
-                    let path = entry.path();
-
-                    if !file_type.is_file() {
-                        bail!(
-                            "pages/ must only contain HTML files. {} which is not a file.",
-                            path.display(),
-                        );
-                    }
+            let output_ref = &output;
+            let writer_ref = &writer;
 
-                    let (file_name, file_ext) =
-                        match path.file_name().and_then(std::ffi::OsStr::to_str) {
-                            Some(file_name) => {
-                                if let Some(file_without_ext) = file_name.strip_suffix(".html") {
-                                    (file_without_ext, "html")
-                                } else {
-                                    bail!(
-                                        "File {} isn't an HTML file, make sure it ends with .html",
-                                        file_name
-                                    )
-                                }
-                            }
-                            None => bail!("Not a valid html file {}", path.display()),
-                        };
+            futures_util::stream::iter(files.into_iter().map(Ok))
+                .and_then(|(path, relative_path)| async move {
+                    let (file_name, kind) = match IndependentPage::from_file_name(&relative_path) {
+                        Some(page) => page,
+                        None => {
+                            // Anything that isn't HTML or Markdown is copied over as is
+                            return writer_ref.copy(path, output_ref.join(&relative_path)).await;
+                        }
+                    };
 
                     let content = tokio::fs::read_to_string(&path).await?;
+                    let (front_matter, content) = FrontMatter::parse(&content);
+                    let content = match kind {
+                        IndependentPage::Html => content.to_string(),
+                        IndependentPage::Markdown => {
+                            let mut rendered = String::with_capacity(content.len());
+                            pulldown_cmark::html::push_html(
+                                &mut rendered,
+                                pulldown_cmark::Parser::new(content),
+                            );
+                            rendered
+                        }
+                    };
 
-                    // For title we want the first letter to be uppercase
-                    let mut title = file_name.to_string();
-                    if let Some(first_char) = title.get_mut(0..1) {
-                        first_char.make_ascii_uppercase();
-                    }
-                    let title = format!("{} - {}", title, config_ref.name);
+                    let title = format!(
+                        "{} - {}",
+                        front_matter
+                            .title
+                            .unwrap_or_else(|| IndependentPage::title(file_name)),
+                        config_ref.name
+                    );
+                    let cover = front_matter
+                        .cover
+                        .map(|cover| match &config_ref.url {
+                            Some(url) => url.join(&cover).map(|cover| cover.to_string()),
+                            None => Ok(cover),
+                        })
+                        .transpose()?;
+                    let description = front_matter.description.map(|description| {
+                        truncate_description(&description, config_ref.meta_description_max)
+                    });
+
+                    let page_head = Head {
+                        description: description.as_deref(),
+                        image: cover.as_deref(),
+                        katex: false,
+                        ..Head::new(&title, file_name, &config_ref.locale)
+                    };
 
                     let markup = html! {
                         (DOCTYPE)
                         html lang=(config_ref.locale.lang) {
-                            head {
-                                meta charset="utf-8";
-                                meta name="viewport" content="width=device-width, initial-scale=1";
-                                title { (title) }
-                                @if let Some(author) = &config_ref.author {
-                                    meta name="author" content=(author.name);
-                                }
-                                @if config_ref.get_atom_id().is_some() {
-                                    link rel="alternate" type="application/atom+xml" href="/feed.xml";
-                                }
-
-                                meta property="og:title" content=(title);
-                                // TODO: Should there be a mechanism to set the description
-                                // for independent pages?
-                                meta property="og:locale" content=(config_ref.locale.locale);
-                                // TODO: Same as description but for images
-                                @if let Some(url) = &config_ref.url {
-                                    meta property="og:url" content=(url.join(file_name)?);
-                                }
-                                @if let Some(twitter_site) = &config_ref.twitter.site {
-                                    meta name="twitter:site" content=(twitter_site);
-                                }
-                                @if let Some(twitter_creator) = &config_ref.twitter.creator {
-                                    meta name="twitter:creator" content=(twitter_creator);
-                                }
-
-                                (*head_ref)
-                            }
+                            (render_head(config_ref, None, head_ref, &page_head)?)
                             body {
                                 header {
+                                    (render_nav(&config_ref.nav, file_name))
+                                    (render_lang_switcher(config_ref, file_name)?)
                                     (*header_ref)
                                 }
                                 (PreEscaped(content))
@@ -1199,30 +4733,214 @@ impl Generator {
                         }
                     };
 
-                    let mut path = directory_ref.join(EXPORT_DIR).join(file_name);
-                    path.set_extension(file_ext);
-                    write(path, markup.into_string()).await
+                    let mut path = output_ref.join(file_name);
+                    path.set_extension("html");
+                    writer_ref.write(path, markup.into_string()).await
                 })
                 .try_collect::<()>()
                 .await
         })
     }
 
-    fn download_cover(&self, page: &Page<Properties>) -> Result<Option<String>> {
-        let cover = page
+    /// Generate social share card images for every entry and article that doesn't have a cover or
+    /// an `og_image`
+    /// Only generates images if `generate_og_images` is enabled in the config
+    pub fn generate_og_images(&self) -> Result<JoinHandle<Result<()>>> {
+        if !self.config.generate_og_images {
+            return Ok(tokio::spawn(async { Ok(()) }));
+        }
+
+        let days = self
+            .lookup_tree
+            .iter()
+            .map(|(date, page)| (format_day(*date, false), Some(*date), page));
+        let articles = self.article_pages.iter().map(|(url, page)| {
+            let published_date = page.properties.published.date.as_ref().map(start_date);
+
+            (url.to_owned(), published_date, page)
+        });
+
+        let drafts = self
+            .drafts
+            .iter()
+            .map(|(path, page)| (path.to_owned(), None, page));
+
+        let images = days
+            .chain(articles)
+            .chain(drafts)
+            .filter(|(_, _, page)| {
+                page.cover.is_none()
+                    && page
+                        .properties
+                        .og_image
+                        .as_ref()
+                        .and_then(|og_image| og_image.files.first())
+                        .is_none()
+            })
+            .map(|(path, date, page)| {
+                let image = og_image::render(
+                    &page.properties.title().plain_text(),
+                    date,
+                    &self.config.name,
+                )?;
+
+                let path = self.output.join(og_image::path(&path));
+                Ok(self.writer.write(path, image))
+            })
+            .collect::<Result<FuturesUnordered<_>>>()?;
+
+        Ok(tokio::spawn(images.try_collect::<()>()))
+    }
+
+    /// The image to use for a page's `og:image`, which is the first file of its `og_image`
+    /// property if it has one, then its cover, and otherwise its generated share card if those are
+    /// enabled
+    fn og_image(
+        &self,
+        page: &Page<Properties>,
+        cover: Option<String>,
+        path: &str,
+    ) -> Result<Option<String>> {
+        let file = page
+            .properties
+            .og_image
+            .as_ref()
+            .and_then(|og_image| og_image.files.first());
+        if let Some(file) = file {
+            let src = self.download_media(file, page.id, MediaKind::OgImage)?;
+
+            return Ok(Some(src));
+        }
+
+        Ok(match cover {
+            Some(cover) => Some(cover),
+            None if self.config.generate_og_images => Some(og_image::src_path(path)),
+            None => None,
+        })
+    }
+
+    /// The favicon of a page made out of its Notion icon, emojis are turned into an SVG with the
+    /// emoji as its only text while images are downloaded
+    fn page_icon(&self, page: &Page<Properties>) -> Result<Option<String>> {
+        match &page.icon {
+            Some(EmojiOrFile::Emoji(Emoji { emoji })) => Ok(Some(format!(
+                "data:image/svg+xml,{}",
+                percent_encode(&format!(
+                    "<svg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 100 100'>\
+<text y='.9em' font-size='90'>{}</text></svg>",
+                    emoji
+                ))
+            ))),
+            Some(EmojiOrFile::File(file)) => {
+                Ok(Some(self.download_media(file, page.id, MediaKind::Icon)?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// The cover of a page as shown atop it and in the feed, captioned covers are wrapped in a
+    /// figure and use their caption as their alt text while the rest use the page's title
+    fn render_cover(
+        &self,
+        page: &Page<Properties>,
+        renderer: &HtmlRenderer,
+    ) -> Result<Option<Markup>> {
+        let src = match self.download_cover(page)? {
+            Some(src) => src,
+            None => return Ok(None),
+        };
+        let caption = match &page.cover {
+            Some(File::File { caption, .. } | File::External { caption, .. })
+                if caption.plain_text().trim().is_empty().not() =>
+            {
+                Some(caption)
+            }
+            _ => None,
+        };
+        let alt = match caption {
+            Some(caption) => caption.plain_text(),
+            None => format!("{} cover", page.properties.title().plain_text()),
+        };
+        let size = page
             .cover
             .as_ref()
-            // Even though a page's cover doesn't have a unique id, since we know nothing else
-            // will use that id as media we will give it to the cover
-            .map(|file| file.as_downloadable(page.id))
-            .transpose()?;
+            .and_then(|file| self.cover_sizes.get(media_source(file).1));
+        let width = size.map(|&(width, _)| width);
+        let height = size.map(|&(_, height)| height);
+
+        // Covers are at the top of pages so they're loaded right away instead of lazily, but
+        // decoding them doesn't have to hold back the text under them
+        let img = html! {
+            img class="cover" alt=(alt) src=(src) decoding="async" width=[width] height=[height];
+        };
+        Ok(Some(html! {
+            @if let Some(caption) = caption {
+                figure {
+                    (img)
+                    figcaption { (renderer.render_rich_text(caption)) }
+                }
+            } @else {
+                (img)
+            }
+        }))
+    }
+
+    fn download_cover(&self, page: &Page<Properties>) -> Result<Option<String>> {
+        page.cover
+            .as_ref()
+            .map(|file| self.download_media(file, page.id, MediaKind::Cover))
+            .transpose()
+    }
+
+    /// Download a file as the `kind` of media of a page and return the path it will be served
+    /// from, unless the same file was already downloaded for another page (i.e a cover shared by
+    /// many entries) in which case that file's path is returned instead
+    fn download_media(&self, file: &File, page: NotionId, kind: MediaKind) -> Result<String> {
+        let mut media = self.media.lock().expect("media lock to not be poisoned");
+        register_media(&mut media, file, page, kind.suffix())
+    }
+}
 
-        let src = cover.as_ref().map(|downloadable| downloadable.src_path());
+#[cfg(test)]
+mod tests {
+    use super::write;
+    use futures_util::future::join_all;
+    use std::fs;
+    use tempdir::TempDir;
 
-        if let Some(cover) = cover {
-            self.downloadables.insert(cover);
+    fn file_names(dir: &TempDir) -> Vec<String> {
+        fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn concurrent_writes_to_the_same_file_do_not_collide() {
+        let dir = TempDir::new("concurrent_writes").unwrap();
+        let path = dir.path().join("index.html");
+
+        let contents = (0..32).map(|i| i.to_string()).collect::<Vec<_>>();
+        let results = join_all(contents.iter().map(|content| write(&path, content))).await;
+        for result in results {
+            result.unwrap();
         }
 
-        Ok(src)
+        assert!(contents.contains(&fs::read_to_string(&path).unwrap()));
+        assert_eq!(file_names(&dir), vec!["index.html"]);
+    }
+
+    #[tokio::test]
+    async fn failed_writes_remove_their_temporary_file() {
+        let dir = TempDir::new("failed_writes").unwrap();
+        // Renaming a file over a directory fails after the temporary file is written
+        fs::create_dir(dir.path().join("index.html")).unwrap();
+
+        write(dir.path().join("index.html"), "<html>")
+            .await
+            .unwrap_err();
+
+        assert_eq!(file_names(&dir), vec!["index.html"]);
+        assert!(dir.path().join("index.html").is_dir());
     }
 }
@@ -1,58 +1,245 @@
 mod utils;
 
-use anyhow::{Context, Result};
-use diary_generator::{katex, Generator, Properties, EXPORT_DIR};
+use anyhow::{bail, Context, Result};
+use diary_generator::{katex, Generator, Options, Properties};
+use futures_util::{
+    future,
+    stream::{self, StreamExt, TryStreamExt},
+};
 use notion_generator::client::NotionClient;
-use std::path::Path;
+use std::path::PathBuf;
+use time::{macros::format_description, Date};
+use tokio::task::JoinHandle;
+use tracing::{info, info_span, warn, Instrument};
+use tracing_subscriber::{fmt::format::FmtSpan, EnvFilter};
 use utils::spawn_copy_all;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let args: Vec<String> = std::env::args().collect::<Vec<String>>();
+    let (flags, args): (Vec<String>, Vec<String>) = std::env::args()
+        .skip(1)
+        .partition(|arg| arg.starts_with("--"));
     let auth_token = std::env::var("NOTION_TOKEN").context("Missing NOTION_TOKEN env variable")?;
-    let database_id = args.get(1).context("Missing page id as first argument")?;
+    if args.is_empty() {
+        bail!("Missing database id as first argument");
+    }
 
-    tracing::subscriber::set_global_default(tracing_subscriber::FmtSubscriber::new())?;
+    let mut options = Options::default();
+    if let Ok(today) = std::env::var("DIARY_TODAY") {
+        options.today = Some(
+            Date::parse(&today, format_description!("[year]-[month]-[day]")).with_context(
+                || format!("DIARY_TODAY must be a date like 2021-11-07, not {}", today),
+            )?,
+        );
+    }
+    for flag in flags {
+        match flag.as_str() {
+            "--drafts" => options.drafts = true,
+            "--strict" => options.strict = true,
+            "--offline" => options.offline = true,
+            "--clean" => options.clean = true,
+            "--dry-run" => options.dry_run = true,
+            "--keep-going" => options.keep_going = true,
+            _ => match flag.strip_prefix("--output=") {
+                Some(output_dir) => options.output_dir = Some(PathBuf::from(output_dir)),
+                None => bail!("Unknown flag {}", flag),
+            },
+        }
+    }
+
+    // Closing spans are logged as well so that the time each phase of the build took is visible
+    tracing::subscriber::set_global_default(
+        tracing_subscriber::fmt()
+            .with_env_filter(
+                EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+            )
+            .with_span_events(FmtSpan::CLOSE)
+            .finish(),
+    )?;
 
     let reqwest_client = reqwest::Client::new();
     let client = NotionClient::with_client(reqwest_client.clone(), auth_token);
-    let pages = client.get_database_pages::<Properties>(database_id).await?;
+    // Every database given is merged into a single diary
+    let pages = future::try_join_all(
+        args.iter()
+            .map(|database_id| client.get_database_pages::<Properties>(database_id)),
+    )
+    .await?
+    .into_iter()
+    .flatten()
+    .collect();
 
-    let generator = Generator::new(std::env::current_dir()?, pages).await?;
+    let offline = options.offline;
+    let clean = options.clean;
+    let dry_run = options.dry_run;
+    let current_dir = std::env::current_dir()?;
+    let mut generator = Generator::with_options(&current_dir, pages, options).await?;
 
+    // Nothing is generated without entries, which must be known before cleaning so that the
+    // previous output isn't deleted without anything to replace it
     let (first_date, last_date) = match generator.get_first_and_last_dates() {
         Some(dates) => dates,
         None => return Ok(()),
     };
 
-    let results = tokio::try_join!(
-        katex::download(reqwest_client.clone()),
-        generator.generate_years(first_date, last_date)?,
-        generator.generate_months(first_date, last_date)?,
-        generator.generate_days()?,
-        generator.generate_article_pages()?,
-        generator.generate_index_page()?,
-        generator.generate_articles_page()?,
-        generator.generate_atom_feed()?,
-        generator.generate_independent_pages(),
-        spawn_copy_all(Path::new("public"), Path::new(EXPORT_DIR))
-    )?;
+    if clean && dry_run {
+        info!(msg = "Would clean output directory", path = %generator.output_dir().display());
+    } else if clean {
+        generator.clean_output().await?;
+    }
 
-    match results {
-        (Err(error), _, _, _, _, _, _, _, _, _) => return Err(error),
-        (_, Err(error), _, _, _, _, _, _, _, _) => return Err(error),
-        (_, _, Err(error), _, _, _, _, _, _, _) => return Err(error),
-        (_, _, _, Err(error), _, _, _, _, _, _) => return Err(error),
-        (_, _, _, _, Err(error), _, _, _, _, _) => return Err(error),
-        (_, _, _, _, _, Err(error), _, _, _, _) => return Err(error),
-        (_, _, _, _, _, _, Err(error), _, _, _) => return Err(error),
-        (_, _, _, _, _, _, _, Err(error), _, _) => return Err(error),
-        (_, _, _, _, _, _, _, _, Err(error), _) => return Err(error),
-        (_, _, _, _, _, _, _, _, _, Err(error)) => return Err(error),
-        (Ok(()), Ok(()), Ok(()), Ok(()), Ok(()), Ok(()), Ok(()), Ok(()), Ok(()), Ok(())) => {}
-    };
+    // Covers are downloaded before the pages showing them are generated so their size is known
+    generator
+        .download_covers(reqwest_client.clone())
+        .instrument(info_span!("generate", phase = "covers"))
+        .await?;
+
+    // KaTeX is downloaded before any page is generated when its stylesheet is inlined into them
+    let katex_downloaded = generator.inlines_katex() && !offline && !dry_run;
+    if generator.inlines_katex() {
+        if offline {
+            warn!("Linking to KaTeX instead of inlining it in offline mode");
+        } else if katex_downloaded {
+            let span = info_span!("generate", phase = "katex");
+            generator
+                .download_katex(reqwest_client.clone())
+                .instrument(span.clone())
+                .await??;
+
+            match katex::inline_styles(generator.output_dir())
+                .instrument(span)
+                .await
+            {
+                Ok(styles) => generator.inline_katex(styles),
+                Err(error) => warn!(
+                    msg = "Linking to KaTeX instead of inlining it",
+                    error = %error
+                ),
+            }
+        }
+    }
+
+    // Generators start their work as soon as they are called so they are wrapped in closures to
+    // only be called once there's room for them to run
+    // Each task is named after the phase of the build it's responsible for
+    type Task<'a> = Box<dyn FnOnce() -> Result<JoinHandle<Result<()>>> + 'a>;
+    let tasks: Vec<(&str, Task)> = vec![
+        (
+            "katex",
+            Box::new(|| {
+                if katex_downloaded {
+                    return Ok(tokio::spawn(async { Ok(()) }));
+                }
+
+                Ok(generator.download_katex(reqwest_client.clone()))
+            }),
+        ),
+        (
+            "years",
+            Box::new(|| generator.generate_years(first_date, last_date)),
+        ),
+        (
+            "months",
+            Box::new(|| generator.generate_months(first_date, last_date)),
+        ),
+        ("days", Box::new(|| generator.generate_days())),
+        ("articles", Box::new(|| generator.generate_article_pages())),
+        ("index", Box::new(|| generator.generate_index_page())),
+        (
+            "articles_page",
+            Box::new(|| generator.generate_articles_page()),
+        ),
+        ("all", Box::new(|| generator.generate_all_page())),
+        (
+            "subscribe",
+            Box::new(|| generator.generate_subscribe_page()),
+        ),
+        ("series", Box::new(|| generator.generate_series_pages())),
+        ("tags", Box::new(|| generator.generate_tag_pages())),
+        ("feed", Box::new(|| generator.generate_atom_feed())),
+        ("json_feed", Box::new(|| generator.generate_json_feed())),
+        ("year_feeds", Box::new(|| generator.generate_year_feeds())),
+        ("month_feeds", Box::new(|| generator.generate_month_feeds())),
+        (
+            "updates_feed",
+            Box::new(|| generator.generate_updates_feed()),
+        ),
+        ("og_images", Box::new(|| generator.generate_og_images())),
+        ("calendar", Box::new(|| generator.generate_calendar_data())),
+        ("latest", Box::new(|| generator.generate_latest_json())),
+        (
+            "search_index",
+            Box::new(|| generator.generate_search_index()),
+        ),
+        ("sitemap", Box::new(|| generator.generate_sitemap())),
+        ("fonts", Box::new(|| Ok(generator.generate_fonts()))),
+        ("humans", Box::new(|| Ok(generator.generate_humans()))),
+        (
+            "output_marker",
+            Box::new(|| Ok(generator.generate_output_marker())),
+        ),
+        (
+            "headers",
+            Box::new(|| Ok(generator.generate_headers_file())),
+        ),
+        (
+            "security_txt",
+            Box::new(|| generator.generate_security_txt()),
+        ),
+        (
+            "independent_pages",
+            Box::new(|| Ok(generator.generate_independent_pages())),
+        ),
+        (
+            "public",
+            Box::new(|| {
+                Ok(spawn_copy_all(
+                    generator.public_dir(),
+                    generator.output_dir().to_owned(),
+                    generator.dry_run().cloned(),
+                ))
+            }),
+        ),
+    ];
+
+    let concurrency = generator.concurrency().unwrap_or(tasks.len());
+    stream::iter(tasks)
+        .map(|(phase, task)| {
+            let span = info_span!("generate", phase);
+            let handle = span.in_scope(task);
+            async move { handle?.await? }.instrument(span)
+        })
+        .buffer_unordered(concurrency.max(1))
+        .try_collect::<()>()
+        .await?;
+
+    // The manifest lists what every other task produced so it's generated once they're all done
+    let span = info_span!("generate", phase = "manifest");
+    let manifest = span.in_scope(|| generator.generate_manifest_json())?;
+    manifest.instrument(span).await??;
+
+    let dry_run = generator.dry_run().cloned();
+    let failures = generator.failures();
+    generator
+        .download_all(reqwest_client.clone())
+        .instrument(info_span!("generate", phase = "downloads"))
+        .await?;
+
+    if failures > 0 {
+        warn!(
+            msg = "Build finished with pages skipped because of errors",
+            failures
+        );
+    }
 
-    generator.download_all(reqwest_client.clone()).await?;
+    if let Some(dry_run) = dry_run {
+        info!(
+            msg = "Dry run finished without writing anything",
+            files = dry_run.files(),
+            bytes = dry_run.bytes(),
+            media = dry_run.media(),
+        );
+    }
 
     Ok(())
 }
@@ -18,3 +18,123 @@ const MONTHS: &[Month] = &[
 pub fn all() -> std::slice::Iter<'static, Month> {
     MONTHS.iter()
 }
+
+/// The name of the month in the language, falling back to English for languages without a table
+pub fn name(month: Month, lang: &str) -> &'static str {
+    let names: [&str; 12] = match lang {
+        "de" => [
+            "Januar",
+            "Februar",
+            "März",
+            "April",
+            "Mai",
+            "Juni",
+            "Juli",
+            "August",
+            "September",
+            "Oktober",
+            "November",
+            "Dezember",
+        ],
+        "es" => [
+            "enero",
+            "febrero",
+            "marzo",
+            "abril",
+            "mayo",
+            "junio",
+            "julio",
+            "agosto",
+            "septiembre",
+            "octubre",
+            "noviembre",
+            "diciembre",
+        ],
+        "fr" => [
+            "janvier",
+            "février",
+            "mars",
+            "avril",
+            "mai",
+            "juin",
+            "juillet",
+            "août",
+            "septembre",
+            "octobre",
+            "novembre",
+            "décembre",
+        ],
+        "it" => [
+            "gennaio",
+            "febbraio",
+            "marzo",
+            "aprile",
+            "maggio",
+            "giugno",
+            "luglio",
+            "agosto",
+            "settembre",
+            "ottobre",
+            "novembre",
+            "dicembre",
+        ],
+        "nl" => [
+            "januari",
+            "februari",
+            "maart",
+            "april",
+            "mei",
+            "juni",
+            "juli",
+            "augustus",
+            "september",
+            "oktober",
+            "november",
+            "december",
+        ],
+        "pt" => [
+            "janeiro",
+            "fevereiro",
+            "março",
+            "abril",
+            "maio",
+            "junho",
+            "julho",
+            "agosto",
+            "setembro",
+            "outubro",
+            "novembro",
+            "dezembro",
+        ],
+        _ => [
+            "January",
+            "February",
+            "March",
+            "April",
+            "May",
+            "June",
+            "July",
+            "August",
+            "September",
+            "October",
+            "November",
+            "December",
+        ],
+    };
+
+    names[month as usize - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::name;
+    use time::Month;
+
+    #[test]
+    fn names_follow_the_language() {
+        assert_eq!(name(Month::November, "en"), "November");
+        assert_eq!(name(Month::February, "fr"), "février");
+        assert_eq!(name(Month::March, "de"), "März");
+        assert_eq!(name(Month::November, "tlh"), "November");
+    }
+}
@@ -0,0 +1,170 @@
+use ab_glyph::{point, Font, FontRef, PxScale, PxScaleFont, ScaleFont};
+use anyhow::Result;
+use image::{codecs::png::PngEncoder, ColorType, ImageEncoder, Rgb, RgbImage};
+use time::{format_description::FormatItem, macros::format_description, Date};
+
+const WIDTH: u32 = 1200;
+const HEIGHT: u32 = 630;
+const MARGIN: f32 = 80.0;
+const MAX_TITLE_LINES: usize = 4;
+
+const BACKGROUND: Rgb<u8> = Rgb([255, 255, 255]);
+const FOREGROUND: Rgb<u8> = Rgb([17, 17, 17]);
+const MUTED: Rgb<u8> = Rgb([102, 102, 102]);
+
+const FONT: &[u8] = include_bytes!("../assets/fonts/DejaVuSans-Bold.ttf");
+
+/// The path relative to the export directory at which the share card of the page at `page_path`
+/// is written
+pub fn path(page_path: &str) -> String {
+    format!("og/{}.png", page_path.trim_matches('/').replace('/', "-"))
+}
+
+/// The path from which the share card of the page at `page_path` will be served
+pub fn src_path(page_path: &str) -> String {
+    format!("/{}", path(page_path))
+}
+
+/// Render a 1200x630 PNG share card containing the title of a page, its date and the name of the
+/// diary
+pub fn render(title: &str, date: Option<Date>, name: &str) -> Result<Vec<u8>> {
+    const READABLE_DATE: &[FormatItem<'_>] = format_description!("[month repr:long] [day], [year]");
+
+    let font = FontRef::try_from_slice(FONT)?;
+    let title_font = font.as_scaled(PxScale::from(72.0));
+    let footer_font = font.as_scaled(PxScale::from(36.0));
+
+    let mut image = RgbImage::from_pixel(WIDTH, HEIGHT, BACKGROUND);
+
+    let mut baseline = MARGIN + title_font.ascent();
+    for line in wrap(&title_font, title, WIDTH as f32 - 2.0 * MARGIN) {
+        draw_line(&mut image, &title_font, &line, MARGIN, baseline, FOREGROUND);
+        baseline += title_font.height() + title_font.line_gap();
+    }
+
+    let footer = match date {
+        Some(date) => format!("{} · {}", date.format(READABLE_DATE)?, name),
+        None => name.to_string(),
+    };
+    draw_line(
+        &mut image,
+        &footer_font,
+        &footer,
+        MARGIN,
+        HEIGHT as f32 - MARGIN,
+        MUTED,
+    );
+
+    let mut bytes = Vec::new();
+    PngEncoder::new(&mut bytes).write_image(image.as_raw(), WIDTH, HEIGHT, ColorType::Rgb8)?;
+
+    Ok(bytes)
+}
+
+fn line_width<F: Font>(font: &PxScaleFont<F>, text: &str) -> f32 {
+    text.chars().map(|c| font.h_advance(font.glyph_id(c))).sum()
+}
+
+/// Greedily wrap the text into lines that fit within `max_width`, truncating it with an ellipsis
+/// when it doesn't fit within the maximum number of lines
+fn wrap<F: Font>(font: &PxScaleFont<F>, text: &str, max_width: f32) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut current = String::new();
+
+    for word in text.split_whitespace() {
+        let candidate = if current.is_empty() {
+            word.to_string()
+        } else {
+            format!("{} {}", current, word)
+        };
+
+        if current.is_empty() || line_width(font, &candidate) <= max_width {
+            current = candidate;
+        } else {
+            lines.push(std::mem::replace(&mut current, word.to_string()));
+        }
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+
+    if lines.len() > MAX_TITLE_LINES {
+        lines.truncate(MAX_TITLE_LINES);
+        if let Some(last) = lines.last_mut() {
+            while !last.is_empty() && line_width(font, &format!("{}…", last)) > max_width {
+                last.pop();
+            }
+            last.push('…');
+        }
+    }
+
+    lines
+}
+
+fn draw_line<F: Font>(
+    image: &mut RgbImage,
+    font: &PxScaleFont<F>,
+    text: &str,
+    x: f32,
+    baseline: f32,
+    color: Rgb<u8>,
+) {
+    let mut caret = x;
+    let mut previous = None;
+
+    for c in text.chars() {
+        let id = font.glyph_id(c);
+        if let Some(previous) = previous {
+            caret += font.kern(previous, id);
+        }
+        previous = Some(id);
+
+        let glyph = id.with_scale_and_position(font.scale(), point(caret, baseline));
+        caret += font.h_advance(id);
+
+        let outlined = match font.outline_glyph(glyph) {
+            Some(outlined) => outlined,
+            None => continue,
+        };
+        let bounds = outlined.px_bounds();
+
+        outlined.draw(|glyph_x, glyph_y, coverage| {
+            let x = bounds.min.x as i64 + glyph_x as i64;
+            let y = bounds.min.y as i64 + glyph_y as i64;
+
+            if x < 0 || y < 0 || x >= WIDTH as i64 || y >= HEIGHT as i64 {
+                return;
+            }
+
+            let pixel = image.get_pixel_mut(x as u32, y as u32);
+            for (channel, target) in pixel.0.iter_mut().zip(color.0) {
+                *channel = (*channel as f32 * (1.0 - coverage) + target as f32 * coverage) as u8;
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{path, render, HEIGHT, WIDTH};
+    use time::macros::date;
+
+    #[test]
+    fn paths_are_flattened() {
+        assert_eq!(path("2021/11/07"), "og/2021-11-07.png");
+        assert_eq!(path("/interesting_article"), "og/interesting_article.png");
+    }
+
+    #[test]
+    fn renders_card_of_expected_size() {
+        let bytes = render(
+            "Day 2: Enter Bevy & Shaders are hard",
+            Some(date!(2021 - 11 - 09)),
+            "Diary",
+        )
+        .unwrap();
+
+        let image = image::load_from_memory(&bytes).unwrap();
+        assert_eq!((image.width(), image.height()), (WIDTH, HEIGHT));
+    }
+}
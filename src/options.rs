@@ -0,0 +1,28 @@
+use std::path::PathBuf;
+use time::Date;
+
+/// Options that control how a build behaves, unlike `Config` which controls how the diary looks
+#[derive(Clone, Default)]
+pub struct Options {
+    /// Whether to render pages that don't have a published date yet into `_drafts/` so they can
+    /// be previewed before publishing
+    pub drafts: bool,
+    /// Whether to fail the build on problems that would otherwise only be warned about
+    pub strict: bool,
+    /// Whether to skip downloading KaTeX and media so the diary can be generated without network
+    /// access beyond Notion's API, leaving their files missing from the output
+    pub offline: bool,
+    /// The directory to generate the diary into instead of `output/` inside the diary's directory
+    pub output_dir: Option<PathBuf>,
+    /// Whether to delete the output directory before generating so files of renamed or deleted
+    /// pages don't linger around
+    pub clean: bool,
+    /// Whether to only log the files that would be generated along with their sizes instead of
+    /// writing them, leaving the output directory untouched
+    pub dry_run: bool,
+    /// The day to treat as today when deciding which pages are published, the real date if not
+    /// present, which makes builds of scheduled pages reproducible
+    pub today: Option<Date>,
+    /// Whether to skip pages that fail to be generated with a warning instead of failing the build
+    pub keep_going: bool,
+}
@@ -0,0 +1,173 @@
+use crate::syndication::XmlDoc;
+use maud::{html, Markup, Render};
+use time::{format_description::FormatItem, macros::format_description, Date};
+
+/// The most URLs a single sitemap is allowed to contain
+pub const MAX_URLS: usize = 50_000;
+
+pub struct SitemapIndex {
+    pub sitemaps: Vec<Sitemap>,
+}
+
+pub struct Sitemap {
+    /// The absolute URL of the sitemap
+    pub loc: reqwest::Url,
+    /// The last time any of the pages in the sitemap was modified, if it is known
+    pub lastmod: Option<Date>,
+}
+
+pub struct UrlSet<'a> {
+    pub urls: &'a [Url],
+}
+
+pub struct Url {
+    /// The absolute URL of the page
+    pub loc: reqwest::Url,
+    /// The last time the page was modified, if it is known
+    pub lastmod: Option<Date>,
+    pub changefreq: ChangeFrequency,
+    /// The priority of the page relative to other pages of the diary between 0.0 and 1.0
+    pub priority: f32,
+}
+
+pub enum ChangeFrequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// Whether a path matches a glob where `*` matches anything but a `/` and `**` matches anything
+/// Both are compared without their leading `/` so `/legal/*` and `legal/*` are the same glob
+pub fn glob_matches(glob: &str, path: &str) -> bool {
+    fn matches(glob: &[u8], path: &[u8]) -> bool {
+        match glob {
+            [] => path.is_empty(),
+            [b'*', b'*', rest @ ..] => (0..=path.len()).any(|skip| matches(rest, &path[skip..])),
+            [b'*', rest @ ..] => (0..=path.len())
+                .take_while(|&skip| skip == 0 || path[skip - 1] != b'/')
+                .any(|skip| matches(rest, &path[skip..])),
+            [char, rest @ ..] => path.first() == Some(char) && matches(rest, &path[1..]),
+        }
+    }
+
+    matches(
+        glob.trim_start_matches('/').as_bytes(),
+        path.trim_start_matches('/').as_bytes(),
+    )
+}
+
+const W3C_DATE: &[FormatItem<'_>] = format_description!("[year]-[month]-[day]");
+
+impl Render for SitemapIndex {
+    fn render(&self) -> Markup {
+        html! {
+            (XmlDoc)
+            sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9" {
+                @for sitemap in &self.sitemaps {
+                    (*sitemap)
+                }
+            }
+        }
+    }
+}
+
+impl Render for Sitemap {
+    fn render(&self) -> Markup {
+        html! {
+            sitemap {
+                loc { (self.loc) }
+                @if let Some(lastmod) = self.lastmod {
+                    lastmod { (lastmod.format(W3C_DATE).unwrap()) }
+                }
+            }
+        }
+    }
+}
+
+impl<'a> Render for UrlSet<'a> {
+    fn render(&self) -> Markup {
+        html! {
+            (XmlDoc)
+            urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9" {
+                @for url in self.urls {
+                    (*url)
+                }
+            }
+        }
+    }
+}
+
+impl Render for Url {
+    fn render(&self) -> Markup {
+        html! {
+            url {
+                loc { (self.loc) }
+                @if let Some(lastmod) = self.lastmod {
+                    lastmod { (lastmod.format(W3C_DATE).unwrap()) }
+                }
+                changefreq { (self.changefreq) }
+                priority { (format!("{:.1}", self.priority)) }
+            }
+        }
+    }
+}
+
+impl Render for ChangeFrequency {
+    fn render_to(&self, buffer: &mut String) {
+        match self {
+            ChangeFrequency::Daily => buffer.push_str("daily"),
+            ChangeFrequency::Weekly => buffer.push_str("weekly"),
+            ChangeFrequency::Monthly => buffer.push_str("monthly"),
+            ChangeFrequency::Yearly => buffer.push_str("yearly"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{glob_matches, ChangeFrequency, Url};
+    use maud::Render;
+    use time::macros::date;
+
+    #[test]
+    fn urls_render() {
+        assert_eq!(
+            Url {
+                loc: "https://gamediary.dev/2021/11/07".parse().unwrap(),
+                lastmod: Some(date!(2021 - 12 - 06)),
+                changefreq: ChangeFrequency::Monthly,
+                priority: 0.7,
+            }
+            .render()
+            .into_string(),
+            "<url><loc>https://gamediary.dev/2021/11/07</loc><lastmod>2021-12-06</lastmod>\
+<changefreq>monthly</changefreq><priority>0.7</priority></url>"
+        );
+
+        assert_eq!(
+            Url {
+                loc: "https://gamediary.dev/".parse().unwrap(),
+                lastmod: None,
+                changefreq: ChangeFrequency::Daily,
+                priority: 1.0,
+            }
+            .render()
+            .into_string(),
+            "<url><loc>https://gamediary.dev/</loc>\
+<changefreq>daily</changefreq><priority>1.0</priority></url>"
+        );
+    }
+
+    #[test]
+    fn globs() {
+        assert!(glob_matches("/legal/*", "/legal/privacy"));
+        assert!(glob_matches("legal/*", "/legal/privacy"));
+        assert!(!glob_matches("/legal/*", "/legal/privacy/2021"));
+        assert!(glob_matches("/legal/**", "/legal/privacy/2021"));
+        assert!(glob_matches("/2021/*/07", "/2021/11/07"));
+        assert!(!glob_matches("/legal/*", "/legalese"));
+        assert!(glob_matches("/thank-you", "/thank-you"));
+        assert!(!glob_matches("/thank-you", "/thank-you-again"));
+    }
+}
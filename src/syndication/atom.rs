@@ -1,4 +1,5 @@
-use maud::{html, Markup, Render};
+use super::XmlDoc;
+use maud::{html, Markup, PreEscaped, Render};
 use time::format_description::well_known::Rfc3339;
 
 pub struct Feed<'a> {
@@ -12,10 +13,10 @@ pub struct Feed<'a> {
     pub last_changed: time::OffsetDateTime,
     pub authors: Vec<Person<'a>>,
 
-    // TODO: Diary generator doesn't currently support tags
+    // TODO: Should the feed itself list every tag of its entries as its categories?
     // categories: &'a [&'a str],
-    /// The generator that is generating this feed
-    pub generator: Generator,
+    /// The generator that is generating this feed, omitted from the feed if not present
+    pub generator: Option<Generator>,
     pub icon: Option<&'a str>,
     pub cover: Option<&'a str>,
     pub lang: &'a str,
@@ -40,13 +41,25 @@ pub struct Entry {
     pub updated: time::OffsetDateTime,
     pub published: time::OffsetDateTime,
     // TODO: Should each entry have an author
-    // TODO: tags AKA categories
-    pub summary: String,
+    pub summary: Summary,
+    /// The tags of the entry
+    pub categories: Vec<String>,
+    /// The language of the entry if it differs from the language of the feed
+    pub lang: Option<String>,
+    /// The absolute URL of the entry's cover
+    pub enclosure: Option<String>,
     pub content: Markup,
 }
 
+pub enum Summary {
+    Text(String),
+    /// HTML that is escaped when rendered like the content
+    Html(Markup),
+}
+
 enum LinkType {
     Alternate,
+    Enclosure,
     Self_,
 }
 
@@ -55,16 +68,22 @@ struct Link<'a> {
     href: &'a str,
 }
 
-struct XmlDoc;
-
-impl Render for XmlDoc {
-    fn render_to(&self, buffer: &mut String) {
-        buffer.push_str(r#"<?xml version="1.0" encoding="utf-8" ?>"#);
-    }
+/// Whether a character is allowed to appear in an XML 1.0 document
+fn is_xml_char(c: char) -> bool {
+    matches!(c, '\t' | '\n' | '\r' | '\u{20}'..='\u{D7FF}' | '\u{E000}'..='\u{FFFD}' | '\u{10000}'..='\u{10FFFF}')
 }
 
 impl<'a> Render for Feed<'a> {
     fn render(&self) -> Markup {
+        // Notion content occasionally contains stray control characters which would make the whole
+        // feed invalid XML
+        let markup = self.render_unchecked().into_string();
+        PreEscaped(markup.chars().filter(|&c| is_xml_char(c)).collect())
+    }
+}
+
+impl<'a> Feed<'a> {
+    fn render_unchecked(&self) -> Markup {
         html! {
             (XmlDoc)
             feed xmlns="http://www.w3.org/2005/Atom" xml:lang=(self.lang) {
@@ -76,7 +95,9 @@ impl<'a> Render for Feed<'a> {
                     (*author)
                 }
 
-                (self.generator)
+                @if let Some(generator) = &self.generator {
+                    (*generator)
+                }
 
                 (Link {
                     href: self.feed_url.as_str(),
@@ -134,12 +155,24 @@ impl Render for Generator {
 impl Render for Entry {
     fn render(&self) -> Markup {
         html! {
-            entry {
+            entry xml:lang=[self.lang.as_deref()] {
                 id { (self.url) }
                 title type="html" { (self.title) }
                 updated { (self.updated.format(&Rfc3339).unwrap()) }
                 published { (self.published.format(&Rfc3339).unwrap()) }
-                summary { (self.summary) }
+                @match &self.summary {
+                    Summary::Text(text) => summary { (text) },
+                    Summary::Html(markup) => summary type="html" { (markup.0) },
+                }
+                @for category in &self.categories {
+                    category term=(category) {}
+                }
+                @if let Some(enclosure) = &self.enclosure {
+                    (Link {
+                        href: enclosure,
+                        ty: LinkType::Enclosure
+                    })
+                }
                 content type="html" { (self.content.0) }
             }
         }
@@ -150,6 +183,7 @@ impl Render for LinkType {
     fn render_to(&self, buffer: &mut String) {
         match self {
             LinkType::Alternate => buffer.push_str("alternate"),
+            LinkType::Enclosure => buffer.push_str("enclosure"),
             LinkType::Self_ => buffer.push_str("self"),
         }
     }
@@ -157,8 +191,8 @@ impl Render for LinkType {
 
 impl<'a> Render for Link<'a> {
     fn render_to(&self, buffer: &mut String) {
-        // In case of alternate which is the longer of the two link types the non-href parts of the
-        // link is 32 1-byte characters long
+        // In case of alternate or enclosure which are the longest of the link types the non-href
+        // parts of the link is 32 1-byte characters long
         buffer.reserve(32 + self.href.len());
         buffer.push_str("<link ");
 
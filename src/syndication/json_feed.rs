@@ -0,0 +1,55 @@
+use serde::Serialize;
+
+/// The version of JSON Feed generated feeds follow, also used as their `version` field
+pub const VERSION: &str = "https://jsonfeed.org/version/1.1";
+
+#[derive(Serialize)]
+pub struct Feed<'a> {
+    pub version: &'static str,
+    /// The title of the feed
+    pub title: &'a str,
+    /// The URL from which the diary itself will be served
+    pub home_page_url: &'a str,
+    /// The URL from which the feed will be served from
+    pub feed_url: String,
+    pub description: &'a str,
+    /// A large image to represent the feed, omitted from the feed if not present
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    /// A small image to represent the feed in lists of feeds, omitted from the feed if not present
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub favicon: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub authors: Vec<Author<'a>>,
+    pub language: &'a str,
+    pub items: Vec<Item>,
+}
+
+#[derive(Serialize)]
+pub struct Author<'a> {
+    pub name: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct Item {
+    /// Unique for the item and never changes, the item's URL like the id of Atom entries
+    pub id: String,
+    pub url: String,
+    pub title: String,
+    pub content_html: String,
+    pub summary: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// The absolute URL of the item's cover
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    /// RFC 3339 formatted
+    pub date_published: String,
+    /// RFC 3339 formatted
+    pub date_modified: String,
+    /// The language of the item if it differs from the language of the feed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+}
@@ -1 +1,29 @@
+use maud::Render;
+
 pub mod atom;
+pub mod json_feed;
+
+/// The declaration every generated XML document starts with
+pub(crate) const XML_DECLARATION: &str = r#"<?xml version="1.0" encoding="utf-8"?>"#;
+
+pub(crate) struct XmlDoc;
+
+impl Render for XmlDoc {
+    fn render_to(&self, buffer: &mut String) {
+        buffer.push_str(XML_DECLARATION);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::XmlDoc;
+    use maud::Render;
+
+    #[test]
+    fn xml_declaration() {
+        assert_eq!(
+            XmlDoc.render().into_string().as_bytes(),
+            br#"<?xml version="1.0" encoding="utf-8"?>"#
+        );
+    }
+}
@@ -1,12 +1,13 @@
 use anyhow::{Context, Result};
 use async_recursion::async_recursion;
+use diary_generator::DryRun;
 use futures_util::stream::{StreamExt, TryStreamExt};
-use std::{io::ErrorKind, path::Path};
+use std::{io::ErrorKind, path::Path, sync::Arc};
 use tokio::{fs, task::JoinHandle};
 use tokio_stream::wrappers::ReadDirStream;
 
 #[async_recursion]
-pub async fn copy_all<I, O>(input_dir: I, output_dir: O) -> Result<()>
+pub async fn copy_all<I, O>(input_dir: I, output_dir: O, dry_run: Option<Arc<DryRun>>) -> Result<()>
 where
     I: AsRef<Path> + Send,
     O: AsRef<Path> + Send,
@@ -25,25 +26,44 @@ where
         }
     };
 
-    fs::create_dir_all(output_dir).await?;
+    if dry_run.is_none() {
+        fs::create_dir_all(output_dir).await?;
+    }
 
     let files = ReadDirStream::new(files);
 
     files
         .map(|result| result.context("Failed to read file while recursively copying"))
-        .and_then(|entry| async move {
-            let file_name = entry.file_name();
+        .and_then(|entry| {
+            let dry_run = dry_run.clone();
+            async move {
+                let file_name = entry.file_name();
 
-            match entry.file_type().await? {
-                file_type if file_type.is_dir() => {
-                    copy_all(input_dir.join(&file_name), output_dir.join(&file_name)).await?;
+                match entry.file_type().await? {
+                    file_type if file_type.is_dir() => {
+                        copy_all(
+                            input_dir.join(&file_name),
+                            output_dir.join(&file_name),
+                            dry_run,
+                        )
+                        .await?;
 
-                    Ok(())
-                }
-                _ => {
-                    fs::copy(input_dir.join(&file_name), output_dir.join(&file_name)).await?;
+                        Ok(())
+                    }
+                    _ => {
+                        match dry_run {
+                            Some(dry_run) => {
+                                let metadata = entry.metadata().await?;
+                                dry_run.record(&output_dir.join(&file_name), metadata.len());
+                            }
+                            None => {
+                                fs::copy(input_dir.join(&file_name), output_dir.join(&file_name))
+                                    .await?;
+                            }
+                        }
 
-                    Ok(())
+                        Ok(())
+                    }
                 }
             }
         })
@@ -53,6 +73,14 @@ where
     Ok(())
 }
 
-pub fn spawn_copy_all(input: &'static Path, output: &'static Path) -> JoinHandle<Result<()>> {
-    tokio::spawn(copy_all(input, output))
+pub fn spawn_copy_all<I, O>(
+    input: I,
+    output: O,
+    dry_run: Option<Arc<DryRun>>,
+) -> JoinHandle<Result<()>>
+where
+    I: AsRef<Path> + Send + 'static,
+    O: AsRef<Path> + Send + 'static,
+{
+    tokio::spawn(copy_all(input, output, dry_run))
 }
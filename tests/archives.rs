@@ -0,0 +1,193 @@
+mod utils;
+
+use diary_generator::{Generator, Properties};
+use notion_generator::response::Page;
+use std::fs;
+use utils::{function, new_entry, TestDir};
+
+fn entries() -> Vec<Page<Properties>> {
+    vec![
+        new_entry(
+            "cf2bacc9d75c4226aab53601c336f295",
+            "Day 0: Nannou, helping L, and lots of noise",
+            "Every journey starts with 1 O'clock: assistance.",
+            Some("2021-11-07".parse().unwrap()),
+            None,
+        ),
+        new_entry(
+            "ac3fb543001f4be5a25e4978abd05b1d",
+            "Day 1: Down the rabbit hole we go",
+            "Alice starts making games.",
+            Some("2021-11-08".parse().unwrap()),
+            None,
+        ),
+    ]
+}
+
+async fn generate_archives(cwd: &TestDir, config: &str) -> (String, String) {
+    fs::write(cwd.path().join("config.json"), config).unwrap();
+
+    let generator = Generator::new(cwd, entries()).await.unwrap();
+    let (first_date, last_date) = generator.get_first_and_last_dates().unwrap();
+    generator
+        .generate_years(first_date, last_date)
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+    generator
+        .generate_months(first_date, last_date)
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    (
+        fs::read_to_string(cwd.path().join("output/2021.html")).unwrap(),
+        fs::read_to_string(cwd.path().join("output/2021/11.html")).unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn oldest_first_by_default() {
+    let cwd = TestDir::new(function!());
+
+    let (year, month) = generate_archives(&cwd, "{}").await;
+    for archive in [year, month] {
+        assert!(archive.find("Day 0").unwrap() < archive.find("Day 1").unwrap());
+    }
+}
+
+#[tokio::test]
+async fn newest_first() {
+    let cwd = TestDir::new(function!());
+
+    let (year, month) = generate_archives(&cwd, r#"{ "archive_order": "newest" }"#).await;
+    for archive in [year, month] {
+        assert!(archive.find("Day 1").unwrap() < archive.find("Day 0").unwrap());
+    }
+}
+
+#[tokio::test]
+async fn descriptions_count_entries() {
+    let cwd = TestDir::new(function!());
+
+    let (year, month) = generate_archives(&cwd, r#"{ "name": "Game Diary" }"#).await;
+    assert!(year.contains(
+        r#"<meta property="og:description" content="2 entries of Game Diary from 2021">"#
+    ));
+    assert!(month.contains(
+        r#"<meta name="description" content="2 entries of Game Diary from November 2021">"#
+    ));
+
+    let (year, month) = generate_archives(
+        &cwd,
+        r#"
+            {
+              "year_description": "{year}: {count}",
+              "month_description": "{month} {year}: {entries}"
+            }
+        "#,
+    )
+    .await;
+    assert!(year.contains(r#"<meta property="og:description" content="2021: 2">"#));
+    assert!(
+        month.contains(r#"<meta property="og:description" content="November 2021: 2 entries">"#)
+    );
+}
+
+#[tokio::test]
+async fn robots_per_page_type() {
+    let cwd = TestDir::new(function!());
+
+    let (year, month) = generate_archives(
+        &cwd,
+        r#"{ "robots": { "archives": "noindex, follow", "entries": "max-image-preview:large" } }"#,
+    )
+    .await;
+    for archive in [year, month] {
+        assert!(archive.contains(r#"<meta name="robots" content="noindex, follow">"#));
+    }
+
+    let generator = Generator::new(&cwd, entries()).await.unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+    generator
+        .generate_index_page()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let day = fs::read_to_string(cwd.path().join("output/2021/11/07.html")).unwrap();
+    assert!(day.contains(r#"<meta name="robots" content="max-image-preview:large">"#));
+    let index = fs::read_to_string(cwd.path().join("output/index.html")).unwrap();
+    assert!(!index.contains(r#"name="robots""#));
+}
+
+#[tokio::test]
+async fn years_before_archive_before_share_a_page() {
+    let cwd = TestDir::new(function!());
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "archive_before": 2000 }"#,
+    )
+    .unwrap();
+
+    let generator = Generator::new(
+        &cwd,
+        vec![
+            new_entry(
+                "e4b3e1e6b5d74d9a8f3b5c2f4a1b0c9d",
+                "An old letter",
+                "Found in the attic.",
+                Some("1987-03-02".parse().unwrap()),
+                None,
+            ),
+            new_entry(
+                "78abd05b1dac3fb543001f4be5a25e49",
+                "Another old letter",
+                "Also found in the attic.",
+                Some("1995-06-14".parse().unwrap()),
+                None,
+            ),
+            new_entry(
+                "cf2bacc9d75c4226aab53601c336f295",
+                "Day 0: Nannou, helping L, and lots of noise",
+                "Every journey starts with 1 O'clock: assistance.",
+                Some("2021-11-07".parse().unwrap()),
+                None,
+            ),
+        ],
+    )
+    .await
+    .unwrap();
+    let (first_date, last_date) = generator.get_first_and_last_dates().unwrap();
+    generator
+        .generate_years(first_date, last_date)
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+    generator
+        .generate_index_page()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let output = cwd.path().join("output");
+    assert!(!output.join("1987.html").exists());
+    assert!(!output.join("1995.html").exists());
+    assert!(output.join("2021.html").exists());
+
+    let before = fs::read_to_string(output.join("before-2000.html")).unwrap();
+    assert!(before.contains("<title>Before 2000 - Diary</title>"));
+    assert!(before.contains("An old letter"));
+    assert!(before.contains("Another old letter"));
+    assert!(!before.contains("Day 0"));
+
+    let index = fs::read_to_string(output.join("index.html")).unwrap();
+    assert!(index.contains(r#"<h1><a href="before-2000">Before 2000</a></h1>"#));
+    assert!(index.contains(r#"<h2><a href="1995/06">June 1995</a></h2>"#));
+    assert!(index.contains(r#"<h1><a href="2021">2021</a></h1>"#));
+}
@@ -1,7 +1,9 @@
 mod utils;
 
-use diary_generator::Generator;
-use notion_generator::response::{Block, BlockType, Page, RichText, RichTextType};
+use diary_generator::{Generator, Properties};
+use notion_generator::response::{
+    properties::RichTextProperty, Block, BlockType, Page, RichText, RichTextType,
+};
 use pretty_assertions::assert_eq;
 use std::{fs, io::Cursor};
 use time::macros::date;
@@ -273,3 +275,773 @@ There’s no turning back now",
         ),
     );
 }
+
+#[tokio::test]
+async fn author_with_email() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"
+            {
+              "author": {
+                "name": "Mathspy",
+                "email": "mathspy@example.com",
+                "url": "https://mathspy.me"
+              },
+              "url": "https://gamediary.dev"
+            }
+        "#,
+    )
+    .unwrap();
+
+    let generator = Generator::new(
+        &cwd,
+        vec![new_article(
+            "78abd05b1dac3fb543001f4be5a25e49",
+            "Some article about something",
+            "some really interesting descritpion",
+            "interesting_article",
+            Some(date!(2021 - 12 - 08)),
+        )],
+    )
+    .await
+    .unwrap();
+    generator
+        .generate_atom_feed()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(
+        xml_string_to_events(
+            &fs::read_to_string(cwd.path().join("output").join("feed.xml")).unwrap()
+        ),
+        xml_string_to_events(
+            r##"
+<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom" xml:lang="en">
+   <id>https://gamediary.dev/</id>
+   <title>Diary</title>
+   <updated>2021-12-08T00:00:00Z</updated>
+   <author>
+      <name>Mathspy</name>
+      <email>mathspy@example.com</email>
+      <uri>https://mathspy.me/</uri>
+   </author>
+   <generator uri="https://github.com/Mathspy/diary-generator" version="0.3.9">diary-generator</generator>
+   <link rel="self" href="https://gamediary.dev/feed.xml" />
+   <link rel="alternate" href="https://gamediary.dev/" />
+   <entry>
+      <id>https://gamediary.dev/interesting_article</id>
+      <title type="html">Some article about something</title>
+      <updated>2021-12-06T09:25:00Z</updated>
+      <published>2021-12-08T00:00:00Z</published>
+      <summary>some really interesting descritpion</summary>
+      <content type="html" />
+   </entry>
+</feed>
+"##
+        ),
+    );
+}
+
+#[tokio::test]
+async fn without_generator() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"
+            {
+              "url": "https://gamediary.dev",
+              "feed_generator": false
+            }
+        "#,
+    )
+    .unwrap();
+
+    let generator = Generator::new(
+        &cwd,
+        vec![new_article(
+            "78abd05b1dac3fb543001f4be5a25e49",
+            "Some article about something",
+            "some really interesting descritpion",
+            "interesting_article",
+            Some(date!(2021 - 12 - 08)),
+        )],
+    )
+    .await
+    .unwrap();
+    generator
+        .generate_atom_feed()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(
+        xml_string_to_events(
+            &fs::read_to_string(cwd.path().join("output").join("feed.xml")).unwrap()
+        ),
+        xml_string_to_events(
+            r##"
+<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom" xml:lang="en">
+   <id>https://gamediary.dev/</id>
+   <title>Diary</title>
+   <updated>2021-12-08T00:00:00Z</updated>
+   <link rel="self" href="https://gamediary.dev/feed.xml" />
+   <link rel="alternate" href="https://gamediary.dev/" />
+   <entry>
+      <id>https://gamediary.dev/interesting_article</id>
+      <title type="html">Some article about something</title>
+      <updated>2021-12-06T09:25:00Z</updated>
+      <published>2021-12-08T00:00:00Z</published>
+      <summary>some really interesting descritpion</summary>
+      <content type="html" />
+   </entry>
+</feed>
+"##
+        ),
+    );
+}
+
+#[tokio::test]
+async fn year_feeds() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"
+            {
+              "url": "https://gamediary.dev",
+              "feed_generator": false
+            }
+        "#,
+    )
+    .unwrap();
+
+    let generator = Generator::new(
+        &cwd,
+        vec![
+            new_entry(
+                "cf2bacc9d75c4226aab53601c336f295",
+                "Day 0: Nannou, helping L, and lots of noise",
+                "Every journey starts with 1 O'clock: assistance.",
+                Some("2020-12-31".parse().unwrap()),
+                Some(date!(2020 - 12 - 31)),
+            ),
+            new_entry(
+                "ac3fb543001f4be5a25e4978abd05b1d",
+                "Day 1: Down the rabbit hole we go",
+                "Alice starts making games.",
+                Some("2021-01-01".parse().unwrap()),
+                Some(date!(2021 - 01 - 01)),
+            ),
+        ],
+    )
+    .await
+    .unwrap();
+    generator
+        .generate_year_feeds()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(
+        DirEntry::breakdown(cwd.path().join("output")),
+        DirEntry::dir(
+            "output",
+            [
+                DirEntry::dir("2020", [DirEntry::file("feed.xml")]),
+                DirEntry::dir("2021", [DirEntry::file("feed.xml")]),
+            ]
+        ),
+    );
+    assert_eq!(
+        xml_string_to_events(&fs::read_to_string(cwd.path().join("output/2020/feed.xml")).unwrap()),
+        xml_string_to_events(
+            r##"
+<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom" xml:lang="en">
+   <id>https://gamediary.dev/2020</id>
+   <title>Diary - 2020</title>
+   <updated>2020-12-31T00:00:00Z</updated>
+   <link rel="self" href="https://gamediary.dev/2020/feed.xml" />
+   <link rel="alternate" href="https://gamediary.dev/2020" />
+   <entry>
+      <id>https://gamediary.dev/2020/12/31</id>
+      <title type="html">Day 0: Nannou, helping L, and lots of noise</title>
+      <updated>2021-12-06T09:25:00Z</updated>
+      <published>2020-12-31T00:00:00Z</published>
+      <summary>Every journey starts with 1 O'clock: assistance.</summary>
+      <content type="html" />
+   </entry>
+</feed>
+"##
+        ),
+    );
+}
+
+#[tokio::test]
+async fn year_feeds_skip_years_before_archive_before() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"
+            {
+              "url": "https://gamediary.dev",
+              "archive_before": 2021
+            }
+        "#,
+    )
+    .unwrap();
+
+    let generator = Generator::new(
+        &cwd,
+        vec![
+            new_entry(
+                "cf2bacc9d75c4226aab53601c336f295",
+                "Day 0: Nannou, helping L, and lots of noise",
+                "Every journey starts with 1 O'clock: assistance.",
+                Some("2020-12-31".parse().unwrap()),
+                Some(date!(2020 - 12 - 31)),
+            ),
+            new_entry(
+                "ac3fb543001f4be5a25e4978abd05b1d",
+                "Day 1: Down the rabbit hole we go",
+                "Alice starts making games.",
+                Some("2021-01-01".parse().unwrap()),
+                Some(date!(2021 - 01 - 01)),
+            ),
+        ],
+    )
+    .await
+    .unwrap();
+    generator
+        .generate_year_feeds()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(
+        DirEntry::breakdown(cwd.path().join("output")),
+        DirEntry::dir(
+            "output",
+            [DirEntry::dir("2021", [DirEntry::file("feed.xml")])]
+        ),
+    );
+}
+
+#[tokio::test]
+async fn month_feeds() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"
+            {
+              "url": "https://gamediary.dev",
+              "feed_generator": false
+            }
+        "#,
+    )
+    .unwrap();
+
+    let generator = Generator::new(
+        &cwd,
+        vec![
+            new_entry(
+                "cf2bacc9d75c4226aab53601c336f295",
+                "Day 0: Nannou, helping L, and lots of noise",
+                "Every journey starts with 1 O'clock: assistance.",
+                Some("2021-11-30".parse().unwrap()),
+                Some(date!(2021 - 11 - 30)),
+            ),
+            new_entry(
+                "ac3fb543001f4be5a25e4978abd05b1d",
+                "Day 1: Down the rabbit hole we go",
+                "Alice starts making games.",
+                Some("2022-02-01".parse().unwrap()),
+                Some(date!(2022 - 02 - 01)),
+            ),
+        ],
+    )
+    .await
+    .unwrap();
+    generator
+        .generate_month_feeds()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    // The months in between without any entries don't get a feed
+    assert_eq!(
+        DirEntry::breakdown(cwd.path().join("output")),
+        DirEntry::dir(
+            "output",
+            [
+                DirEntry::dir("2021", [DirEntry::dir("11", [DirEntry::file("feed.xml")])]),
+                DirEntry::dir("2022", [DirEntry::dir("02", [DirEntry::file("feed.xml")])]),
+            ]
+        ),
+    );
+    assert_eq!(
+        xml_string_to_events(
+            &fs::read_to_string(cwd.path().join("output/2021/11/feed.xml")).unwrap()
+        ),
+        xml_string_to_events(
+            r##"
+<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom" xml:lang="en">
+   <id>https://gamediary.dev/2021/11</id>
+   <title>Diary - November 2021</title>
+   <updated>2021-11-30T00:00:00Z</updated>
+   <link rel="self" href="https://gamediary.dev/2021/11/feed.xml" />
+   <link rel="alternate" href="https://gamediary.dev/2021/11" />
+   <entry>
+      <id>https://gamediary.dev/2021/11/30</id>
+      <title type="html">Day 0: Nannou, helping L, and lots of noise</title>
+      <updated>2021-12-06T09:25:00Z</updated>
+      <published>2021-11-30T00:00:00Z</published>
+      <summary>Every journey starts with 1 O'clock: assistance.</summary>
+      <content type="html" />
+   </entry>
+</feed>
+"##
+        ),
+    );
+}
+
+#[tokio::test]
+async fn control_characters_are_stripped() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"
+            {
+              "url": "https://gamediary.dev",
+              "feed_generator": false
+            }
+        "#,
+    )
+    .unwrap();
+
+    let generator = Generator::new(
+        &cwd,
+        vec![new_article(
+            "78abd05b1dac3fb543001f4be5a25e49",
+            "Some article\u{b} about something",
+            "some really interesting descritpion",
+            "interesting_article",
+            Some(date!(2021 - 12 - 08)),
+        )],
+    )
+    .await
+    .unwrap();
+    generator
+        .generate_atom_feed()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(
+        xml_string_to_events(
+            &fs::read_to_string(cwd.path().join("output").join("feed.xml")).unwrap()
+        ),
+        xml_string_to_events(
+            r##"
+<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom" xml:lang="en">
+   <id>https://gamediary.dev/</id>
+   <title>Diary</title>
+   <updated>2021-12-08T00:00:00Z</updated>
+   <link rel="self" href="https://gamediary.dev/feed.xml" />
+   <link rel="alternate" href="https://gamediary.dev/" />
+   <entry>
+      <id>https://gamediary.dev/interesting_article</id>
+      <title type="html">Some article about something</title>
+      <updated>2021-12-06T09:25:00Z</updated>
+      <published>2021-12-08T00:00:00Z</published>
+      <summary>some really interesting descritpion</summary>
+      <content type="html" />
+   </entry>
+</feed>
+"##
+        ),
+    );
+}
+
+#[tokio::test]
+async fn content_is_cut_between_blocks() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{"url": "https://example.com", "feed_content_max_chars": 80}"#,
+    )
+    .unwrap();
+
+    let paragraph = |id: &str, content: &str| Block {
+        object: "block".to_string(),
+        id: id.parse().unwrap(),
+        created_time: "2021-11-15T18:03:00.000Z".to_string(),
+        last_edited_time: "2021-11-16T11:23:00.000Z".to_string(),
+        has_children: false,
+        archived: false,
+        ty: BlockType::Paragraph {
+            text: vec![RichText {
+                plain_text: content.to_string(),
+                href: None,
+                annotations: Default::default(),
+                ty: RichTextType::Text {
+                    content: content.to_string(),
+                    link: None,
+                },
+            }],
+            children: vec![],
+        },
+    };
+
+    let generator = Generator::new(
+        &cwd,
+        vec![Page {
+            children: vec![
+                paragraph(
+                    "4fb9dd79-2fc7-45b1-b3a2-8efae49992ed",
+                    "The first paragraph",
+                ),
+                paragraph(
+                    "817c0ca1-721a-4565-ac54-eedbbe471f0b",
+                    "The second paragraph",
+                ),
+            ],
+            ..new_entry(
+                "cf2bacc9d75c4226aab53601c336f295",
+                "Day 0: Nannou, helping L, and lots of noise",
+                "Every journey starts with 1 O'clock: assistance.",
+                Some("2021-11-07".parse().unwrap()),
+                Some(date!(2021 - 12 - 05)),
+            )
+        }],
+    )
+    .await
+    .unwrap();
+    generator
+        .generate_atom_feed()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let feed = fs::read_to_string(cwd.path().join("output").join("feed.xml")).unwrap();
+    assert!(feed.contains("The first paragraph"));
+    assert!(!feed.contains("The second paragraph"));
+    assert!(feed.contains(
+        "&lt;p&gt;&lt;a href=&quot;https://example.com/2021/11/07&quot;&gt;Read more&lt;/a&gt;&lt;/p&gt;</content>"
+    ));
+}
+
+#[tokio::test]
+async fn updates_feed() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{"url": "https://gamediary.dev", "feed_generator": false}"#,
+    )
+    .unwrap();
+
+    let generator = Generator::new(
+        &cwd,
+        vec![
+            new_entry(
+                "cf2bacc9d75c4226aab53601c336f295",
+                "Day 0: Nannou, helping L, and lots of noise",
+                "Every journey starts with 1 O'clock: assistance.",
+                Some("2021-11-07".parse().unwrap()),
+                Some(date!(2021 - 11 - 07)),
+            ),
+            Page {
+                last_edited_time: "2022-03-01T10:00:00.000Z".to_string(),
+                ..new_entry(
+                    "ac3fb543001f4be5a25e4978abd05b1d",
+                    "Day 1: Down the rabbit hole we go",
+                    "Alice starts making games.",
+                    Some("2021-11-08".parse().unwrap()),
+                    Some(date!(2021 - 11 - 08)),
+                )
+            },
+        ],
+    )
+    .await
+    .unwrap();
+    generator
+        .generate_updates_feed()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let feed = fs::read_to_string(cwd.path().join("output").join("updates.xml")).unwrap();
+    assert!(feed.contains(concat!(
+        "<id>https://gamediary.dev/updates.xml</id>",
+        "<title>Updates - Diary</title>",
+        "<updated>2022-03-01T10:00:00Z</updated>"
+    )));
+    // The entry edited last comes first even though it was published last
+    let day_0 = feed
+        .find("<id>https://gamediary.dev/2021/11/07</id>")
+        .unwrap();
+    let day_1 = feed
+        .find("<id>https://gamediary.dev/2021/11/08</id>")
+        .unwrap();
+    assert!(day_1 < day_0);
+}
+
+#[tokio::test]
+async fn summary_separator() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{"url": "https://example.com", "summary_separator": {}}"#,
+    )
+    .unwrap();
+
+    let block = |id: &str, ty: BlockType| Block {
+        object: "block".to_string(),
+        id: id.parse().unwrap(),
+        created_time: "2021-11-15T18:03:00.000Z".to_string(),
+        last_edited_time: "2021-11-16T11:23:00.000Z".to_string(),
+        has_children: false,
+        archived: false,
+        ty,
+    };
+    let paragraph = |id: &str, content: &str| {
+        block(
+            id,
+            BlockType::Paragraph {
+                text: vec![RichText {
+                    plain_text: content.to_string(),
+                    href: None,
+                    annotations: Default::default(),
+                    ty: RichTextType::Text {
+                        content: content.to_string(),
+                        link: None,
+                    },
+                }],
+                children: vec![],
+            },
+        )
+    };
+
+    let generator = Generator::new(
+        &cwd,
+        vec![
+            Page {
+                children: vec![
+                    paragraph("4fb9dd79-2fc7-45b1-b3a2-8efae49992ed", "The summary"),
+                    paragraph("817c0ca1-721a-4565-ac54-eedbbe471f0b", "<!-- more -->"),
+                    paragraph("91ac3b4e-5c2f-4c0a-9a3b-1c6c2f0d7e11", "The rest"),
+                ],
+                ..new_entry(
+                    "cf2bacc9d75c4226aab53601c336f295",
+                    "Day 0: Nannou, helping L, and lots of noise",
+                    "Every journey starts with 1 O'clock: assistance.",
+                    Some("2021-11-07".parse().unwrap()),
+                    Some(date!(2021 - 12 - 05)),
+                )
+            },
+            Page {
+                children: vec![
+                    paragraph("2a4c3f1e-8d7b-4e6a-b5c9-0f1e2d3c4b5a", "Above the divider"),
+                    block("6b5a4c3d-2e1f-4a0b-9c8d-7e6f5a4b3c2d", BlockType::Divider),
+                    paragraph("0c1d2e3f-4a5b-4c6d-8e7f-9a0b1c2d3e4f", "Below the divider"),
+                ],
+                ..new_entry(
+                    "ac3fb543001f4be5a25e4978abd05b1d",
+                    "Day 1: Down the rabbit hole we go",
+                    "Alice starts making games.",
+                    Some("2021-11-08".parse().unwrap()),
+                    Some(date!(2021 - 12 - 05)),
+                )
+            },
+            new_entry(
+                "e4b3e1e6b5d74d9a8f3b5c2f4a1b0c9d",
+                "Day 2: No separator",
+                "Falls back to the description.",
+                Some("2021-11-09".parse().unwrap()),
+                Some(date!(2021 - 12 - 05)),
+            ),
+            Page {
+                children: vec![
+                    paragraph("5d6e7f8a-9b0c-4d1e-8f2a-3b4c5d6e7f8a", "<!-- more -->"),
+                    paragraph("7f8a9b0c-1d2e-4f3a-9b4c-5d6e7f8a9b0c", "Nothing above"),
+                ],
+                ..new_entry(
+                    "f1e2d3c4b5a64978a8b7c6d5e4f3a2b1",
+                    "Day 3: Separator first",
+                    "Also falls back to the description.",
+                    Some("2021-11-10".parse().unwrap()),
+                    Some(date!(2021 - 12 - 05)),
+                )
+            },
+        ],
+    )
+    .await
+    .unwrap();
+    generator
+        .generate_atom_feed()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+    generator
+        .generate_index_page()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let feed = fs::read_to_string(cwd.path().join("output").join("feed.xml")).unwrap();
+    assert!(feed.contains(r#"<summary type="html">&lt;p id="#));
+    assert!(feed.contains("The summary&lt;/p&gt;</summary>"));
+    assert!(feed.contains("Above the divider&lt;/p&gt;</summary>"));
+    assert!(feed.contains("<summary>Falls back to the description.</summary>"));
+    assert!(!feed.contains("more --"));
+
+    let index = fs::read_to_string(cwd.path().join("output").join("index.html")).unwrap();
+    assert!(index.contains("The summary</p></div>"));
+    assert!(!index.contains("The rest"));
+    assert!(index.contains("Above the divider</p></div>"));
+    assert!(!index.contains("Below the divider"));
+    assert!(index.contains(r#"<p itemprop="description">Falls back to the description.</p>"#));
+    // A separator before anything else leaves no excerpt to show
+    assert!(index.contains(r#"<p itemprop="description">Also falls back to the description.</p>"#));
+    assert!(!index.contains(r#"<div itemprop="description"></div>"#));
+    assert!(feed.contains("<summary>Also falls back to the description.</summary>"));
+
+    let day = fs::read_to_string(cwd.path().join("output/2021/11/07.html")).unwrap();
+    assert!(day.contains("The summary"));
+    assert!(day.contains("The rest"));
+    assert!(!day.contains("more --"));
+}
+
+#[tokio::test]
+async fn feed_sources() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{"url": "https://gamediary.dev", "feed_sources": "entries"}"#,
+    )
+    .unwrap();
+
+    let generator = Generator::new(
+        &cwd,
+        vec![
+            new_entry(
+                "cf2bacc9d75c4226aab53601c336f295",
+                "Day 0: Nannou, helping L, and lots of noise",
+                "Every journey starts with 1 O'clock: assistance.",
+                Some("2021-11-07".parse().unwrap()),
+                Some(date!(2021 - 11 - 07)),
+            ),
+            new_article(
+                "78abd05b1dac3fb543001f4be5a25e49",
+                "Some article about something",
+                "some really interesting descritpion",
+                "interesting_article",
+                Some(date!(2021 - 12 - 08)),
+            ),
+        ],
+    )
+    .await
+    .unwrap();
+    generator
+        .generate_atom_feed()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let feed = fs::read_to_string(cwd.path().join("output").join("feed.xml")).unwrap();
+    assert!(feed.contains("Day 0: Nannou, helping L, and lots of noise"));
+    assert!(!feed.contains("Some article about something"));
+    // The feed last changed when the last entry was published, not the left out article
+    assert!(feed.contains("<updated>2021-11-07T00:00:00Z</updated>"));
+    assert!(!feed.contains("2021-12-08"));
+}
+
+#[tokio::test]
+async fn feed_summary_property() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{"url": "https://example.com"}"#,
+    )
+    .unwrap();
+
+    let entry = new_entry(
+        "cf2bacc9d75c4226aab53601c336f295",
+        "Day 0: Nannou, helping L, and lots of noise",
+        "Every journey starts with 1 O'clock: assistance.",
+        Some("2021-11-07".parse().unwrap()),
+        Some(date!(2021 - 12 - 05)),
+    );
+    let blurb = "A longer blurb about Nannou, helping L and all of the noise, just for the feed.";
+    let generator = Generator::new(
+        &cwd,
+        vec![
+            Page {
+                properties: Properties {
+                    feed_summary: Some(RichTextProperty {
+                        id: "fs%3Ds".to_string(),
+                        rich_text: vec![RichText {
+                            plain_text: blurb.to_string(),
+                            href: None,
+                            annotations: Default::default(),
+                            ty: RichTextType::Text {
+                                content: blurb.to_string(),
+                                link: None,
+                            },
+                        }],
+                    }),
+                    ..entry.properties
+                },
+                ..entry
+            },
+            new_entry(
+                "ac3fb543001f4be5a25e4978abd05b1d",
+                "Day 1: Down the rabbit hole we go",
+                "Alice starts making games.",
+                Some("2021-11-08".parse().unwrap()),
+                Some(date!(2021 - 12 - 05)),
+            ),
+        ],
+    )
+    .await
+    .unwrap();
+    generator
+        .generate_atom_feed()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let feed = fs::read_to_string(cwd.path().join("output").join("feed.xml")).unwrap();
+    assert!(feed.contains(&format!("<summary>{}</summary>", blurb)));
+    assert!(!feed.contains("<summary>Every journey"));
+    assert!(feed.contains("<summary>Alice starts making games.</summary>"));
+}
@@ -3,7 +3,7 @@ mod utils;
 use std::fs;
 
 use diary_generator::{Generator, Properties};
-use maud::{html, DOCTYPE};
+use maud::{html, PreEscaped, DOCTYPE};
 use notion_generator::response::{properties::DateProperty, Page};
 use pretty_assertions::assert_eq;
 use utils::{function, new_entry, DirEntry, TestDir};
@@ -104,6 +104,11 @@ async fn able_to_locate_partials() {
                     meta property="og:title" content="Diary";
                     meta property="og:description" content="A neat diary";
                     meta property="og:locale" content="en_US";
+                    meta property="og:type" content="website";
+                    meta property="og:site_name" content="Diary";
+                    script type="application/ld+json" {
+                        (PreEscaped(r#"{"@context":"https://schema.org","@type":"WebSite","description":"A neat diary","name":"Diary"}"#))
+                    }
                     link rel="icon" href="/favicon.ico" sizes="any";
                 }
                 body {
@@ -0,0 +1,63 @@
+mod utils;
+
+use diary_generator::Generator;
+use serde_json::json;
+use std::fs;
+use utils::{function, new_entry, TestDir};
+
+#[tokio::test]
+async fn calendar_weeks_start_on_first_day_of_week() {
+    let cwd = TestDir::new(function!());
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "first_day_of_week": "monday" }"#,
+    )
+    .unwrap();
+
+    let generator = Generator::new(
+        &cwd,
+        vec![
+            new_entry(
+                "cf2bacc9d75c4226aab53601c336f295",
+                "Day 0: Nannou, helping L, and lots of noise",
+                "Every journey starts with 1 O'clock: assistance.",
+                Some("2021-11-07".parse().unwrap()),
+                None,
+            ),
+            new_entry(
+                "ac3fb543001f4be5a25e4978abd05b1d",
+                "Day 1: Down the rabbit hole we go",
+                "Alice starts making games.",
+                Some("2021-11-08".parse().unwrap()),
+                None,
+            ),
+            new_entry(
+                "78abd05b1dac3fb543001f4be5a25e49",
+                "Day 2: Trains",
+                "Watching trains with the loveliest coding conductor.",
+                Some("2021-11-09".parse().unwrap()),
+                None,
+            ),
+        ],
+    )
+    .await
+    .unwrap();
+    generator
+        .generate_calendar_data()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let calendar: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(cwd.path().join("output/calendar.json")).unwrap())
+            .unwrap();
+    assert_eq!(
+        calendar,
+        json!([
+            { "date": "2021-11-07", "week": "2021-11-01", "count": 1, "url": "/2021/11/07" },
+            { "date": "2021-11-08", "week": "2021-11-08", "count": 1, "url": "/2021/11/08" },
+            { "date": "2021-11-09", "week": "2021-11-08", "count": 1, "url": "/2021/11/09" }
+        ])
+    );
+}
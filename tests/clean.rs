@@ -0,0 +1,113 @@
+mod utils;
+
+use diary_generator::{Generator, Options, Properties};
+use notion_generator::response::Page;
+use std::{fs, path::Path};
+use utils::{function, new_entry, TestDir};
+
+fn entries() -> Vec<Page<Properties>> {
+    vec![new_entry(
+        "cf2bacc9d75c4226aab53601c336f295",
+        "Day 0: Nannou, helping L, and lots of noise",
+        "Every journey starts with 1 O'clock: assistance.",
+        Some("2021-11-07".parse().unwrap()),
+        None,
+    )]
+}
+
+async fn generator_into(cwd: &TestDir, output: &Path) -> Generator {
+    Generator::with_options(
+        cwd,
+        entries(),
+        Options {
+            output_dir: Some(output.to_path_buf()),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn cleans_generated_output() {
+    let cwd = TestDir::new(function!());
+
+    let generator = generator_into(&cwd, &cwd.path().join("output")).await;
+    generator.generate_output_marker().await.unwrap().unwrap();
+    fs::write(cwd.path().join("output/stale.html"), "").unwrap();
+
+    generator.clean_output().await.unwrap();
+    assert!(!cwd.path().join("output").exists());
+
+    // Nothing to clean
+    generator.clean_output().await.unwrap();
+}
+
+#[tokio::test]
+async fn refuses_to_clean_sources() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(cwd.path().join("config.json"), r#"{ "pages_dir": "site" }"#).unwrap();
+    for source in ["public", "site", "partials", "fonts"] {
+        fs::create_dir(cwd.path().join(source)).unwrap();
+    }
+
+    for output in [".", "public", "site", "partials", "fonts"] {
+        let output = cwd.path().join(output);
+        let generator = generator_into(&cwd, &output).await;
+        generator.generate_output_marker().await.unwrap().unwrap();
+
+        let error = generator.clean_output().await.unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("which the diary is generated from"),
+            "{}",
+            error
+        );
+        assert!(output.is_dir());
+    }
+}
+
+#[tokio::test]
+async fn refuses_to_clean_directories_it_did_not_generate() {
+    let cwd = TestDir::new(function!());
+
+    fs::create_dir(cwd.path().join("other-project")).unwrap();
+    fs::write(cwd.path().join("other-project/main.rs"), "fn main() {}").unwrap();
+
+    let generator = generator_into(&cwd, &cwd.path().join("other-project")).await;
+    let error = generator.clean_output().await.unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("the diary wasn't generated into it before"));
+    assert!(cwd.path().join("other-project/main.rs").is_file());
+}
+
+#[tokio::test]
+async fn keeps_output_without_entries_to_generate() {
+    let cwd = TestDir::new(function!());
+
+    let output = cwd.path().join("output");
+    generator_into(&cwd, &output)
+        .await
+        .generate_output_marker()
+        .await
+        .unwrap()
+        .unwrap();
+    fs::create_dir_all(output.join("2021/11")).unwrap();
+    fs::write(output.join("2021/11/07.html"), "").unwrap();
+
+    let generator = Generator::with_options(
+        &cwd,
+        Vec::new(),
+        Options {
+            output_dir: Some(output.clone()),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    generator.clean_output().await.unwrap();
+    assert!(output.join("2021/11/07.html").is_file());
+}
@@ -0,0 +1,98 @@
+mod utils;
+
+use diary_generator::Generator;
+use std::fs;
+use utils::{function, new_entry, TestDir};
+
+async fn config_error(cwd: TestDir, config: &str) -> String {
+    fs::write(cwd.path().join("config.json"), config).unwrap();
+
+    let pages = vec![new_entry(
+        "cf2bacc9d75c4226aab53601c336f295",
+        "Day 0: Nannou, helping L, and lots of noise",
+        "Every journey starts with 1 O'clock: assistance.",
+        Some("2021-11-07".parse().unwrap()),
+        None,
+    )];
+
+    let error = Generator::new(&cwd, pages).await.err().unwrap();
+    format!("{:#}", error)
+}
+
+#[tokio::test]
+async fn url_without_scheme_is_rejected() {
+    assert_eq!(
+        config_error(TestDir::new(function!()), r#"{ "url": "example.com" }"#).await,
+        "Failed to parse config.json: invalid value: string \"example.com\", \
+expected an absolute http or https URL at line 1 column 24"
+    );
+}
+
+#[tokio::test]
+async fn url_with_other_scheme_is_rejected() {
+    assert_eq!(
+        config_error(
+            TestDir::new(function!()),
+            r#"{ "url": "ftp://example.com/diary" }"#
+        )
+        .await,
+        "Failed to parse config.json: invalid value: string \"ftp://example.com/diary\", \
+expected an absolute http or https URL at line 1 column 36"
+    );
+}
+
+#[tokio::test]
+async fn unknown_first_day_of_week_is_rejected() {
+    assert_eq!(
+        config_error(
+            TestDir::new(function!()),
+            r#"{ "first_day_of_week": "someday" }"#
+        )
+        .await,
+        "Failed to parse config.json: invalid value: string \"someday\", \
+expected a day of the week (i.e monday) at line 1 column 34"
+    );
+}
+
+#[tokio::test]
+async fn preconnect_with_path_is_rejected() {
+    assert_eq!(
+        config_error(
+            TestDir::new(function!()),
+            r#"{ "preconnect": ["https://comments.example.com/embed.js"] }"#
+        )
+        .await,
+        "Failed to parse config.json: invalid value: string \"https://comments.example.com/embed.js\", \
+expected an http or https origin (i.e https://example.com) at line 1 column 59"
+    );
+}
+
+#[tokio::test]
+async fn security_txt_already_expired_is_rejected() {
+    assert_eq!(
+        config_error(
+            TestDir::new(function!()),
+            r#"{ "security": { "contact": "mailto:me@example.com", "expires_in_days": 0 } }"#
+        )
+        .await,
+        "Failed to parse config.json: invalid value: integer `0`, expected a nonzero u16 \
+at line 1 column 73"
+    );
+}
+
+#[tokio::test]
+async fn negative_max_years_ahead_is_rejected() {
+    assert_eq!(
+        config_error(TestDir::new(function!()), r#"{ "max_years_ahead": -1 }"#).await,
+        "Failed to parse config.json: invalid value: integer `-1`, expected u16 \
+at line 1 column 24"
+    );
+}
+
+#[tokio::test]
+async fn max_years_ahead_past_supported_dates_is_rejected() {
+    assert_eq!(
+        config_error(TestDir::new(function!()), r#"{ "max_years_ahead": 65535 }"#).await,
+        "max_years_ahead of 65535 is past the latest supported date"
+    );
+}
@@ -0,0 +1,1488 @@
+mod utils;
+
+use diary_generator::{
+    CheckboxProperty, FilesProperty, Generator, MultiSelectProperty, Properties, SelectOption,
+    UrlProperty,
+};
+use notion_generator::response::{
+    properties::{DateProperty, RichTextProperty},
+    Block, BlockType, Emoji, EmojiOrFile, File, NotionDate, Page, RichText, RichTextType,
+};
+use std::fs;
+use time::macros::date;
+use utils::{function, new_article, new_entry, TestDir};
+
+fn tagged(page: Page<Properties>, tags: &[&str]) -> Page<Properties> {
+    Page {
+        properties: Properties {
+            tags: Some(MultiSelectProperty {
+                id: "%3BtNr".to_string(),
+                multi_select: tags
+                    .iter()
+                    .map(|tag| SelectOption {
+                        id: tag.to_string(),
+                        name: tag.to_string(),
+                        color: "default".to_string(),
+                    })
+                    .collect(),
+            }),
+            ..page.properties
+        },
+        ..page
+    }
+}
+
+#[tokio::test]
+async fn updated_after_publishing() {
+    let cwd = TestDir::new(function!());
+
+    let generator = Generator::new(
+        &cwd,
+        vec![
+            new_entry(
+                "cf2bacc9d75c4226aab53601c336f295",
+                "Day 0: Nannou, helping L, and lots of noise",
+                "Every journey starts with 1 O'clock: assistance.",
+                Some("2021-11-07".parse().unwrap()),
+                Some(date!(2021 - 11 - 07)),
+            ),
+            new_entry(
+                "ac3fb543001f4be5a25e4978abd05b1d",
+                "Day 1: Down the rabbit hole we go",
+                "Alice starts making games.",
+                Some("2021-12-05".parse().unwrap()),
+                Some(date!(2021 - 12 - 05)),
+            ),
+        ],
+    )
+    .await
+    .unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+
+    let edited = fs::read_to_string(cwd.path().join("output/2021/11/07.html")).unwrap();
+    assert!(edited.contains(
+        r#"<p class="updated">Updated <time datetime="2021-12-06" itemprop="dateModified">December 06, 2021</time></p>"#
+    ));
+
+    // Edited the day after publishing
+    let touched_up = fs::read_to_string(cwd.path().join("output/2021/12/05.html")).unwrap();
+    assert!(!touched_up.contains("Updated"));
+}
+
+#[tokio::test]
+async fn emoji_icons_become_favicons() {
+    let cwd = TestDir::new(function!());
+
+    let generator = Generator::new(
+        &cwd,
+        vec![
+            Page {
+                icon: Some(EmojiOrFile::Emoji(Emoji {
+                    emoji: "🎮".to_string(),
+                })),
+                ..new_entry(
+                    "cf2bacc9d75c4226aab53601c336f295",
+                    "Day 0: Nannou, helping L, and lots of noise",
+                    "Every journey starts with 1 O'clock: assistance.",
+                    Some("2021-11-07".parse().unwrap()),
+                    None,
+                )
+            },
+            new_entry(
+                "ac3fb543001f4be5a25e4978abd05b1d",
+                "Day 1: Down the rabbit hole we go",
+                "Alice starts making games.",
+                Some("2021-11-08".parse().unwrap()),
+                None,
+            ),
+        ],
+    )
+    .await
+    .unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+
+    let with_icon = fs::read_to_string(cwd.path().join("output/2021/11/07.html")).unwrap();
+    assert!(with_icon.contains(concat!(
+        r#"<link rel="icon" href="data:image/svg+xml,%3Csvg%20xmlns='http://www.w3.org/2000/svg'%20"#,
+        r#"viewBox='0%200%20100%20100'%3E%3Ctext%20y='.9em'%20font-size='90'%3E%F0%9F%8E%AE"#,
+        r#"%3C/text%3E%3C/svg%3E"></head>"#
+    )));
+
+    let without_icon = fs::read_to_string(cwd.path().join("output/2021/11/08.html")).unwrap();
+    assert!(!without_icon.contains(r#"rel="icon""#));
+}
+
+#[tokio::test]
+async fn related_entries_share_tags() {
+    let cwd = TestDir::new(function!());
+
+    let generator = Generator::new(
+        &cwd,
+        vec![
+            tagged(
+                new_entry(
+                    "cf2bacc9d75c4226aab53601c336f295",
+                    "Day 0: Nannou, helping L, and lots of noise",
+                    "Every journey starts with 1 O'clock: assistance.",
+                    Some("2021-11-07".parse().unwrap()),
+                    None,
+                ),
+                &["rust", "nannou"],
+            ),
+            tagged(
+                new_entry(
+                    "ac3fb543001f4be5a25e4978abd05b1d",
+                    "Day 1: Down the rabbit hole we go",
+                    "Alice starts making games.",
+                    Some("2021-11-08".parse().unwrap()),
+                    None,
+                ),
+                &["rust"],
+            ),
+            tagged(
+                new_entry(
+                    "5f5e3d4b9b7a4a3c8d8e2f1a0b9c8d7e",
+                    "Day 2: Enter Bevy & Shaders are hard",
+                    "Bevy time.",
+                    Some("2021-11-09".parse().unwrap()),
+                    None,
+                ),
+                &["rust", "nannou"],
+            ),
+            new_entry(
+                "0b9c8d7e5f5e3d4b9b7a4a3c8d8e2f1a",
+                "Day 3: Resting",
+                "Nothing today.",
+                Some("2021-11-10".parse().unwrap()),
+                None,
+            ),
+        ],
+    )
+    .await
+    .unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+
+    let day_0 = fs::read_to_string(cwd.path().join("output/2021/11/07.html")).unwrap();
+    let start = day_0.find(r#"<section class="related">"#).unwrap();
+    let related = &day_0[start..start + day_0[start..].find("</section>").unwrap()];
+    let day_2 = related.find(r#"href="/2021/11/09""#).unwrap();
+    let day_1 = related.find(r#"href="/2021/11/08""#).unwrap();
+    // Sharing both tags ranks above sharing one
+    assert!(day_2 < day_1);
+    assert!(!related.contains(r#"href="/2021/11/07""#));
+    assert!(!related.contains(r#"href="/2021/11/10""#));
+
+    let untagged = fs::read_to_string(cwd.path().join("output/2021/11/10.html")).unwrap();
+    assert!(!untagged.contains(r#"class="related""#));
+}
+
+#[tokio::test]
+async fn duplicate_dates_are_rejected() {
+    let cwd = TestDir::new(function!());
+
+    let error = Generator::new(
+        &cwd,
+        vec![
+            new_entry(
+                "cf2bacc9d75c4226aab53601c336f295",
+                "Day 0: Nannou, helping L, and lots of noise",
+                "Every journey starts with 1 O'clock: assistance.",
+                Some("2021-11-07".parse().unwrap()),
+                None,
+            ),
+            new_entry(
+                "ac3fb543001f4be5a25e4978abd05b1d",
+                "Day 0 at work",
+                "Meetings.",
+                Some("2021-11-07".parse().unwrap()),
+                None,
+            ),
+        ],
+    )
+    .await
+    .err()
+    .unwrap();
+
+    assert!(error.to_string().ends_with("both have the date 2021-11-07"));
+}
+
+#[tokio::test]
+async fn author_link() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "author": { "name": "Mathspy", "url": "https://mathspy.dev/" } }"#,
+    )
+    .unwrap();
+
+    let generator = Generator::new(
+        &cwd,
+        vec![new_entry(
+            "cf2bacc9d75c4226aab53601c336f295",
+            "Day 0: Nannou, helping L, and lots of noise",
+            "Every journey starts with 1 O'clock: assistance.",
+            Some("2021-11-07".parse().unwrap()),
+            None,
+        )],
+    )
+    .await
+    .unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+
+    let day = fs::read_to_string(cwd.path().join("output/2021/11/07.html")).unwrap();
+    assert!(day.contains(
+        r#"<meta name="author" content="Mathspy"><link rel="author" href="https://mathspy.dev/">"#
+    ));
+}
+
+#[tokio::test]
+async fn paging_includes_articles() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "paging_includes_articles": true }"#,
+    )
+    .unwrap();
+
+    let generator = Generator::new(
+        &cwd,
+        vec![
+            new_entry(
+                "cf2bacc9d75c4226aab53601c336f295",
+                "Day 0: Nannou, helping L, and lots of noise",
+                "Every journey starts with 1 O'clock: assistance.",
+                Some("2021-11-07".parse().unwrap()),
+                None,
+            ),
+            new_article(
+                "78abd05b1dac3fb543001f4be5a25e49",
+                "Some article about something",
+                "some really interesting descritpion",
+                "interesting_article",
+                Some(date!(2021 - 11 - 08)),
+            ),
+            new_entry(
+                "ac3fb543001f4be5a25e4978abd05b1d",
+                "Day 1: Down the rabbit hole we go",
+                "Alice starts making games.",
+                Some("2021-11-09".parse().unwrap()),
+                None,
+            ),
+        ],
+    )
+    .await
+    .unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+    generator
+        .generate_article_pages()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let day_0 = fs::read_to_string(cwd.path().join("output/2021/11/07.html")).unwrap();
+    assert!(day_0.contains(
+        r#"<a href="/interesting_article"><article><p>Next up:</p><header><h3>Some article about something</h3>"#
+    ));
+
+    let article = fs::read_to_string(cwd.path().join("output/interesting_article.html")).unwrap();
+    assert!(article.contains(r#"<a href="/2021/11/07"><article><p>Previously:</p>"#));
+    assert!(article.contains(r#"<a href="/2021/11/09"><article><p>Next up:</p>"#));
+
+    let day_1 = fs::read_to_string(cwd.path().join("output/2021/11/09.html")).unwrap();
+    assert!(day_1.contains(r#"<a href="/interesting_article"><article><p>Previously:</p>"#));
+}
+
+#[tokio::test]
+async fn paging_wording_for_small_gaps() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "paging": { "max_gap_days": 3, "in_days": "Dans {} jours :" } }"#,
+    )
+    .unwrap();
+
+    let generator = Generator::new(
+        &cwd,
+        vec![
+            new_entry(
+                "cf2bacc9d75c4226aab53601c336f295",
+                "Day 0: Nannou, helping L, and lots of noise",
+                "Every journey starts with 1 O'clock: assistance.",
+                Some("2021-11-07".parse().unwrap()),
+                None,
+            ),
+            new_entry(
+                "ac3fb543001f4be5a25e4978abd05b1d",
+                "Day 1: Down the rabbit hole we go",
+                "Alice starts making games.",
+                Some("2021-11-10".parse().unwrap()),
+                None,
+            ),
+            new_entry(
+                "78abd05b1dac3fb543001f4be5a25e49",
+                "Day 2: Enter Bevy & Shaders are hard",
+                "Bevy time.",
+                Some("2021-11-20".parse().unwrap()),
+                None,
+            ),
+        ],
+    )
+    .await
+    .unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+
+    let day_0 = fs::read_to_string(cwd.path().join("output/2021/11/07.html")).unwrap();
+    assert!(day_0.contains(r#"<a href="/2021/11/10"><article><p>Dans 3 jours :</p>"#));
+
+    let day_1 = fs::read_to_string(cwd.path().join("output/2021/11/10.html")).unwrap();
+    assert!(day_1.contains(r#"<a href="/2021/11/07"><article><p>3 days ago:</p>"#));
+    assert!(day_1.contains(r#"<a href="/2021/11/20"><article><p>Next up:</p>"#));
+}
+
+#[tokio::test]
+async fn back_to_top() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(cwd.path().join("config.json"), r#"{ "back_to_top": true }"#).unwrap();
+
+    let generator = Generator::new(
+        &cwd,
+        vec![new_entry(
+            "cf2bacc9d75c4226aab53601c336f295",
+            "Day 0: Nannou, helping L, and lots of noise",
+            "Every journey starts with 1 O'clock: assistance.",
+            Some("2021-11-07".parse().unwrap()),
+            None,
+        )],
+    )
+    .await
+    .unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+
+    let day = fs::read_to_string(cwd.path().join("output/2021/11/07.html")).unwrap();
+    assert!(day.contains(r#"<main id="content">"#));
+    assert!(day.contains(r##"<a class="back-to-top" href="#content">Back to top</a></main>"##));
+}
+
+#[tokio::test]
+async fn og_image_property_replaces_cover() {
+    let cwd = TestDir::new(function!());
+
+    let entry = new_entry(
+        "cf2bacc9d75c4226aab53601c336f295",
+        "Day 0: Nannou, helping L, and lots of noise",
+        "Every journey starts with 1 O'clock: assistance.",
+        Some("2021-11-07".parse().unwrap()),
+        None,
+    );
+    let generator = Generator::new(
+        &cwd,
+        vec![Page {
+            properties: Properties {
+                og_image: Some(
+                    serde_json::from_str::<FilesProperty>(
+                        r#"{
+                            "id": "og%7Bi",
+                            "files": [{
+                                "name": "share.png",
+                                "type": "external",
+                                "external": { "url": "https://gamediary.dev/share.png" }
+                            }]
+                        }"#,
+                    )
+                    .unwrap(),
+                ),
+                ..entry.properties
+            },
+            ..entry
+        }],
+    )
+    .await
+    .unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+
+    let day = fs::read_to_string(cwd.path().join("output/2021/11/07.html")).unwrap();
+    assert!(day.contains(r#"<meta property="og:image" content="/media/"#));
+    // Named after the entry's id so it can't overwrite the entry's cover
+    assert!(day.contains(r#"-og-image.png">"#));
+    assert!(!day.contains(r#"class="cover""#));
+}
+
+#[tokio::test]
+async fn empty_og_image_property_gets_a_share_card() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "generate_og_images": true }"#,
+    )
+    .unwrap();
+    let entry = new_entry(
+        "cf2bacc9d75c4226aab53601c336f295",
+        "Day 0: Nannou, helping L, and lots of noise",
+        "Every journey starts with 1 O'clock: assistance.",
+        Some("2021-11-07".parse().unwrap()),
+        None,
+    );
+    let generator = Generator::new(
+        &cwd,
+        vec![Page {
+            properties: Properties {
+                og_image: Some(
+                    serde_json::from_str::<FilesProperty>(r#"{ "id": "og%7Bi", "files": [] }"#)
+                        .unwrap(),
+                ),
+                ..entry.properties
+            },
+            ..entry
+        }],
+    )
+    .await
+    .unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+    generator
+        .generate_og_images()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let day = fs::read_to_string(cwd.path().join("output/2021/11/07.html")).unwrap();
+    assert!(day.contains(r#"<meta property="og:image" content="/og/2021-11-07.png">"#));
+    assert!(cwd.path().join("output/og/2021-11-07.png").is_file());
+}
+
+#[tokio::test]
+async fn pages_without_covers_get_a_share_card() {
+    let cwd = TestDir::new(function!());
+
+    let pages = || {
+        let covered = new_entry(
+            "ac3fb543001f4be5a25e4978abd05b1d",
+            "Day 1: Down the rabbit hole we go",
+            "Alice starts making games.",
+            Some("2021-11-08".parse().unwrap()),
+            None,
+        );
+        vec![
+            new_entry(
+                "cf2bacc9d75c4226aab53601c336f295",
+                "Day 0: Nannou, helping L, and lots of noise",
+                "Every journey starts with 1 O'clock: assistance.",
+                Some("2021-11-07".parse().unwrap()),
+                None,
+            ),
+            Page {
+                cover: Some(
+                    serde_json::from_str(
+                        r#"{
+                            "type": "external",
+                            "external": { "url": "https://gamediary.dev/cover.png" }
+                        }"#,
+                    )
+                    .unwrap(),
+                ),
+                ..covered
+            },
+            new_article(
+                "78abd05b1dac3fb543001f4be5a25e49",
+                "Some article about something",
+                "some really interesting descritpion",
+                "interesting_article",
+                Some(date!(2021 - 11 - 08)),
+            ),
+        ]
+    };
+    let generate = |config: &'static str| {
+        let cwd = &cwd;
+        let pages = pages();
+        async move {
+            fs::write(cwd.path().join("config.json"), config).unwrap();
+            let generator = Generator::new(cwd, pages).await.unwrap();
+            generator.generate_days().unwrap().await.unwrap().unwrap();
+            generator
+                .generate_article_pages()
+                .unwrap()
+                .await
+                .unwrap()
+                .unwrap();
+            generator
+                .generate_og_images()
+                .unwrap()
+                .await
+                .unwrap()
+                .unwrap();
+        }
+    };
+    let read = |path: &str| fs::read_to_string(cwd.path().join("output").join(path)).unwrap();
+
+    generate("{}").await;
+    assert!(!read("2021/11/07.html").contains(r#"property="og:image""#));
+    assert!(!read("interesting_article.html").contains(r#"property="og:image""#));
+    assert!(!cwd.path().join("output/og").exists());
+
+    generate(r#"{ "generate_og_images": true }"#).await;
+    assert!(read("2021/11/07.html")
+        .contains(r#"<meta property="og:image" content="/og/2021-11-07.png">"#));
+    assert!(read("interesting_article.html")
+        .contains(r#"<meta property="og:image" content="/og/interesting_article.png">"#));
+    assert!(!read("2021/11/08.html").contains("/og/"));
+    assert!(cwd.path().join("output/og/2021-11-07.png").is_file());
+    assert!(cwd
+        .path()
+        .join("output/og/interesting_article.png")
+        .is_file());
+    assert!(!cwd.path().join("output/og/2021-11-08.png").exists());
+}
+
+#[tokio::test]
+async fn shared_covers_are_downloaded_once() {
+    let cwd = TestDir::new(function!());
+
+    let with_cover = |page: Page<Properties>| Page {
+        cover: Some(
+            serde_json::from_str(
+                r#"{
+                    "type": "external",
+                    "external": { "url": "https://gamediary.dev/cover.png" }
+                }"#,
+            )
+            .unwrap(),
+        ),
+        ..page
+    };
+    let generator = Generator::new(
+        &cwd,
+        vec![
+            with_cover(new_entry(
+                "cf2bacc9d75c4226aab53601c336f295",
+                "Day 0: Nannou, helping L, and lots of noise",
+                "Every journey starts with 1 O'clock: assistance.",
+                Some("2021-11-07".parse().unwrap()),
+                None,
+            )),
+            with_cover(new_entry(
+                "ac3fb543001f4be5a25e4978abd05b1d",
+                "Day 1: Down the rabbit hole we go",
+                "Alice starts making games.",
+                Some("2021-11-08".parse().unwrap()),
+                None,
+            )),
+        ],
+    )
+    .await
+    .unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+
+    let cover_src = |path: &str| {
+        let day = fs::read_to_string(cwd.path().join(path)).unwrap();
+        let (_, rest) = day.split_once(r#"class="cover""#).unwrap();
+        let (_, rest) = rest.split_once(r#"src=""#).unwrap();
+        rest.split_once('"').unwrap().0.to_string()
+    };
+    let first = cover_src("output/2021/11/07.html");
+    assert!(first.starts_with("/media/"));
+    assert_eq!(first, cover_src("output/2021/11/08.html"));
+}
+
+#[tokio::test]
+async fn cover_captions() {
+    let cwd = TestDir::new(function!());
+
+    let mut cover: File = serde_json::from_str(
+        r#"{
+            "type": "external",
+            "external": { "url": "https://gamediary.dev/cover.png" }
+        }"#,
+    )
+    .unwrap();
+    if let File::External { caption, .. } = &mut cover {
+        caption.push(RichText {
+            ty: RichTextType::Text {
+                content: "A field of noise".to_string(),
+                link: None,
+            },
+            annotations: Default::default(),
+            plain_text: "A field of noise".to_string(),
+            href: None,
+        });
+    }
+    let entry = new_entry(
+        "cf2bacc9d75c4226aab53601c336f295",
+        "Day 0: Nannou, helping L, and lots of noise",
+        "Every journey starts with 1 O'clock: assistance.",
+        Some("2021-11-07".parse().unwrap()),
+        None,
+    );
+    let generator = Generator::new(
+        &cwd,
+        vec![Page {
+            cover: Some(cover),
+            ..entry
+        }],
+    )
+    .await
+    .unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+
+    let day = fs::read_to_string(cwd.path().join("output/2021/11/07.html")).unwrap();
+    assert!(day.contains(r#"<figure><img class="cover" alt="A field of noise" src="/media/"#));
+    assert!(day.contains("<figcaption>A field of noise</figcaption></figure>"));
+}
+
+#[tokio::test]
+async fn covers_render_the_same_everywhere() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "url": "https://gamediary.dev" }"#,
+    )
+    .unwrap();
+
+    let cover = || -> File {
+        serde_json::from_str(
+            r#"{
+                "type": "external",
+                "external": { "url": "https://gamediary.dev/cover.png" }
+            }"#,
+        )
+        .unwrap()
+    };
+    let entry = new_entry(
+        "cf2bacc9d75c4226aab53601c336f295",
+        "Noise",
+        "Every journey starts with 1 O'clock: assistance.",
+        Some("2021-11-07".parse().unwrap()),
+        None,
+    );
+    let article = new_article(
+        "78abd05b1dac3fb543001f4be5a25e49",
+        "Noise",
+        "some really interesting descritpion",
+        "interesting_article",
+        Some(date!(2021 - 11 - 08)),
+    );
+    let generator = Generator::new(
+        &cwd,
+        vec![
+            Page {
+                cover: Some(cover()),
+                ..entry
+            },
+            Page {
+                cover: Some(cover()),
+                ..article
+            },
+        ],
+    )
+    .await
+    .unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+    generator
+        .generate_article_pages()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+    generator
+        .generate_atom_feed()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+    generator
+        .generate_json_feed()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let cover_of = |html: &str| {
+        let start = html.find(r#"<img class="cover""#).unwrap();
+        let end = start + html[start..].find('>').unwrap() + 1;
+        html[start..end].to_string()
+    };
+    let day = fs::read_to_string(cwd.path().join("output/2021/11/07.html")).unwrap();
+    let article = fs::read_to_string(cwd.path().join("output/interesting_article.html")).unwrap();
+    let cover = cover_of(&day);
+    assert!(cover.starts_with(r#"<img class="cover" alt="Noise cover" src="/media/"#));
+    assert!(cover.ends_with(r#"" decoding="async">"#));
+    assert_eq!(cover, cover_of(&article));
+
+    let feed = fs::read_to_string(cwd.path().join("output/feed.xml")).unwrap();
+    let escaped = cover
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;");
+    assert_eq!(feed.matches(&escaped).count(), 2);
+    let src = cover
+        .split(r#"src=""#)
+        .nth(1)
+        .unwrap()
+        .split('"')
+        .next()
+        .unwrap();
+    assert_eq!(
+        feed.matches(&format!(
+            r#"<link rel="enclosure" href="https://gamediary.dev{}" />"#,
+            src
+        ))
+        .count(),
+        2
+    );
+
+    let json_feed: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(cwd.path().join("output/feed.json")).unwrap())
+            .unwrap();
+    let image = format!("https://gamediary.dev{}", src);
+    assert_eq!(json_feed["items"][0]["image"], image);
+    assert_eq!(json_feed["items"][1]["image"], image);
+    let content = json_feed["items"][0]["content_html"].as_str().unwrap();
+    assert_eq!(content.matches(&cover).count(), 1);
+}
+
+#[tokio::test]
+async fn time_zones_decide_the_day() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "url": "https://gamediary.dev" }"#,
+    )
+    .unwrap();
+
+    let entry = new_entry(
+        "cf2bacc9d75c4226aab53601c336f295",
+        "Day 0: Nannou, helping L, and lots of noise",
+        "Every journey starts with 1 O'clock: assistance.",
+        Some("2021-11-08T03:30:00.000Z".parse().unwrap()),
+        Some(date!(2021 - 11 - 08)),
+    );
+    let in_zone = |date: DateProperty, time_zone: &str| DateProperty {
+        date: date.date.map(|date| NotionDate {
+            time_zone: Some(time_zone.to_string()),
+            ..date
+        }),
+        ..date
+    };
+    let generator = Generator::new(
+        &cwd,
+        vec![Page {
+            properties: Properties {
+                date: in_zone(entry.properties.date, "America/New_York"),
+                published: in_zone(entry.properties.published, "Asia/Tokyo"),
+                ..entry.properties
+            },
+            ..entry
+        }],
+    )
+    .await
+    .unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+    generator
+        .generate_atom_feed()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let day = fs::read_to_string(cwd.path().join("output/2021/11/07.html")).unwrap();
+    assert!(day.contains(r#"datetime="2021-11-07T22:30:00-05:00""#));
+
+    let feed = fs::read_to_string(cwd.path().join("output/feed.xml")).unwrap();
+    assert!(feed.contains("<published>2021-11-08T00:00:00+09:00</published>"));
+}
+
+#[tokio::test]
+async fn date_ranges() {
+    let cwd = TestDir::new(function!());
+
+    let until = |date: DateProperty, end: &str| DateProperty {
+        date: date.date.map(|date| NotionDate {
+            end: Some(end.parse().unwrap()),
+            ..date
+        }),
+        ..date
+    };
+    let entry = new_entry(
+        "cf2bacc9d75c4226aab53601c336f295",
+        "Day 0: Nannou, helping L, and lots of noise",
+        "Every journey starts with 1 O'clock: assistance.",
+        Some("2021-11-07".parse().unwrap()),
+        None,
+    );
+    let article = new_article(
+        "78abd05b1dac3fb543001f4be5a25e49",
+        "Some article about something",
+        "some really interesting descritpion",
+        "interesting_article",
+        Some(date!(2021 - 11 - 30)),
+    );
+    let generator = Generator::new(
+        &cwd,
+        vec![
+            Page {
+                properties: Properties {
+                    date: until(entry.properties.date, "2021-11-09"),
+                    ..entry.properties
+                },
+                ..entry
+            },
+            Page {
+                properties: Properties {
+                    published: until(article.properties.published, "2021-12-02"),
+                    ..article.properties
+                },
+                ..article
+            },
+        ],
+    )
+    .await
+    .unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+    generator
+        .generate_article_pages()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let day = fs::read_to_string(cwd.path().join("output/2021/11/07.html")).unwrap();
+    assert!(day.contains(concat!(
+        r#"<p><time datetime="2021-11-07" itemprop="datePublished">November 07</time>"#,
+        r#"–<time datetime="2021-11-09">09, 2021</time></p>"#
+    )));
+
+    let article = fs::read_to_string(cwd.path().join("output/interesting_article.html")).unwrap();
+    assert!(article.contains(concat!(
+        r#"<p><time datetime="2021-11-30" itemprop="datePublished">November 30</time>"#,
+        r#" – <time datetime="2021-12-02">December 02, 2021</time></p>"#
+    )));
+}
+
+#[tokio::test]
+async fn reading_time() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "reading_time": { "wpm": 100 } }"#,
+    )
+    .unwrap();
+
+    let paragraph = |id: &str, words: usize| {
+        let text = vec!["noise"; words].join(" ");
+        Block {
+            object: "block".to_string(),
+            id: id.parse().unwrap(),
+            created_time: "2021-11-15T18:03:00.000Z".to_string(),
+            last_edited_time: "2021-11-16T11:23:00.000Z".to_string(),
+            has_children: false,
+            archived: false,
+            ty: BlockType::Paragraph {
+                text: vec![RichText {
+                    plain_text: text.clone(),
+                    href: None,
+                    annotations: Default::default(),
+                    ty: RichTextType::Text {
+                        content: text,
+                        link: None,
+                    },
+                }],
+                children: vec![],
+            },
+        }
+    };
+    let entry = new_entry(
+        "cf2bacc9d75c4226aab53601c336f295",
+        "Day 0: Nannou, helping L, and lots of noise",
+        "Every journey starts with 1 O'clock: assistance.",
+        Some("2021-11-07".parse().unwrap()),
+        None,
+    );
+    let generator = Generator::new(
+        &cwd,
+        vec![
+            Page {
+                children: vec![
+                    paragraph("4fb9dd79-2fc7-45b1-b3a2-8efae49992ed", 150),
+                    paragraph("817c0ca1-721a-4565-ac54-eedbbe471f0b", 101),
+                ],
+                ..entry
+            },
+            new_entry(
+                "ac3fb543001f4be5a25e4978abd05b1d",
+                "Day 1: Down the rabbit hole we go",
+                "Alice starts making games.",
+                Some("2021-11-08".parse().unwrap()),
+                None,
+            ),
+        ],
+    )
+    .await
+    .unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+
+    let day_0 = fs::read_to_string(cwd.path().join("output/2021/11/07.html")).unwrap();
+    assert!(day_0.contains(r#"<p class="reading-time">3 min read</p>"#));
+
+    let day_1 = fs::read_to_string(cwd.path().join("output/2021/11/08.html")).unwrap();
+    assert!(day_1.contains(r#"<p class="reading-time">1 min read</p>"#));
+}
+
+#[tokio::test]
+async fn reading_time_template() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "reading_time": { "template": "{minutes} Min. Lesezeit" } }"#,
+    )
+    .unwrap();
+
+    let generator = Generator::new(
+        &cwd,
+        vec![new_entry(
+            "cf2bacc9d75c4226aab53601c336f295",
+            "Day 0: Nannou, helping L, and lots of noise",
+            "Every journey starts with 1 O'clock: assistance.",
+            Some("2021-11-07".parse().unwrap()),
+            None,
+        )],
+    )
+    .await
+    .unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+
+    let day = fs::read_to_string(cwd.path().join("output/2021/11/07.html")).unwrap();
+    assert!(day.contains(r#"<p class="reading-time">1 Min. Lesezeit</p>"#));
+}
+
+#[tokio::test]
+async fn reading_time_can_be_hidden() {
+    let cwd = TestDir::new(function!());
+    let entries = || {
+        vec![new_entry(
+            "cf2bacc9d75c4226aab53601c336f295",
+            "Day 0: Nannou, helping L, and lots of noise",
+            "Every journey starts with 1 O'clock: assistance.",
+            Some("2021-11-07".parse().unwrap()),
+            None,
+        )]
+    };
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "reading_time": { "wpm": 0 } }"#,
+    )
+    .unwrap();
+    assert!(Generator::new(&cwd, entries()).await.is_err());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "reading_time": null }"#,
+    )
+    .unwrap();
+    let generator = Generator::new(&cwd, entries()).await.unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+
+    let day = fs::read_to_string(cwd.path().join("output/2021/11/07.html")).unwrap();
+    assert!(!day.contains("reading-time"));
+}
+
+#[tokio::test]
+async fn table_of_contents() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "table_of_contents": true }"#,
+    )
+    .unwrap();
+
+    let heading = |id: &str, ty: fn(Vec<RichText>) -> BlockType, text: &str| Block {
+        object: "block".to_string(),
+        id: id.parse().unwrap(),
+        created_time: "2021-11-15T18:03:00.000Z".to_string(),
+        last_edited_time: "2021-11-16T11:23:00.000Z".to_string(),
+        has_children: false,
+        archived: false,
+        ty: ty(vec![RichText {
+            plain_text: text.to_string(),
+            href: None,
+            annotations: Default::default(),
+            ty: RichTextType::Text {
+                content: text.to_string(),
+                link: None,
+            },
+        }]),
+    };
+    let h1 = |text| BlockType::HeadingOne { text };
+    let h2 = |text| BlockType::HeadingTwo { text };
+    let day_0 = new_entry(
+        "cf2bacc9d75c4226aab53601c336f295",
+        "Day 0: Nannou, helping L, and lots of noise",
+        "Every journey starts with 1 O'clock: assistance.",
+        Some("2021-11-07".parse().unwrap()),
+        None,
+    );
+    let day_1 = new_entry(
+        "ac3fb543001f4be5a25e4978abd05b1d",
+        "Day 1: Down the rabbit hole we go",
+        "Alice starts making games.",
+        Some("2021-11-08".parse().unwrap()),
+        None,
+    );
+    let generator = Generator::new(
+        &cwd,
+        vec![
+            Page {
+                children: vec![
+                    heading("4fb9dd79-2fc7-45b1-b3a2-8efae49992ed", h1, "Nannou"),
+                    heading("817c0ca1-721a-4565-ac54-eedbbe471f0b", h2, "Noise"),
+                ],
+                ..day_0
+            },
+            Page {
+                children: vec![heading(
+                    "5f5e3d4b-9b7a-4a3c-8d8e-2f1a0b9c8d7e",
+                    h1,
+                    "Trains",
+                )],
+                ..day_1
+            },
+        ],
+    )
+    .await
+    .unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+
+    let day_0 = fs::read_to_string(cwd.path().join("output/2021/11/07.html")).unwrap();
+    assert!(day_0.contains(concat!(
+        r#"<nav class="toc"><ul>"#,
+        r##"<li class="toc-h1"><a href="#4fb9dd792fc745b1b3a28efae49992ed">Nannou</a></li>"##,
+        r##"<li class="toc-h2"><a href="#817c0ca1721a4565ac54eedbbe471f0b">Noise</a></li>"##,
+        "</ul></nav>"
+    )));
+
+    let day_1 = fs::read_to_string(cwd.path().join("output/2021/11/08.html")).unwrap();
+    assert!(!day_1.contains(r#"<nav class="toc">"#));
+}
+
+#[tokio::test]
+async fn entry_and_article_counts() {
+    let cwd = TestDir::new(function!());
+
+    let generator = Generator::new(
+        &cwd,
+        vec![
+            new_entry(
+                "cf2bacc9d75c4226aab53601c336f295",
+                "Day 0: Nannou, helping L, and lots of noise",
+                "Every journey starts with 1 O'clock: assistance.",
+                Some("2021-11-07".parse().unwrap()),
+                None,
+            ),
+            new_article(
+                "78abd05b1dac3fb543001f4be5a25e49",
+                "Some article about something",
+                "some really interesting descritpion",
+                "interesting_article",
+                Some(date!(2021 - 11 - 08)),
+            ),
+            new_entry(
+                "ac3fb543001f4be5a25e4978abd05b1d",
+                "Day 1: Down the rabbit hole we go",
+                "Alice starts making games.",
+                Some("2021-11-09".parse().unwrap()),
+                None,
+            ),
+        ],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(generator.entry_count(), 2);
+    assert_eq!(generator.article_count(), 1);
+    assert_eq!(
+        generator.get_first_and_last_dates(),
+        Some((date!(2021 - 11 - 07), date!(2021 - 11 - 09)))
+    );
+}
+
+#[tokio::test]
+async fn unlisted_entries() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "url": "https://example.com" }"#,
+    )
+    .unwrap();
+
+    let unlisted = new_entry(
+        "ac3fb543001f4be5a25e4978abd05b1d",
+        "Day 1: Down the rabbit hole we go",
+        "Alice starts making games.",
+        Some("2021-11-08".parse().unwrap()),
+        Some(date!(2021 - 11 - 08)),
+    );
+    let generator = Generator::new(
+        &cwd,
+        vec![
+            new_entry(
+                "cf2bacc9d75c4226aab53601c336f295",
+                "Day 0: Nannou, helping L, and lots of noise",
+                "Every journey starts with 1 O'clock: assistance.",
+                Some("2021-11-07".parse().unwrap()),
+                Some(date!(2021 - 11 - 07)),
+            ),
+            Page {
+                properties: Properties {
+                    unlisted: Some(CheckboxProperty {
+                        id: "%3AuNl".to_string(),
+                        checkbox: true,
+                    }),
+                    ..unlisted.properties
+                },
+                ..unlisted
+            },
+        ],
+    )
+    .await
+    .unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+    generator
+        .generate_index_page()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+    generator
+        .generate_atom_feed()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+    generator
+        .generate_sitemap()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let day = fs::read_to_string(cwd.path().join("output/2021/11/08.html")).unwrap();
+    assert!(day.contains("Day 1: Down the rabbit hole we go"));
+    assert!(day.contains(r#"<meta name="robots" content="noindex">"#));
+    let listed = fs::read_to_string(cwd.path().join("output/2021/11/07.html")).unwrap();
+    assert!(!listed.contains("noindex"));
+    assert!(!listed.contains("2021/11/08"));
+
+    let index = fs::read_to_string(cwd.path().join("output/index.html")).unwrap();
+    assert!(index.contains("Day 0"));
+    assert!(!index.contains("Day 1"));
+
+    let feed = fs::read_to_string(cwd.path().join("output/feed.xml")).unwrap();
+    assert!(feed.contains("Day 0"));
+    assert!(!feed.contains("Day 1"));
+
+    let sitemap = fs::read_to_string(cwd.path().join("output/sitemap.xml")).unwrap();
+    assert!(sitemap.contains("https://example.com/2021/11/07"));
+    assert!(!sitemap.contains("https://example.com/2021/11/08"));
+}
+
+#[tokio::test]
+async fn cross_posts_link_to_the_original() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "url": "https://example.com" }"#,
+    )
+    .unwrap();
+
+    let canonical = |page: Page<Properties>, url: &str| Page {
+        properties: Properties {
+            canonical: Some(UrlProperty {
+                id: "c%7DnL".to_string(),
+                url: Some(url.to_string()),
+            }),
+            ..page.properties
+        },
+        ..page
+    };
+    let generator = Generator::new(
+        &cwd,
+        vec![
+            canonical(
+                new_entry(
+                    "cf2bacc9d75c4226aab53601c336f295",
+                    "Day 0: Nannou, helping L, and lots of noise",
+                    "Every journey starts with 1 O'clock: assistance.",
+                    Some("2021-11-07".parse().unwrap()),
+                    None,
+                ),
+                "https://dev.to/alice/nannou",
+            ),
+            canonical(
+                new_entry(
+                    "ac3fb543001f4be5a25e4978abd05b1d",
+                    "Day 1: Down the rabbit hole we go",
+                    "Alice starts making games.",
+                    Some("2021-11-08".parse().unwrap()),
+                    None,
+                ),
+                "https://example.com/2021/11/08",
+            ),
+        ],
+    )
+    .await
+    .unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+
+    let cross_post = fs::read_to_string(cwd.path().join("output/2021/11/07.html")).unwrap();
+    assert!(cross_post.contains(r#"<link rel="canonical" href="https://dev.to/alice/nannou">"#));
+    assert!(cross_post.contains(concat!(
+        r#"<p class="original">Originally published at "#,
+        r#"<a href="https://dev.to/alice/nannou">dev.to</a></p>"#
+    )));
+
+    let original = fs::read_to_string(cwd.path().join("output/2021/11/08.html")).unwrap();
+    assert!(original.contains(r#"<link rel="canonical" href="https://example.com/2021/11/08">"#));
+    assert!(!original.contains(r#"class="original""#));
+}
+
+#[tokio::test]
+async fn entries_dated_with_a_time() {
+    let cwd = TestDir::new(function!());
+
+    let generator = Generator::new(
+        &cwd,
+        vec![
+            new_entry(
+                "cf2bacc9d75c4226aab53601c336f295",
+                "Day 0: Nannou, helping L, and lots of noise",
+                "Every journey starts with 1 O'clock: assistance.",
+                Some("2021-11-07T14:30:00.000+00:00".parse().unwrap()),
+                None,
+            ),
+            new_entry(
+                "ac3fb543001f4be5a25e4978abd05b1d",
+                "Day 1: Down the rabbit hole we go",
+                "Alice starts making games.",
+                Some("2021-11-08".parse().unwrap()),
+                None,
+            ),
+        ],
+    )
+    .await
+    .unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+
+    // The entry is still routed by its day
+    let timed = fs::read_to_string(cwd.path().join("output/2021/11/07.html")).unwrap();
+    assert!(timed.contains(concat!(
+        r#"<time datetime="2021-11-07T14:30:00Z" itemprop="datePublished">"#,
+        "November 07, 2021 at 14:30</time>"
+    )));
+
+    let untimed = fs::read_to_string(cwd.path().join("output/2021/11/08.html")).unwrap();
+    assert!(untimed.contains(
+        r#"<time datetime="2021-11-08" itemprop="datePublished">November 08, 2021</time>"#
+    ));
+}
+
+async fn generate_with_display_date(cwd: &TestDir, config: &str) -> (String, String) {
+    fs::write(cwd.path().join("config.json"), config).unwrap();
+
+    let generator = Generator::new(
+        cwd,
+        vec![new_entry(
+            "cf2bacc9d75c4226aab53601c336f295",
+            "Day 0: Nannou, helping L, and lots of noise",
+            "Every journey starts with 1 O'clock: assistance.",
+            Some("2021-11-07".parse().unwrap()),
+            Some(date!(2021 - 11 - 09)),
+        )],
+    )
+    .await
+    .unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+    generator
+        .generate_index_page()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    (
+        fs::read_to_string(cwd.path().join("output/2021/11/07.html")).unwrap(),
+        fs::read_to_string(cwd.path().join("output/index.html")).unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn display_date_defaults_to_the_date() {
+    let cwd = TestDir::new(function!());
+
+    let (day, index) = generate_with_display_date(&cwd, "{}").await;
+    let time = r#"<time datetime="2021-11-07" itemprop="datePublished">November 07, 2021</time>"#;
+    assert!(day.contains(time));
+    assert!(index.contains(time));
+}
+
+#[tokio::test]
+async fn display_date_published() {
+    let cwd = TestDir::new(function!());
+
+    let (day, index) = generate_with_display_date(&cwd, r#"{ "display_date": "published" }"#).await;
+    let time = r#"<time datetime="2021-11-09" itemprop="datePublished">November 09, 2021</time>"#;
+    assert!(day.contains(time));
+    assert!(index.contains(time));
+}
+
+async fn generate_with_config(cwd: &TestDir, config: &str) -> (String, String, String) {
+    fs::write(cwd.path().join("config.json"), config).unwrap();
+
+    let generator = Generator::new(
+        cwd,
+        vec![
+            new_entry(
+                "cf2bacc9d75c4226aab53601c336f295",
+                "Day 0: Nannou, helping L, and lots of noise",
+                "Every journey starts with 1 O'clock: assistance.",
+                Some("2021-11-07".parse().unwrap()),
+                None,
+            ),
+            new_article(
+                "78abd05b1dac3fb543001f4be5a25e49",
+                "Some article about something",
+                "some really interesting descritpion",
+                "interesting_article",
+                Some(date!(2021 - 11 - 08)),
+            ),
+        ],
+    )
+    .await
+    .unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+    generator
+        .generate_article_pages()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+    generator
+        .generate_index_page()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    (
+        fs::read_to_string(cwd.path().join("output/2021/11/07.html")).unwrap(),
+        fs::read_to_string(cwd.path().join("output/interesting_article.html")).unwrap(),
+        fs::read_to_string(cwd.path().join("output/index.html")).unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn comments_embed() {
+    let cwd = TestDir::new(function!());
+
+    let embed = r#"<script src="https://giscus.app/client.js" async></script>"#;
+    let (day, article, index) = generate_with_config(
+        &cwd,
+        r#"{ "comments": "<script src=\"https://giscus.app/client.js\" async></script>" }"#,
+    )
+    .await;
+    assert!(day.contains(&format!("{}</main>", embed)));
+    assert!(article.contains(&format!("{}</main>", embed)));
+    assert!(!index.contains(embed));
+}
+
+#[tokio::test]
+async fn no_comments_embed_by_default() {
+    let cwd = TestDir::new(function!());
+
+    let (day, article, _) = generate_with_config(&cwd, "{}").await;
+    assert!(!day.contains("giscus"));
+    assert!(!article.contains("giscus"));
+}
+
+#[tokio::test]
+async fn entry_language() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "url": "https://diary.example.com" }"#,
+    )
+    .unwrap();
+
+    let entry = new_entry(
+        "cf2bacc9d75c4226aab53601c336f295",
+        "Jour 0 : Nannou, aider L et beaucoup de bruit",
+        "Chaque voyage commence à 1 heure : assistance.",
+        Some("2021-11-07".parse().unwrap()),
+        None,
+    );
+    let generator = Generator::new(
+        &cwd,
+        vec![
+            Page {
+                properties: Properties {
+                    lang: Some(RichTextProperty {
+                        id: "lang".to_string(),
+                        rich_text: vec![RichText {
+                            plain_text: "fr_FR".to_string(),
+                            href: None,
+                            annotations: Default::default(),
+                            ty: RichTextType::Text {
+                                content: "fr_FR".to_string(),
+                                link: None,
+                            },
+                        }],
+                    }),
+                    ..entry.properties
+                },
+                ..entry
+            },
+            new_entry(
+                "ac3fb543001f4be5a25e4978abd05b1d",
+                "Day 1: Down the rabbit hole we go",
+                "Alice starts making games.",
+                Some("2021-11-08".parse().unwrap()),
+                None,
+            ),
+        ],
+    )
+    .await
+    .unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+    generator
+        .generate_atom_feed()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let day_0 = fs::read_to_string(cwd.path().join("output/2021/11/07.html")).unwrap();
+    assert!(day_0.contains(r#"<html lang="fr">"#));
+    assert!(day_0.contains(r#"<meta property="og:locale" content="fr_FR">"#));
+
+    let day_1 = fs::read_to_string(cwd.path().join("output/2021/11/08.html")).unwrap();
+    assert!(day_1.contains(r#"<html lang="en">"#));
+    assert!(day_1.contains(r#"<meta property="og:locale" content="en_US">"#));
+
+    let feed = fs::read_to_string(cwd.path().join("output/feed.xml")).unwrap();
+    assert!(feed.contains(r#"<feed xmlns="http://www.w3.org/2005/Atom" xml:lang="en">"#));
+    assert_eq!(feed.matches(r#"<entry xml:lang="fr">"#).count(), 1);
+    assert_eq!(feed.matches("<entry>").count(), 1);
+}
@@ -0,0 +1,232 @@
+mod utils;
+
+use diary_generator::{Generator, Options, Properties};
+use image::{codecs::png::PngEncoder, ColorType, ImageEncoder};
+use notion_generator::response::{Block, BlockType, Page};
+use reqwest::Client;
+use std::fs;
+use utils::{function, new_entry, Server, TestDir};
+
+/// Serves a 64 bytes cover that, like files uploaded to Notion, is only reachable through its
+/// signed URL
+fn cover_server() -> Server {
+    Server::new(|_, path| {
+        if path.starts_with("/cover.png?X-Amz-Signature=") {
+            (200, vec![0; 64])
+        } else {
+            (403, Vec::new())
+        }
+    })
+}
+
+fn entries(server: &Server) -> Vec<Page<Properties>> {
+    vec![Page {
+        cover: Some(
+            serde_json::from_value(serde_json::json!({
+                "type": "file",
+                "file": {
+                    "url": format!("{}cover.png?X-Amz-Signature=abc", server.url()),
+                    "expiry_time": "2021-11-08T00:00:00.000Z"
+                }
+            }))
+            .unwrap(),
+        ),
+        ..new_entry(
+            "cf2bacc9d75c4226aab53601c336f295",
+            "Day 0: Nannou, helping L, and lots of noise",
+            "Every journey starts with 1 O'clock: assistance.",
+            Some("2021-11-07".parse().unwrap()),
+            None,
+        )
+    }]
+}
+
+#[tokio::test]
+async fn oversized_media_fails_strict_builds() {
+    let cwd = TestDir::new(function!());
+    let server = cover_server();
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "max_download_bytes": 32 }"#,
+    )
+    .unwrap();
+    let generator = Generator::with_options(
+        &cwd,
+        entries(&server),
+        Options {
+            strict: true,
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+
+    let error = generator
+        .download_all(Client::builder().no_proxy().build().unwrap())
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("over max_download_bytes of 32"));
+    assert_eq!(server.requests(), ["HEAD /cover.png?X-Amz-Signature=abc"]);
+}
+
+#[tokio::test]
+async fn oversized_media_is_skipped() {
+    let cwd = TestDir::new(function!());
+    let server = cover_server();
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "max_download_bytes": 32 }"#,
+    )
+    .unwrap();
+    let generator = Generator::new(&cwd, entries(&server)).await.unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+
+    generator
+        .download_all(Client::builder().no_proxy().build().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(server.requests(), ["HEAD /cover.png?X-Amz-Signature=abc"]);
+    assert!(!cwd.path().join("output/media").exists());
+}
+
+#[tokio::test]
+async fn audio_and_video_uploaded_to_notion_are_downloaded() {
+    let cwd = TestDir::new(function!());
+    let server = Server::new(|_, _| (200, vec![0; 64]));
+
+    let block = |id: &str, ty: BlockType| Block {
+        object: "block".to_string(),
+        id: id.parse().unwrap(),
+        created_time: "2021-11-15T18:03:00.000Z".to_string(),
+        last_edited_time: "2021-11-16T11:23:00.000Z".to_string(),
+        has_children: false,
+        archived: false,
+        ty,
+    };
+    let video = serde_json::from_value(serde_json::json!({
+        "type": "file",
+        "file": {
+            "url": format!("{}video.mp4?X-Amz-Signature=abc", server.url()),
+            "expiry_time": "2021-11-08T00:00:00.000Z"
+        }
+    }))
+    .unwrap();
+    let audio = serde_json::from_value(serde_json::json!({
+        "type": "external",
+        "external": { "url": format!("{}audio.mp3", server.url()) }
+    }))
+    .unwrap();
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "max_download_bytes": 1024 }"#,
+    )
+    .unwrap();
+    let generator = Generator::new(
+        &cwd,
+        vec![Page {
+            children: vec![
+                block(
+                    "4fb9dd79-2fc7-45b1-b3a2-8efae49992ed",
+                    BlockType::Video { video },
+                ),
+                block(
+                    "817c0ca1-721a-4565-ac54-eedbbe471f0b",
+                    BlockType::Audio { audio },
+                ),
+            ],
+            ..new_entry(
+                "cf2bacc9d75c4226aab53601c336f295",
+                "Day 0: Nannou, helping L, and lots of noise",
+                "Every journey starts with 1 O'clock: assistance.",
+                Some("2021-11-07".parse().unwrap()),
+                None,
+            )
+        }],
+    )
+    .await
+    .unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+
+    let day = fs::read_to_string(cwd.path().join("output/2021/11/07.html")).unwrap();
+    assert!(!day.contains("video.mp4"));
+
+    generator
+        .download_all(Client::builder().no_proxy().build().unwrap())
+        .await
+        .unwrap();
+    // Only the video uploaded to Notion is downloaded, the external audio is left as it is
+    assert_eq!(server.requests(), ["HEAD /video.mp4?X-Amz-Signature=abc"]);
+}
+
+#[tokio::test]
+async fn offline_builds_download_nothing() {
+    let cwd = TestDir::new(function!());
+    let server = cover_server();
+    let client = Client::builder().no_proxy().build().unwrap();
+
+    let generator = Generator::with_options(
+        &cwd,
+        entries(&server),
+        Options {
+            offline: true,
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+
+    generator
+        .download_katex_from(client.clone(), format!("{}katex/", server.url()))
+        .await
+        .unwrap()
+        .unwrap();
+    generator.download_all(client).await.unwrap();
+
+    assert!(server.requests().is_empty());
+    assert!(cwd.path().join("output/2021/11/07.html").is_file());
+    assert!(!cwd.path().join("output/katex").exists());
+    assert!(!cwd.path().join("output/media").exists());
+}
+
+#[tokio::test]
+async fn covers_are_downloaded_first_and_rendered_with_their_size() {
+    let cwd = TestDir::new(function!());
+
+    let mut png = Vec::new();
+    PngEncoder::new(&mut png)
+        .write_image(&[0; 3 * 2 * 3], 3, 2, ColorType::Rgb8)
+        .unwrap();
+    let server = Server::new(move |_, path| {
+        if path.starts_with("/cover.png?X-Amz-Signature=") {
+            (200, png.clone())
+        } else {
+            (403, Vec::new())
+        }
+    });
+    let client = Client::builder().no_proxy().build().unwrap();
+
+    let mut generator = Generator::new(&cwd, entries(&server)).await.unwrap();
+    generator.download_covers(client.clone()).await.unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+    generator.download_all(client).await.unwrap();
+
+    let day = fs::read_to_string(cwd.path().join("output/2021/11/07.html")).unwrap();
+    let start = day.find(r#"<img class="cover""#).unwrap();
+    let cover = &day[start..start + day[start..].find('>').unwrap() + 1];
+    assert!(cover.ends_with(r#" decoding="async" width="3" height="2">"#));
+    let src = cover
+        .split(r#"src=""#)
+        .nth(1)
+        .unwrap()
+        .split('"')
+        .next()
+        .unwrap();
+    assert!(cwd.path().join("output").join(&src[1..]).is_file());
+    // Covers aren't downloaded again with the rest of the media
+    assert_eq!(server.requests(), ["GET /cover.png?X-Amz-Signature=abc"]);
+}
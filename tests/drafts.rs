@@ -0,0 +1,203 @@
+mod utils;
+
+use diary_generator::{CheckboxProperty, Generator, Options, Properties};
+use notion_generator::response::{properties::DateProperty, Page};
+use pretty_assertions::assert_eq;
+use std::fs;
+use time::macros::date;
+use utils::{function, new_entry, DirEntry, TestDir};
+
+fn unpublished_entry() -> Page<Properties> {
+    let page = new_entry(
+        "ac3fb543-001f-4be5-a25e-4978abd05b1d",
+        "Day 1: Down the rabbit hole we go",
+        "Alice starts making games by watching trains with the loveliest coding conductor.",
+        Some("2021-11-08".parse().unwrap()),
+        None,
+    );
+
+    Page {
+        properties: Properties {
+            published: DateProperty {
+                date: None,
+                ..page.properties.published
+            },
+            ..page.properties
+        },
+        ..page
+    }
+}
+
+#[tokio::test]
+async fn today_decides_what_is_published() {
+    let cwd = TestDir::new(function!());
+
+    let scheduled = || {
+        new_entry(
+            "ac3fb543-001f-4be5-a25e-4978abd05b1d",
+            "Day 1: Down the rabbit hole we go",
+            "Alice starts making games by watching trains with the loveliest coding conductor.",
+            Some("2021-11-08".parse().unwrap()),
+            Some(date!(2021 - 11 - 10)),
+        )
+    };
+
+    let generator = Generator::with_options(
+        &cwd,
+        vec![scheduled()],
+        Options {
+            today: Some(date!(2021 - 11 - 09)),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(generator.get_first_and_last_dates(), None);
+
+    let generator = Generator::with_options(
+        &cwd,
+        vec![scheduled()],
+        Options {
+            today: Some(date!(2021 - 11 - 10)),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        generator.get_first_and_last_dates(),
+        Some((date!(2021 - 11 - 08), date!(2021 - 11 - 08)))
+    );
+}
+
+#[tokio::test]
+async fn drafts_are_skipped_by_default() {
+    let cwd = TestDir::new(function!());
+
+    let generator = Generator::new(&cwd, vec![unpublished_entry()])
+        .await
+        .unwrap();
+    generator
+        .generate_article_pages()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(generator.get_first_and_last_dates(), None);
+    assert_eq!(
+        DirEntry::breakdown(&cwd),
+        DirEntry::dir(cwd.path().file_name().unwrap(), []),
+    );
+}
+
+#[tokio::test]
+async fn pages_marked_as_drafts_are_skipped() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "url": "https://gamediary.dev" }"#,
+    )
+    .unwrap();
+
+    let page = new_entry(
+        "ac3fb543-001f-4be5-a25e-4978abd05b1d",
+        "Day 1: Down the rabbit hole we go",
+        "Alice starts making games by watching trains with the loveliest coding conductor.",
+        Some("2021-11-08".parse().unwrap()),
+        Some(date!(2021 - 11 - 08)),
+    );
+    let generator = Generator::new(
+        &cwd,
+        vec![Page {
+            properties: Properties {
+                draft: Some(CheckboxProperty {
+                    id: "%3EdRf".to_string(),
+                    checkbox: true,
+                }),
+                ..page.properties
+            },
+            ..page
+        }],
+    )
+    .await
+    .unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+    generator
+        .generate_atom_feed()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+    generator
+        .generate_index_page()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(generator.get_first_and_last_dates(), None);
+    assert_eq!(
+        DirEntry::breakdown(cwd.path().join("output")),
+        DirEntry::dir("output", [DirEntry::file("index.html")]),
+    );
+
+    let index = fs::read_to_string(cwd.path().join("output/index.html")).unwrap();
+    assert!(!index.contains("Down the rabbit hole"));
+}
+
+#[tokio::test]
+async fn drafts_are_rendered_in_drafts_mode() {
+    let cwd = TestDir::new(function!());
+
+    let generator = Generator::with_options(
+        &cwd,
+        vec![unpublished_entry()],
+        Options {
+            drafts: true,
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    generator
+        .generate_article_pages()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+    generator
+        .generate_index_page()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(generator.get_first_and_last_dates(), None);
+    assert_eq!(
+        DirEntry::breakdown(&cwd),
+        DirEntry::dir(
+            cwd.path().file_name().unwrap(),
+            [DirEntry::dir(
+                "output",
+                [
+                    DirEntry::file("index.html"),
+                    DirEntry::dir(
+                        "_drafts",
+                        [DirEntry::dir(
+                            "2021",
+                            [DirEntry::dir("11", [DirEntry::file("08.html")])]
+                        )]
+                    )
+                ]
+            )]
+        ),
+    );
+
+    let draft = fs::read_to_string(cwd.path().join("output/_drafts/2021/11/08.html")).unwrap();
+    assert!(draft.contains(r#"<meta name="robots" content="noindex">"#));
+
+    let index = fs::read_to_string(cwd.path().join("output/index.html")).unwrap();
+    assert!(!index.contains("Down the rabbit hole"));
+}
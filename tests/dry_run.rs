@@ -0,0 +1,51 @@
+mod utils;
+
+use diary_generator::{Generator, Options};
+use pretty_assertions::assert_eq;
+use utils::{function, new_entry, DirEntry, TestDir};
+
+#[tokio::test]
+async fn dry_run_writes_nothing() {
+    let cwd = TestDir::new(function!());
+
+    let generator = Generator::with_options(
+        &cwd,
+        vec![
+            new_entry(
+                "cf2bacc9d75c4226aab53601c336f295",
+                "Day 0: Nannou, helping L, and lots of noise",
+                "Every journey starts with 1 O'clock: assistance.",
+                Some("2021-11-07".parse().unwrap()),
+                None,
+            ),
+            new_entry(
+                "ac3fb543001f4be5a25e4978abd05b1d",
+                "Day 1: Down the rabbit hole we go",
+                "Alice starts making games.",
+                Some("2021-11-08".parse().unwrap()),
+                None,
+            ),
+        ],
+        Options {
+            dry_run: true,
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+    generator
+        .generate_index_page()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let dry_run = generator.dry_run().unwrap();
+    assert_eq!(dry_run.files(), 3);
+    assert!(dry_run.bytes() > 0);
+    assert_eq!(
+        DirEntry::breakdown(&cwd),
+        DirEntry::dir(cwd.path().file_name().unwrap(), []),
+    );
+}
@@ -0,0 +1,80 @@
+mod utils;
+
+use diary_generator::{Generator, Properties};
+use notion_generator::response::Page;
+use std::fs;
+use utils::{function, new_entry, DirEntry, TestDir};
+
+fn entries() -> Vec<Page<Properties>> {
+    vec![new_entry(
+        "cf2bacc9d75c4226aab53601c336f295",
+        "Day 0: Nannou, helping L, and lots of noise",
+        "Every journey starts with 1 O'clock: assistance.",
+        Some("2021-11-07".parse().unwrap()),
+        None,
+    )]
+}
+
+#[tokio::test]
+async fn fonts_are_copied_and_preloaded() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"
+            {
+              "fonts": [
+                { "href": "Inter.woff2", "format": "woff2", "preload": true },
+                { "href": "Inter-Italic.woff2", "format": "woff2" }
+              ]
+            }
+        "#,
+    )
+    .unwrap();
+    fs::create_dir(cwd.path().join("fonts")).unwrap();
+    fs::write(cwd.path().join("fonts/Inter.woff2"), "wOF2").unwrap();
+    fs::write(cwd.path().join("fonts/Inter-Italic.woff2"), "wOF2").unwrap();
+
+    let generator = Generator::new(&cwd, entries()).await.unwrap();
+    generator.generate_fonts().await.unwrap().unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+
+    assert_eq!(
+        DirEntry::breakdown(cwd.path().join("output").join("fonts")),
+        DirEntry::dir(
+            "fonts",
+            [
+                DirEntry::file("Inter-Italic.woff2"),
+                DirEntry::file("Inter.woff2"),
+            ]
+        ),
+    );
+
+    let day = fs::read_to_string(cwd.path().join("output/2021/11/07.html")).unwrap();
+    assert!(day.contains(
+        r#"<link rel="preload" as="font" type="font/woff2" href="/fonts/Inter.woff2" crossorigin>"#
+    ));
+    assert!(!day.contains("Inter-Italic.woff2"));
+}
+
+#[tokio::test]
+async fn missing_fonts_fail() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "fonts": [{ "href": "Inter.woff2", "format": "woff2" }] }"#,
+    )
+    .unwrap();
+
+    let generator = Generator::new(&cwd, entries()).await.unwrap();
+    let error = generator.generate_fonts().await.unwrap().err().unwrap();
+
+    assert_eq!(
+        error.to_string(),
+        format!(
+            "Failed to copy font {} listed in config.json",
+            cwd.path().join("fonts/Inter.woff2").display()
+        )
+    );
+}
@@ -0,0 +1,60 @@
+mod utils;
+
+use diary_generator::Generator;
+use pretty_assertions::assert_eq;
+use std::fs;
+use utils::{function, new_entry, TestDir};
+
+#[tokio::test]
+async fn default_rules() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(cwd.path().join("config.json"), r#"{ "headers": {} }"#).unwrap();
+
+    let generator = Generator::new(&cwd, Vec::new()).await.unwrap();
+    generator.generate_headers_file().await.unwrap().unwrap();
+
+    assert_eq!(
+        fs::read_to_string(cwd.path().join("output/_headers")).unwrap(),
+        "/katex/*
+  Cache-Control: public, max-age=31536000, immutable
+/media/*
+  Cache-Control: public, max-age=31536000, immutable
+/*
+  Cache-Control: public, max-age=600
+"
+    );
+}
+
+#[tokio::test]
+async fn overridden_rules() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "headers": { "rules": [{ "path": "/fonts/*", "headers": { "Cache-Control": "max-age=86400", "X-Robots-Tag": "noindex" } }] } }"#,
+    )
+    .unwrap();
+
+    let generator = Generator::new(
+        &cwd,
+        vec![new_entry(
+            "cf2bacc9d75c4226aab53601c336f295",
+            "Day 0: Nannou, helping L, and lots of noise",
+            "Every journey starts with 1 O'clock: assistance.",
+            Some("2021-11-07".parse().unwrap()),
+            None,
+        )],
+    )
+    .await
+    .unwrap();
+    generator.generate_headers_file().await.unwrap().unwrap();
+
+    assert_eq!(
+        fs::read_to_string(cwd.path().join("output/_headers")).unwrap(),
+        "/fonts/*
+  Cache-Control: max-age=86400
+  X-Robots-Tag: noindex
+"
+    );
+}
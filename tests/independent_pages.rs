@@ -0,0 +1,198 @@
+mod utils;
+
+use diary_generator::{Generator, Properties};
+use notion_generator::response::Page;
+use std::fs;
+use utils::{function, new_entry, DirEntry, TestDir};
+
+fn entries() -> Vec<Page<Properties>> {
+    vec![new_entry(
+        "cf2bacc9d75c4226aab53601c336f295",
+        "Day 0: Nannou, helping L, and lots of noise",
+        "Every journey starts with 1 O'clock: assistance.",
+        Some("2021-11-07".parse().unwrap()),
+        None,
+    )]
+}
+
+#[tokio::test]
+async fn wraps_html_and_markdown_and_copies_the_rest() {
+    let cwd = TestDir::new(function!());
+
+    fs::create_dir(cwd.path().join("pages")).unwrap();
+    fs::write(cwd.path().join("pages/about.html"), "<p>Hello there</p>").unwrap();
+    fs::write(cwd.path().join("pages/now.md"), "# Now\n\nWriting *things*").unwrap();
+    fs::write(cwd.path().join("pages/humans.txt"), "/* TEAM */").unwrap();
+    fs::write(cwd.path().join("pages/opml.xml"), "<opml/>").unwrap();
+
+    let generator = Generator::new(&cwd, entries()).await.unwrap();
+    generator
+        .generate_independent_pages()
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(
+        DirEntry::breakdown(cwd.path().join("output")),
+        DirEntry::dir(
+            "output",
+            [
+                DirEntry::file("about.html"),
+                DirEntry::file("humans.txt"),
+                DirEntry::file("now.html"),
+                DirEntry::file("opml.xml"),
+            ]
+        ),
+    );
+
+    let about = fs::read_to_string(cwd.path().join("output/about.html")).unwrap();
+    assert!(about.contains("<title>About - "));
+    assert!(about.contains("<p>Hello there</p>"));
+
+    let now = fs::read_to_string(cwd.path().join("output/now.html")).unwrap();
+    assert!(now.contains("<title>Now - "));
+    assert!(now.contains("<h1>Now</h1>\n<p>Writing <em>things</em></p>"));
+
+    assert_eq!(
+        fs::read_to_string(cwd.path().join("output/humans.txt")).unwrap(),
+        "/* TEAM */"
+    );
+    assert_eq!(
+        fs::read_to_string(cwd.path().join("output/opml.xml")).unwrap(),
+        "<opml/>"
+    );
+}
+
+#[tokio::test]
+async fn pages_dir_is_configurable() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "pages_dir": "site/pages" }"#,
+    )
+    .unwrap();
+    fs::create_dir_all(cwd.path().join("site/pages")).unwrap();
+    fs::write(
+        cwd.path().join("site/pages/about.html"),
+        "<p>Hello there</p>",
+    )
+    .unwrap();
+
+    let generator = Generator::new(&cwd, entries()).await.unwrap();
+    generator
+        .generate_independent_pages()
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(
+        DirEntry::breakdown(cwd.path().join("output")),
+        DirEntry::dir("output", [DirEntry::file("about.html")]),
+    );
+    assert_eq!(generator.public_dir(), cwd.path().join("public"));
+}
+
+#[tokio::test]
+async fn front_matter() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "url": "https://gamediary.dev" }"#,
+    )
+    .unwrap();
+    fs::create_dir(cwd.path().join("pages")).unwrap();
+    fs::write(
+        cwd.path().join("pages/about.md"),
+        concat!(
+            "---\n",
+            "title: About me\n",
+            "description: \"Who writes this diary: a person\"\n",
+            "cover: /media/about.png\n",
+            "---\n",
+            "# About\n",
+        ),
+    )
+    .unwrap();
+    fs::write(cwd.path().join("pages/now.md"), "---\n\nWriting *things*").unwrap();
+    fs::write(
+        cwd.path().join("pages/uses.md"),
+        "---\n\nWhat I use\n\nHardware\n---\n\nA laptop",
+    )
+    .unwrap();
+
+    let generator = Generator::new(&cwd, entries()).await.unwrap();
+    generator
+        .generate_independent_pages()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let about = fs::read_to_string(cwd.path().join("output/about.html")).unwrap();
+    assert!(about.contains("<title>About me - Diary</title>"));
+    assert!(
+        about.contains(r#"<meta name="description" content="Who writes this diary: a person">"#)
+    );
+    assert!(about
+        .contains(r#"<meta property="og:description" content="Who writes this diary: a person">"#));
+    assert!(about
+        .contains(r#"<meta property="og:image" content="https://gamediary.dev/media/about.png">"#));
+    assert!(about.contains("<h1>About</h1>"));
+    assert!(!about.contains("cover:"));
+
+    // A leading horizontal rule without a closing one isn't front matter
+    let now = fs::read_to_string(cwd.path().join("output/now.html")).unwrap();
+    assert!(now.contains("<title>Now - Diary</title>"));
+    assert!(now.contains("<hr />\n<p>Writing <em>things</em></p>"));
+    assert!(!now.contains(r#"<meta name="description""#));
+
+    // Neither are lines that aren't `key: value` pairs between a rule and a heading underline
+    let uses = fs::read_to_string(cwd.path().join("output/uses.html")).unwrap();
+    assert!(uses.contains("<title>Uses - Diary</title>"));
+    assert!(uses.contains("<hr />\n<p>What I use</p>\n<h2>Hardware</h2>\n<p>A laptop</p>"));
+}
+
+#[tokio::test]
+async fn subdirectories_keep_their_path() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "url": "https://example.com/" }"#,
+    )
+    .unwrap();
+    fs::create_dir_all(cwd.path().join("pages/about/media")).unwrap();
+    fs::write(cwd.path().join("pages/about.html"), "<p>Hello there</p>").unwrap();
+    fs::write(cwd.path().join("pages/about/team.html"), "<p>The team</p>").unwrap();
+    fs::write(cwd.path().join("pages/about/media/logo.svg"), "<svg/>").unwrap();
+
+    let generator = Generator::new(&cwd, entries()).await.unwrap();
+    generator
+        .generate_independent_pages()
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(
+        DirEntry::breakdown(cwd.path().join("output")),
+        DirEntry::dir(
+            "output",
+            [
+                DirEntry::dir(
+                    "about",
+                    [
+                        DirEntry::dir("media", [DirEntry::file("logo.svg")]),
+                        DirEntry::file("team.html"),
+                    ]
+                ),
+                DirEntry::file("about.html"),
+            ]
+        ),
+    );
+
+    let team = fs::read_to_string(cwd.path().join("output/about/team.html")).unwrap();
+    assert!(team.contains("<title>Team - "));
+    assert!(team.contains(r#"<meta property="og:url" content="https://example.com/about/team">"#));
+    assert!(team.contains("<p>The team</p>"));
+}
@@ -1,10 +1,11 @@
 mod utils;
 
-use diary_generator::Generator;
-use maud::{html, DOCTYPE};
+use diary_generator::{Generator, Options};
+use maud::{html, PreEscaped, DOCTYPE};
+use notion_generator::response::Page;
 use pretty_assertions::assert_eq;
 use std::fs;
-use utils::{function, new_entry, DirEntry, TestDir};
+use utils::{function, new_article, new_entry, DirEntry, TestDir};
 
 #[tokio::test]
 async fn empty_index() {
@@ -40,6 +41,11 @@ async fn empty_index() {
                     meta property="og:title" content="Diary";
                     meta property="og:description" content="A neat diary";
                     meta property="og:locale" content="en_US";
+                    meta property="og:type" content="website";
+                    meta property="og:site_name" content="Diary";
+                    script type="application/ld+json" {
+                        (PreEscaped(r#"{"@context":"https://schema.org","@type":"WebSite","description":"A neat diary","name":"Diary"}"#))
+                    }
                 }
                 body {
                     header {}
@@ -116,6 +122,11 @@ There’s no turning back now",
                     meta property="og:title" content="Diary";
                     meta property="og:description" content="A neat diary";
                     meta property="og:locale" content="en_US";
+                    meta property="og:type" content="website";
+                    meta property="og:site_name" content="Diary";
+                    script type="application/ld+json" {
+                        (PreEscaped(r#"{"@context":"https://schema.org","@type":"WebSite","description":"A neat diary","name":"Diary"}"#))
+                    }
                 }
                 body {
                     header {}
@@ -124,38 +135,38 @@ There’s no turning back now",
                             h1 { a href="2021" { "2021" } }
                             section {
                                 h2 { a href="2021/11" { "November" } }
-                                article {
+                                article id="ac3fb543001f4be5a25e4978abd05b1d" itemscope itemtype="https://schema.org/BlogPosting" {
                                     header {
-                                        h3 {
-                                            a href="/2021/11/09" {
+                                        h3 itemprop="headline" {
+                                            a href="/2021/11/09" itemprop="url" {
                                                 "Day 2: Enter Bevy & Shaders are hard"
                                             }
                                         }
-                                        p { time datetime="2021-11-09" { "November 09, 2021" } }
+                                        p { time datetime="2021-11-09" itemprop="datePublished" { "November 09, 2021" } }
                                     }
-                                    p { "3 O’clock: departure. We are not entering the world of Bevy where we will actually make things happen. There’s no turning back now" }
+                                    p itemprop="description" { "3 O’clock: departure. We are not entering the world of Bevy where we will actually make things happen. There’s no turning back now" }
                                 }
-                                article {
+                                article id="ac3fb543001f4be5a25e4978abd05b1d" itemscope itemtype="https://schema.org/BlogPosting" {
                                     header {
-                                        h3 {
-                                            a href="/2021/11/08" {
+                                        h3 itemprop="headline" {
+                                            a href="/2021/11/08" itemprop="url" {
                                                 "Day 1: Down the rabbit hole we go"
                                             }
                                         }
-                                        p { time datetime="2021-11-08" { "November 08, 2021" } }
+                                        p { time datetime="2021-11-08" itemprop="datePublished" { "November 08, 2021" } }
                                     }
-                                    p { "Alice starts making games by watching trains with the loveliest coding conductor." }
+                                    p itemprop="description" { "Alice starts making games by watching trains with the loveliest coding conductor." }
                                 }
-                                article {
+                                article id="cf2bacc9d75c4226aab53601c336f295" itemscope itemtype="https://schema.org/BlogPosting" {
                                     header {
-                                        h3 {
-                                            a href="/2021/11/07" {
+                                        h3 itemprop="headline" {
+                                            a href="/2021/11/07" itemprop="url" {
                                                 "Day 0: Nannou, helping L, and lots of noise"
                                             }
                                         }
-                                        p { time datetime="2021-11-07" { "November 07, 2021" } }
+                                        p { time datetime="2021-11-07" itemprop="datePublished" { "November 07, 2021" } }
                                     }
-                                    p { "Every journey starts with 1 O'clock: assistance. I just didn't know mine will also start with noise." }
+                                    p itemprop="description" { "Every journey starts with 1 O'clock: assistance. I just didn't know mine will also start with noise." }
                                 }
                             }
                         }
@@ -213,10 +224,16 @@ async fn with_config_url() {
                     link rel="stylesheet" href="/katex/katex.min.css";
                     title { "Diary" }
                     link rel="alternate" type="application/atom+xml" href="/feed.xml";
+                    link rel="alternate" type="application/feed+json" href="/feed.json";
                     meta property="og:title" content="Diary";
                     meta property="og:description" content="A neat diary";
                     meta property="og:locale" content="en_US";
                     meta property="og:url" content="https://gamediary.dev/";
+                    meta property="og:type" content="website";
+                    meta property="og:site_name" content="Diary";
+                    script type="application/ld+json" {
+                        (PreEscaped(r#"{"@context":"https://schema.org","@type":"WebSite","description":"A neat diary","name":"Diary","url":"https://gamediary.dev/"}"#))
+                    }
                 }
                 body {
                     header {}
@@ -228,3 +245,569 @@ async fn with_config_url() {
         .into_string(),
     );
 }
+
+#[tokio::test]
+async fn entries_per_year_limit() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "index_entries_per_year": 2 }"#,
+    )
+    .unwrap();
+
+    let generator = Generator::new(
+        &cwd,
+        vec![
+            new_entry(
+                "cf2bacc9d75c4226aab53601c336f295",
+                "Day 0: Nannou, helping L, and lots of noise",
+                "Every journey starts with 1 O'clock: assistance.",
+                Some("2021-10-07".parse().unwrap()),
+                None,
+            ),
+            new_entry(
+                "ac3fb543001f4be5a25e4978abd05b1d",
+                "Day 1: Down the rabbit hole we go",
+                "Alice starts making games.",
+                Some("2021-11-08".parse().unwrap()),
+                None,
+            ),
+            new_entry(
+                "78abd05b1dac3fb543001f4be5a25e49",
+                "Day 2: Enter Bevy & Shaders are hard",
+                "3 O’clock: departure.",
+                Some("2021-11-09".parse().unwrap()),
+                None,
+            ),
+            new_entry(
+                "a5e4978abd05b1dac3fb543001f4be52",
+                "Prologue",
+                "Before it all began.",
+                Some("2020-12-31".parse().unwrap()),
+                None,
+            ),
+        ],
+    )
+    .await
+    .unwrap();
+    generator
+        .generate_index_page()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let index = fs::read_to_string(cwd.path().join("output").join("index.html")).unwrap();
+    assert!(index.contains("Day 2: Enter Bevy &amp; Shaders are hard"));
+    assert!(index.contains("Day 1: Down the rabbit hole we go"));
+    assert!(!index.contains("Day 0: Nannou, helping L, and lots of noise"));
+    assert!(index.contains("Prologue"));
+    // The month of the hidden entry shouldn't be left behind empty
+    assert!(!index.contains(r#"<a href="2021/10">"#));
+    assert!(index.contains(r#"<a class="see-all" href="2021">See all 3 entries from 2021</a>"#));
+    assert!(!index.contains(r#"href="2020">See all"#));
+}
+
+#[tokio::test]
+async fn custom_viewport() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "viewport": "width=device-width, initial-scale=1, viewport-fit=cover" }"#,
+    )
+    .unwrap();
+
+    let generator = Generator::new(&cwd, Vec::new()).await.unwrap();
+    generator
+        .generate_index_page()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let index = fs::read_to_string(cwd.path().join("output").join("index.html")).unwrap();
+    assert!(index.contains(
+        r#"<meta name="viewport" content="width=device-width, initial-scale=1, viewport-fit=cover">"#
+    ));
+}
+
+#[tokio::test]
+async fn single_theme_color() {
+    let cwd = TestDir::new(function!());
+    fs::write(
+        cwd.path().join("config.json"),
+        r##"{ "theme_color": "#663399" }"##,
+    )
+    .unwrap();
+
+    let generator = Generator::new(&cwd, Vec::new()).await.unwrap();
+    generator
+        .generate_index_page()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let index = fs::read_to_string(cwd.path().join("output").join("index.html")).unwrap();
+    assert!(index.contains(r##"<meta name="theme-color" content="#663399">"##));
+}
+
+#[tokio::test]
+async fn theme_color_per_scheme() {
+    let cwd = TestDir::new(function!());
+    fs::write(
+        cwd.path().join("config.json"),
+        r##"{ "theme_color": { "light": "#ffffff", "dark": "#111111" } }"##,
+    )
+    .unwrap();
+
+    let generator = Generator::new(&cwd, Vec::new()).await.unwrap();
+    generator
+        .generate_index_page()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let index = fs::read_to_string(cwd.path().join("output").join("index.html")).unwrap();
+    assert!(index.contains(concat!(
+        r##"<meta name="theme-color" media="(prefers-color-scheme: light)" content="#ffffff">"##,
+        r##"<meta name="theme-color" media="(prefers-color-scheme: dark)" content="#111111">"##
+    )));
+}
+
+#[tokio::test]
+async fn preconnect_hints() {
+    let cwd = TestDir::new(function!());
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "preconnect": ["https://comments.example.com/", "https://stats.example.com:8443"] }"#,
+    )
+    .unwrap();
+
+    let generator = Generator::new(&cwd, Vec::new()).await.unwrap();
+    generator
+        .generate_index_page()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let index = fs::read_to_string(cwd.path().join("output").join("index.html")).unwrap();
+    assert!(index.contains(concat!(
+        r#"<link rel="preconnect" href="https://comments.example.com">"#,
+        r#"<link rel="dns-prefetch" href="https://comments.example.com">"#,
+        r#"<link rel="preconnect" href="https://stats.example.com:8443">"#,
+        r#"<link rel="dns-prefetch" href="https://stats.example.com:8443">"#
+    )));
+}
+
+#[tokio::test]
+async fn nav_marks_current_page() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{
+            "articles_page": true,
+            "nav": [{ "label": "Home", "href": "/" }, { "label": "Articles", "href": "/articles" }]
+        }"#,
+    )
+    .unwrap();
+
+    let generator = Generator::new(&cwd, Vec::new()).await.unwrap();
+    generator
+        .generate_index_page()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+    generator
+        .generate_articles_page()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let index = fs::read_to_string(cwd.path().join("output").join("index.html")).unwrap();
+    assert!(index.contains(concat!(
+        r#"<header><nav><ul><li><a href="/" aria-current="page">Home</a></li>"#,
+        r#"<li><a href="/articles">Articles</a></li></ul></nav>"#
+    )));
+
+    let articles = fs::read_to_string(cwd.path().join("output").join("articles.html")).unwrap();
+    assert!(articles.contains(concat!(
+        r#"<li><a href="/">Home</a></li>"#,
+        r#"<li><a href="/articles" aria-current="page">Articles</a></li>"#
+    )));
+}
+
+#[tokio::test]
+async fn custom_output_dir() {
+    let cwd = TestDir::new(function!());
+
+    let generator = Generator::with_options(
+        &cwd,
+        Vec::new(),
+        Options {
+            output_dir: Some(cwd.path().join("site")),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    generator
+        .generate_index_page()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert!(cwd.path().join("site").join("index.html").exists());
+    assert!(!cwd.path().join("output").exists());
+}
+
+#[tokio::test]
+async fn all_page() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(cwd.path().join("config.json"), r#"{ "all_page": true }"#).unwrap();
+
+    let generator = Generator::new(
+        &cwd,
+        vec![
+            new_entry(
+                "ac3fb543001f4be5a25e4978abd05b1d",
+                "Day 1: Down the rabbit hole we go",
+                "Alice starts making games.",
+                Some("2021-11-08".parse().unwrap()),
+                None,
+            ),
+            new_entry(
+                "cf2bacc9d75c4226aab53601c336f295",
+                "Day 0: Nannou, helping L, and lots of noise",
+                "Every journey starts with 1 O'clock: assistance.",
+                Some("2021-11-07".parse().unwrap()),
+                None,
+            ),
+        ],
+    )
+    .await
+    .unwrap();
+    generator
+        .generate_all_page()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let all = fs::read_to_string(cwd.path().join("output").join("all.html")).unwrap();
+    assert!(all.contains(concat!(
+        r#"<ul class="all-entries">"#,
+        r#"<li><time datetime="2021-11-07">2021-11-07</time> <a href="/2021/11/07">Day 0: Nannou, helping L, and lots of noise</a></li>"#,
+        r#"<li><time datetime="2021-11-08">2021-11-08</time> <a href="/2021/11/08">Day 1: Down the rabbit hole we go</a></li>"#,
+        "</ul>"
+    )));
+}
+
+#[tokio::test]
+async fn lang_switcher() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{
+            "articles_page": true,
+            "alternates": [
+                { "lang": "en", "label": "English", "url": "https://gamediary.dev" },
+                { "lang": "fr", "label": "Français", "url": "https://fr.gamediary.dev" }
+            ]
+        }"#,
+    )
+    .unwrap();
+
+    let generator = Generator::new(&cwd, Vec::new()).await.unwrap();
+    generator
+        .generate_articles_page()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let articles = fs::read_to_string(cwd.path().join("output").join("articles.html")).unwrap();
+    assert!(articles.contains(concat!(
+        r#"<nav class="lang-switcher"><ul>"#,
+        r#"<li><a href="https://gamediary.dev/articles" hreflang="en" lang="en" class="active" aria-current="true">English</a></li>"#,
+        r#"<li><a href="https://fr.gamediary.dev/articles" hreflang="fr" lang="fr">Français</a></li>"#,
+        "</ul></nav>"
+    )));
+}
+
+#[tokio::test]
+async fn no_articles_page_without_articles() {
+    let cwd = TestDir::new(function!());
+
+    let generator = Generator::new(
+        &cwd,
+        vec![new_entry(
+            "cf2bacc9d75c4226aab53601c336f295",
+            "Day 0: Nannou, helping L, and lots of noise",
+            "Every journey starts with 1 O'clock: assistance.",
+            Some("2021-11-07".parse().unwrap()),
+            None,
+        )],
+    )
+    .await
+    .unwrap();
+    generator
+        .generate_articles_page()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert!(!cwd.path().join("output").join("articles.html").exists());
+}
+
+#[tokio::test]
+async fn async_katex_link() {
+    let cwd = TestDir::new(function!());
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "katex_link": "async" }"#,
+    )
+    .unwrap();
+
+    let generator = Generator::new(&cwd, Vec::new()).await.unwrap();
+    generator
+        .generate_index_page()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let index = fs::read_to_string(cwd.path().join("output").join("index.html")).unwrap();
+    assert!(index.contains(concat!(
+        r#"<link rel="stylesheet" href="/katex/katex.min.css" media="print" onload="this.media='all'">"#,
+        r#"<noscript><link rel="stylesheet" href="/katex/katex.min.css"></noscript>"#,
+    )));
+}
+
+#[tokio::test]
+async fn inline_katex() {
+    let cwd = TestDir::new(function!());
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "katex_link": "inline" }"#,
+    )
+    .unwrap();
+
+    let mut generator = Generator::new(&cwd, Vec::new()).await.unwrap();
+    assert!(generator.inlines_katex());
+
+    // Until the stylesheet is downloaded there's nothing to inline
+    generator
+        .generate_index_page()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+    let index = fs::read_to_string(cwd.path().join("output").join("index.html")).unwrap();
+    assert!(index.contains(r#"<link rel="stylesheet" href="/katex/katex.min.css">"#));
+
+    generator.inline_katex(".katex{font:normal 1.21em KaTeX_Main}".to_string());
+    generator
+        .generate_index_page()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+    let index = fs::read_to_string(cwd.path().join("output").join("index.html")).unwrap();
+    assert!(index.contains("<style>.katex{font:normal 1.21em KaTeX_Main}</style>"));
+    assert!(!index.contains("katex.min.css"));
+}
+
+#[tokio::test]
+async fn footer_text() {
+    let cwd = TestDir::new(function!());
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "author": { "name": "Alice", "url": null }, "footer_text": "© {year} {author}" }"#,
+    )
+    .unwrap();
+    let year = time::OffsetDateTime::now_utc().year();
+
+    let generator = Generator::new(&cwd, Vec::new()).await.unwrap();
+    generator
+        .generate_index_page()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let index = fs::read_to_string(cwd.path().join("output").join("index.html")).unwrap();
+    assert!(index.contains(&format!(
+        r#"<footer><p class="footer-text">© {} Alice</p></footer>"#,
+        year
+    )));
+
+    // With a footer partial the line goes below it
+    fs::create_dir(cwd.path().join("partials")).unwrap();
+    fs::write(
+        cwd.path().join("partials/footer.html"),
+        r#"<a href="/feed.xml">Feed</a>"#,
+    )
+    .unwrap();
+
+    let generator = Generator::new(&cwd, Vec::new()).await.unwrap();
+    generator
+        .generate_index_page()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let index = fs::read_to_string(cwd.path().join("output").join("index.html")).unwrap();
+    assert!(index.contains(&format!(
+        r#"<footer><a href="/feed.xml">Feed</a><p class="footer-text">© {} Alice</p></footer>"#,
+        year
+    )));
+}
+
+#[tokio::test]
+async fn website_structured_data() {
+    let cwd = TestDir::new(function!());
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{
+            "name": "Game Diary",
+            "description": "Making games </script> one day at a time",
+            "url": "https://gamediary.dev/"
+        }"#,
+    )
+    .unwrap();
+
+    let generator = Generator::new(&cwd, Vec::new()).await.unwrap();
+    generator
+        .generate_index_page()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let index = fs::read_to_string(cwd.path().join("output").join("index.html")).unwrap();
+    let (_, json_ld) = index
+        .split_once(r#"<script type="application/ld+json">"#)
+        .unwrap();
+    let (json_ld, _) = json_ld.split_once("</script>").unwrap();
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(json_ld).unwrap(),
+        serde_json::json!({
+            "@context": "https://schema.org",
+            "@type": "WebSite",
+            "name": "Game Diary",
+            "description": "Making games </script> one day at a time",
+            "url": "https://gamediary.dev/"
+        })
+    );
+}
+
+#[tokio::test]
+async fn articles_description() {
+    let cwd = TestDir::new(function!());
+
+    let generator = Generator::new(
+        &cwd,
+        vec![new_article(
+            "78abd05b1dac3fb543001f4be5a25e49",
+            "Some article about something",
+            "some really interesting descritpion",
+            "interesting_article",
+            None,
+        )],
+    )
+    .await
+    .unwrap();
+    generator
+        .generate_articles_page()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let articles = fs::read_to_string(cwd.path().join("output").join("articles.html")).unwrap();
+    assert!(articles.contains(r#"<meta name="description" content="1 article of Diary">"#));
+    assert!(articles.contains(r#"<meta property="og:description" content="1 article of Diary">"#));
+}
+
+async fn index_with_rich_description(cwd: &TestDir, config: &str) -> String {
+    fs::write(cwd.path().join("config.json"), config).unwrap();
+
+    let entry = new_entry(
+        "ac3fb543001f4be5a25e4978abd05b1d",
+        "Day 1: Down the rabbit hole we go",
+        "",
+        Some("2021-11-08".parse().unwrap()),
+        None,
+    );
+    let text = |content: &str, bold: bool| {
+        serde_json::json!({
+            "type": "text",
+            "text": { "content": content, "link": null },
+            "annotations": {
+                "bold": bold,
+                "italic": false,
+                "strikethrough": false,
+                "underline": false,
+                "code": false,
+                "color": "default"
+            },
+            "plain_text": content,
+            "href": null
+        })
+    };
+    let mut properties = entry.properties;
+    properties.description.rich_text = serde_json::from_value(serde_json::json!([
+        text("Alice starts ", false),
+        text("making games", true),
+        text(".", false),
+    ]))
+    .unwrap();
+
+    let generator = Generator::new(
+        cwd,
+        vec![Page {
+            properties,
+            ..entry
+        }],
+    )
+    .await
+    .unwrap();
+    generator
+        .generate_index_page()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    fs::read_to_string(cwd.path().join("output/index.html")).unwrap()
+}
+
+#[tokio::test]
+async fn plain_descriptions_on_cards() {
+    let cwd = TestDir::new(function!());
+
+    let index = index_with_rich_description(&cwd, "{}").await;
+    assert!(index.contains(r#"<p itemprop="description">Alice starts making games.</p>"#));
+}
+
+#[tokio::test]
+async fn rich_descriptions_on_cards() {
+    let cwd = TestDir::new(function!());
+
+    let index = index_with_rich_description(&cwd, r#"{ "rich_descriptions": true }"#).await;
+    assert!(index.contains(r#"<p itemprop="description">Alice starts <"#));
+    assert!(!index.contains(r#"<p itemprop="description">Alice starts making games.</p>"#));
+}
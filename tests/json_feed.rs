@@ -0,0 +1,280 @@
+mod utils;
+
+use diary_generator::{Generator, Properties};
+use notion_generator::response::{
+    properties::RichTextProperty, Block, BlockType, Page, RichText, RichTextType,
+};
+use serde_json::json;
+use std::fs;
+use time::macros::date;
+use utils::{function, new_article, new_entry, TestDir};
+
+#[tokio::test]
+async fn json_feed() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"
+            {
+              "name": "Game Dev Diary",
+              "description": "A really cool diary",
+              "author": {
+                "name": "Mathspy",
+                "url": "https://mathspy.me"
+              },
+              "icon": "/media/icon.png",
+              "cover": "/media/cover.png",
+              "url": "https://gamediary.dev"
+            }
+        "#,
+    )
+    .unwrap();
+
+    let generator = Generator::new(
+        &cwd,
+        vec![
+            new_entry(
+                "cf2bacc9d75c4226aab53601c336f295",
+                "Day 0: Nannou, helping L, and lots of noise",
+                "Every journey starts with 1 O'clock: assistance.",
+                Some("2021-11-07".parse().unwrap()),
+                Some(date!(2021 - 11 - 07)),
+            ),
+            new_article(
+                "78abd05b1dac3fb543001f4be5a25e49",
+                "Some article about something",
+                "some really interesting descritpion",
+                "interesting_article",
+                Some(date!(2021 - 12 - 08)),
+            ),
+        ],
+    )
+    .await
+    .unwrap();
+    generator
+        .generate_json_feed()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let feed: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(cwd.path().join("output/feed.json")).unwrap())
+            .unwrap();
+    assert_eq!(
+        feed,
+        json!({
+            "version": "https://jsonfeed.org/version/1.1",
+            "title": "Game Dev Diary",
+            "home_page_url": "https://gamediary.dev/",
+            "feed_url": "https://gamediary.dev/feed.json",
+            "description": "A really cool diary",
+            "icon": "https://gamediary.dev/media/cover.png",
+            "favicon": "https://gamediary.dev/media/icon.png",
+            "authors": [{ "name": "Mathspy", "url": "https://mathspy.me/" }],
+            "language": "en",
+            "items": [
+                {
+                    "id": "https://gamediary.dev/interesting_article",
+                    "url": "https://gamediary.dev/interesting_article",
+                    "title": "Some article about something",
+                    "content_html": "",
+                    "summary": "some really interesting descritpion",
+                    "date_published": "2021-12-08T00:00:00Z",
+                    "date_modified": "2021-12-06T09:25:00Z"
+                },
+                {
+                    "id": "https://gamediary.dev/2021/11/07",
+                    "url": "https://gamediary.dev/2021/11/07",
+                    "title": "Day 0: Nannou, helping L, and lots of noise",
+                    "content_html": "",
+                    "summary": "Every journey starts with 1 O'clock: assistance.",
+                    "date_published": "2021-11-07T00:00:00Z",
+                    "date_modified": "2021-12-06T09:25:00Z"
+                }
+            ]
+        })
+    );
+}
+
+#[tokio::test]
+async fn no_json_feed_without_url() {
+    let cwd = TestDir::new(function!());
+
+    let generator = Generator::new(
+        &cwd,
+        vec![new_article(
+            "78abd05b1dac3fb543001f4be5a25e49",
+            "Some article about something",
+            "some really interesting descritpion",
+            "interesting_article",
+            Some(date!(2021 - 12 - 08)),
+        )],
+    )
+    .await
+    .unwrap();
+    generator
+        .generate_json_feed()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert!(!cwd.path().join("output/feed.json").exists());
+}
+
+#[tokio::test]
+async fn items_follow_the_same_rules_as_the_atom_feed() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"
+            {
+              "url": "https://example.com",
+              "feed_content_max_chars": 80,
+              "summary_separator": {}
+            }
+        "#,
+    )
+    .unwrap();
+
+    let text = |content: &str| {
+        vec![RichText {
+            plain_text: content.to_string(),
+            href: None,
+            annotations: Default::default(),
+            ty: RichTextType::Text {
+                content: content.to_string(),
+                link: None,
+            },
+        }]
+    };
+    let block = |id: &str, ty: BlockType| Block {
+        object: "block".to_string(),
+        id: id.parse().unwrap(),
+        created_time: "2021-11-15T18:03:00.000Z".to_string(),
+        last_edited_time: "2021-11-16T11:23:00.000Z".to_string(),
+        has_children: false,
+        archived: false,
+        ty,
+    };
+    let paragraph = |id: &str, content: &str| {
+        block(
+            id,
+            BlockType::Paragraph {
+                text: text(content),
+                children: vec![],
+            },
+        )
+    };
+
+    let day_1 = new_entry(
+        "ac3fb543001f4be5a25e4978abd05b1d",
+        "Day 1: Down the rabbit hole we go",
+        "Alice starts making games.",
+        Some("2021-11-08".parse().unwrap()),
+        Some(date!(2021 - 11 - 08)),
+    );
+    let blurb = "A longer blurb about the rabbit hole, just for the feed.";
+    let generator = Generator::new(
+        &cwd,
+        vec![
+            Page {
+                children: vec![
+                    paragraph(
+                        "4fb9dd79-2fc7-45b1-b3a2-8efae49992ed",
+                        "The first paragraph",
+                    ),
+                    block("6f3f0d6b-6bd7-4f6c-a1f2-0b6d6a3c5e11", BlockType::Divider),
+                    paragraph(
+                        "817c0ca1-721a-4565-ac54-eedbbe471f0b",
+                        "The second paragraph, which is long enough to be cut from the feed",
+                    ),
+                ],
+                ..new_entry(
+                    "cf2bacc9d75c4226aab53601c336f295",
+                    "Day 0: Nannou, helping L, and lots of noise",
+                    "Every journey starts with 1 O'clock: assistance.",
+                    Some("2021-11-07".parse().unwrap()),
+                    Some(date!(2021 - 11 - 07)),
+                )
+            },
+            Page {
+                properties: Properties {
+                    feed_summary: Some(RichTextProperty {
+                        id: "fs%3Ds".to_string(),
+                        rich_text: text(blurb),
+                    }),
+                    ..day_1.properties
+                },
+                ..day_1
+            },
+        ],
+    )
+    .await
+    .unwrap();
+    generator
+        .generate_json_feed()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let feed: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(cwd.path().join("output/feed.json")).unwrap())
+            .unwrap();
+
+    let day_1 = &feed["items"][0];
+    assert_eq!(day_1["summary"], blurb);
+
+    let day_0 = &feed["items"][1];
+    assert_eq!(day_0["summary"], "The first paragraph");
+    let content = day_0["content_html"].as_str().unwrap();
+    assert!(content.contains("The first paragraph"));
+    assert!(!content.contains("The second paragraph"));
+    assert!(content.ends_with(r#"<p><a href="https://example.com/2021/11/07">Read more</a></p>"#));
+}
+
+#[tokio::test]
+async fn json_feed_is_linked() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "url": "https://gamediary.dev" }"#,
+    )
+    .unwrap();
+
+    let generator = Generator::new(
+        &cwd,
+        vec![new_entry(
+            "cf2bacc9d75c4226aab53601c336f295",
+            "Day 0: Nannou, helping L, and lots of noise",
+            "Every journey starts with 1 O'clock: assistance.",
+            Some("2021-11-07".parse().unwrap()),
+            Some(date!(2021 - 11 - 07)),
+        )],
+    )
+    .await
+    .unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+    generator
+        .generate_subscribe_page()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let day = fs::read_to_string(cwd.path().join("output/2021/11/07.html")).unwrap();
+    assert!(day.contains(concat!(
+        r#"<link rel="alternate" type="application/atom+xml" href="/feed.xml">"#,
+        r#"<link rel="alternate" type="application/feed+json" href="/feed.json">"#
+    )));
+
+    let subscribe = fs::read_to_string(cwd.path().join("output/subscribe.html")).unwrap();
+    assert!(subscribe.contains(
+        r#"<li><a href="https://gamediary.dev/feed.json" type="application/feed+json">JSON Feed</a></li>"#
+    ));
+}
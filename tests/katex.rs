@@ -0,0 +1,98 @@
+mod utils;
+
+use diary_generator::katex;
+use reqwest::Client;
+use std::fs;
+use utils::{function, Server, TestDir};
+
+/// Serves a tiny KaTeX for every version
+fn katex_server() -> Server {
+    Server::new(|_, path| {
+        if path.ends_with("/katex.min.css") {
+            (
+                200,
+                b"@font-face{src:url(fonts/KaTeX_Main-Bold.woff2) format(\"woff2\")}".to_vec(),
+            )
+        } else {
+            (200, b"font".to_vec())
+        }
+    })
+}
+
+#[tokio::test]
+async fn downloads_are_cached() {
+    let cwd = TestDir::new(function!());
+    let server = katex_server();
+    let client = Client::builder().no_proxy().build().unwrap();
+    let cache = cwd.path().join(".cache");
+    let output = cwd.path().join("output");
+
+    let download = |version: &str| {
+        katex::download_from(
+            client.clone(),
+            format!("{}katex@{}/", server.url(), version),
+            cache.clone(),
+            output.clone(),
+            None,
+            false,
+        )
+    };
+
+    download("0.15.1").await.unwrap().unwrap();
+    assert_eq!(server.requests().len(), 2);
+    assert_eq!(
+        fs::read_to_string(output.join("katex/fonts/KaTeX_Main-Bold.woff2")).unwrap(),
+        "font"
+    );
+    // The cache is kept out of the output so that it isn't deployed
+    assert!(cache.join("katex/cache.json").is_file());
+    assert!(!output.join("katex/cache.json").exists());
+
+    // Even an output that was cleaned in between is filled from the cache
+    fs::remove_dir_all(&output).unwrap();
+    download("0.15.1").await.unwrap().unwrap();
+    assert_eq!(server.requests().len(), 2);
+    assert!(output.join("katex/katex.min.css").is_file());
+    assert!(output.join("katex/fonts/KaTeX_Main-Bold.woff2").is_file());
+
+    // A different version of KaTeX isn't in the cache
+    download("0.16.0").await.unwrap().unwrap();
+    assert_eq!(server.requests().len(), 4);
+
+    // Neither are files that changed since they were downloaded
+    fs::write(cache.join("katex/katex.min.css"), "").unwrap();
+    download("0.16.0").await.unwrap().unwrap();
+    assert_eq!(
+        server.requests()[4..],
+        [
+            "GET /katex@0.16.0/katex.min.css",
+            "GET /katex@0.16.0/fonts/KaTeX_Main-Bold.woff2"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn oversized_stylesheet_is_rejected() {
+    let cwd = TestDir::new(function!());
+    let server = katex_server();
+    let client = Client::builder().no_proxy().build().unwrap();
+
+    let error = katex::download_from(
+        client,
+        format!("{}katex@0.15.1/", server.url()),
+        cwd.path().join(".cache"),
+        cwd.path().join("output"),
+        Some(16),
+        false,
+    )
+    .await
+    .unwrap()
+    .unwrap_err();
+
+    assert!(error
+        .to_string()
+        .contains("katex.min.css is at least 64 bytes which is over max_download_bytes of 16"));
+    // Without the stylesheet there are no fonts to download
+    assert_eq!(server.requests(), ["GET /katex@0.15.1/katex.min.css"]);
+    assert!(!cwd.path().join("output/katex").exists());
+}
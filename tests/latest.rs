@@ -0,0 +1,72 @@
+mod utils;
+
+use diary_generator::Generator;
+use serde_json::json;
+use std::fs;
+use utils::{function, new_entry, TestDir};
+
+#[tokio::test]
+async fn latest_entries() {
+    let cwd = TestDir::new(function!());
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "url": "https://gamediary.dev/", "latest_entries": 2 }"#,
+    )
+    .unwrap();
+
+    let generator = Generator::new(
+        &cwd,
+        vec![
+            new_entry(
+                "cf2bacc9d75c4226aab53601c336f295",
+                "Day 0: Nannou, helping L, and lots of noise",
+                "Every journey starts with 1 O'clock: assistance.",
+                Some("2021-11-07".parse().unwrap()),
+                None,
+            ),
+            new_entry(
+                "ac3fb543001f4be5a25e4978abd05b1d",
+                "Day 1: Down the rabbit hole we go",
+                "Alice starts making games.",
+                Some("2021-11-08".parse().unwrap()),
+                None,
+            ),
+            new_entry(
+                "78abd05b1dac3fb543001f4be5a25e49",
+                "Day 2: Trains",
+                "Watching trains with the loveliest coding conductor.",
+                Some("2021-11-09".parse().unwrap()),
+                None,
+            ),
+        ],
+    )
+    .await
+    .unwrap();
+    generator
+        .generate_latest_json()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let latest: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(cwd.path().join("output/latest.json")).unwrap())
+            .unwrap();
+    assert_eq!(
+        latest,
+        json!([
+            {
+                "title": "Day 2: Trains",
+                "url": "https://gamediary.dev/2021/11/09",
+                "date": "2021-11-09",
+                "description": "Watching trains with the loveliest coding conductor."
+            },
+            {
+                "title": "Day 1: Down the rabbit hole we go",
+                "url": "https://gamediary.dev/2021/11/08",
+                "date": "2021-11-08",
+                "description": "Alice starts making games."
+            }
+        ])
+    );
+}
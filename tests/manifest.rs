@@ -0,0 +1,133 @@
+mod utils;
+
+use diary_generator::Generator;
+use serde_json::{json, Value};
+use std::fs;
+use time::macros::date;
+use utils::{function, new_article, new_entry, TestDir};
+
+#[tokio::test]
+async fn lists_every_page() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "url": "https://example.com" }"#,
+    )
+    .unwrap();
+    fs::create_dir(cwd.path().join("pages")).unwrap();
+    fs::write(cwd.path().join("pages/about.html"), "<p>Hello there</p>").unwrap();
+    fs::write(cwd.path().join("pages/humans.txt"), "/* TEAM */").unwrap();
+
+    let generator = Generator::new(
+        &cwd,
+        vec![
+            new_entry(
+                "cf2bacc9d75c4226aab53601c336f295",
+                "Day 0: Nannou, helping L, and lots of noise",
+                "Every journey starts with 1 O'clock: assistance.",
+                Some("2021-11-07".parse().unwrap()),
+                Some(date!(2021 - 11 - 07)),
+            ),
+            new_article(
+                "78abd05b1dac3fb543001f4be5a25e49",
+                "Some article about something",
+                "some really interesting descritpion",
+                "interesting_article",
+                Some(date!(2021 - 12 - 08)),
+            ),
+        ],
+    )
+    .await
+    .unwrap();
+    generator
+        .generate_manifest_json()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let manifest: Value =
+        serde_json::from_str(&fs::read_to_string(cwd.path().join("output/pages.json")).unwrap())
+            .unwrap();
+    let page = |path: &str, url: &str, ty: &str, title: &str, published: Value, last_edited| {
+        json!({
+            "path": path,
+            "url": url,
+            "type": ty,
+            "title": title,
+            "published": published,
+            "last_edited": last_edited,
+        })
+    };
+    let last_edited = json!("2021-12-06T09:25:00.000Z");
+    assert_eq!(
+        manifest,
+        json!([
+            page(
+                "index.html",
+                "https://example.com/",
+                "index",
+                "Diary",
+                Value::Null,
+                last_edited.clone()
+            ),
+            page(
+                "articles.html",
+                "https://example.com/articles",
+                "articles",
+                "Articles",
+                Value::Null,
+                last_edited.clone()
+            ),
+            page(
+                "subscribe.html",
+                "https://example.com/subscribe",
+                "subscribe",
+                "Subscribe",
+                Value::Null,
+                Value::Null
+            ),
+            page(
+                "2021.html",
+                "https://example.com/2021",
+                "year",
+                "2021",
+                Value::Null,
+                last_edited.clone()
+            ),
+            page(
+                "2021/11.html",
+                "https://example.com/2021/11",
+                "month",
+                "November 2021",
+                Value::Null,
+                last_edited.clone()
+            ),
+            page(
+                "2021/11/07.html",
+                "https://example.com/2021/11/07",
+                "entry",
+                "Day 0: Nannou, helping L, and lots of noise",
+                json!("2021-11-07"),
+                last_edited.clone()
+            ),
+            page(
+                "interesting_article.html",
+                "https://example.com/interesting_article",
+                "article",
+                "Some article about something",
+                json!("2021-12-08"),
+                last_edited.clone()
+            ),
+            page(
+                "about.html",
+                "https://example.com/about",
+                "page",
+                "About",
+                Value::Null,
+                Value::Null
+            ),
+        ])
+    );
+}
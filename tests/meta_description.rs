@@ -0,0 +1,50 @@
+mod utils;
+
+use diary_generator::Generator;
+use std::fs;
+use utils::{function, new_entry, TestDir};
+
+#[tokio::test]
+async fn long_descriptions_are_truncated() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"
+            {
+              "description": "A really cool diary",
+              "meta_description_max": 30
+            }
+        "#,
+    )
+    .unwrap();
+
+    let generator = Generator::new(
+        &cwd,
+        vec![new_entry(
+            "cf2bacc9d75c4226aab53601c336f295",
+            "Day 0: Nannou, helping L, and lots of noise",
+            "Every journey starts with 1 O'clock: assistance.",
+            Some("2021-11-07".parse().unwrap()),
+            None,
+        )],
+    )
+    .await
+    .unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+    generator
+        .generate_index_page()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let day = fs::read_to_string(cwd.path().join("output/2021/11/07.html")).unwrap();
+    assert!(day.contains(r#"<meta name="description" content="Every journey starts with 1…">"#));
+    assert!(
+        day.contains(r#"<meta property="og:description" content="Every journey starts with 1…">"#)
+    );
+
+    let index = fs::read_to_string(cwd.path().join("output/index.html")).unwrap();
+    assert!(index.contains(r#"<meta name="description" content="A really cool diary">"#));
+}
@@ -0,0 +1,116 @@
+mod utils;
+
+use diary_generator::Generator;
+use notion_generator::response::{Block, BlockType, Page, RichText, RichTextType};
+use serde_json::json;
+use std::fs;
+use time::macros::date;
+use utils::{function, new_article, new_entry, TestDir};
+
+fn paragraph(id: &str, text: &str, children: Vec<Block>) -> Block {
+    Block {
+        object: "block".to_string(),
+        id: id.parse().unwrap(),
+        created_time: "2021-11-15T18:03:00.000Z".to_string(),
+        last_edited_time: "2021-11-16T11:23:00.000Z".to_string(),
+        has_children: !children.is_empty(),
+        archived: false,
+        ty: BlockType::Paragraph {
+            text: vec![RichText {
+                plain_text: text.to_string(),
+                href: None,
+                annotations: Default::default(),
+                ty: RichTextType::Text {
+                    content: text.to_string(),
+                    link: None,
+                },
+            }],
+            children,
+        },
+    }
+}
+
+#[tokio::test]
+async fn search_index() {
+    let cwd = TestDir::new(function!());
+
+    let entry = new_entry(
+        "cf2bacc9d75c4226aab53601c336f295",
+        "Day 0: Nannou, helping L, and lots of noise",
+        "Every journey starts with 1 O'clock: assistance.",
+        Some("2021-11-07".parse().unwrap()),
+        None,
+    );
+    let generator = Generator::new(
+        &cwd,
+        vec![
+            Page {
+                children: vec![paragraph(
+                    "4fb9dd79-2fc7-45b1-b3a2-8efae49992ed",
+                    "Playing with Nannou",
+                    vec![paragraph(
+                        "817c0ca1-721a-4565-ac54-eedbbe471f0b",
+                        "and lots of noise",
+                        vec![],
+                    )],
+                )],
+                ..entry
+            },
+            new_article(
+                "78abd05b1dac3fb543001f4be5a25e49",
+                "Some article about something",
+                "some really interesting descritpion",
+                "interesting_article",
+                Some(date!(2021 - 12 - 08)),
+            ),
+        ],
+    )
+    .await
+    .unwrap();
+    generator
+        .generate_search_index()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let index: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(cwd.path().join("output/search-index.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        index,
+        json!([
+            {
+                "title": "Day 0: Nannou, helping L, and lots of noise",
+                "url": "/2021/11/07",
+                "description": "Every journey starts with 1 O'clock: assistance.",
+                "content": "Playing with Nannou\nand lots of noise"
+            },
+            {
+                "title": "Some article about something",
+                "url": "/interesting_article",
+                "description": "some really interesting descritpion",
+                "content": ""
+            }
+        ])
+    );
+}
+
+#[tokio::test]
+async fn empty_search_index() {
+    let cwd = TestDir::new(function!());
+
+    let generator = Generator::new(&cwd, Vec::new()).await.unwrap();
+    generator
+        .generate_search_index()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(
+        fs::read_to_string(cwd.path().join("output/search-index.json")).unwrap(),
+        "[]"
+    );
+}
@@ -0,0 +1,134 @@
+mod utils;
+
+use diary_generator::{Generator, Properties};
+use notion_generator::response::{properties::RichTextProperty, Page, RichText, RichTextType};
+use pretty_assertions::assert_eq;
+use std::fs;
+use utils::{function, new_entry, DirEntry, TestDir};
+
+fn in_series(page: Page<Properties>, series: &str) -> Page<Properties> {
+    Page {
+        properties: Properties {
+            series: Some(RichTextProperty {
+                id: "Xr%3Cq".to_string(),
+                rich_text: vec![RichText {
+                    ty: RichTextType::Text {
+                        content: series.to_string(),
+                        link: None,
+                    },
+                    annotations: Default::default(),
+                    plain_text: series.to_string(),
+                    href: None,
+                }],
+            }),
+            ..page.properties
+        },
+        ..page
+    }
+}
+
+#[tokio::test]
+async fn series_pages_and_links() {
+    let cwd = TestDir::new(function!());
+
+    let generator = Generator::new(
+        &cwd,
+        vec![
+            in_series(
+                new_entry(
+                    "cf2bacc9d75c4226aab53601c336f295",
+                    "Day 0: Nannou, helping L, and lots of noise",
+                    "Every journey starts with 1 O'clock: assistance.",
+                    Some("2021-11-07".parse().unwrap()),
+                    None,
+                ),
+                "Learning Bevy",
+            ),
+            new_entry(
+                "ac3fb543001f4be5a25e4978abd05b1d",
+                "Day 1: Down the rabbit hole we go",
+                "Alice starts making games.",
+                Some("2021-11-08".parse().unwrap()),
+                None,
+            ),
+            in_series(
+                new_entry(
+                    "78abd05b1dac3fb543001f4be5a25e49",
+                    "Day 2: Enter Bevy & Shaders are hard",
+                    "3 O’clock: departure.",
+                    Some("2021-11-09".parse().unwrap()),
+                    None,
+                ),
+                "Learning Bevy",
+            ),
+        ],
+    )
+    .await
+    .unwrap();
+    generator
+        .generate_series_pages()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+
+    assert_eq!(
+        DirEntry::breakdown(cwd.path().join("output").join("series")),
+        DirEntry::dir("series", [DirEntry::file("learning-bevy.html")]),
+    );
+
+    let series_page =
+        fs::read_to_string(cwd.path().join("output/series/learning-bevy.html")).unwrap();
+    assert!(series_page.contains("<title>Learning Bevy - Diary</title>"));
+    assert!(series_page.contains(r#"<a href="/2021/11/07" itemprop="url">Day 0"#));
+    assert!(series_page.contains(r#"<a href="/2021/11/09" itemprop="url">Day 2"#));
+    assert!(!series_page.contains("Day 1"));
+
+    let first_day = fs::read_to_string(cwd.path().join("output/2021/11/07.html")).unwrap();
+    assert!(first_day.contains(
+        r#"<nav class="series-links"><p>Part 1 of <a href="/series/learning-bevy">Learning Bevy</a></p><a href="/2021/11/09" rel="next">Next: Day 2: Enter Bevy &amp; Shaders are hard</a></nav>"#
+    ));
+
+    let unrelated_day = fs::read_to_string(cwd.path().join("output/2021/11/08.html")).unwrap();
+    assert!(!unrelated_day.contains("series-links"));
+}
+
+#[tokio::test]
+async fn series_sharing_a_page_fail() {
+    let cwd = TestDir::new(function!());
+
+    let error = Generator::new(
+        &cwd,
+        vec![
+            in_series(
+                new_entry(
+                    "cf2bacc9d75c4226aab53601c336f295",
+                    "Day 0: Nannou, helping L, and lots of noise",
+                    "Every journey starts with 1 O'clock: assistance.",
+                    Some("2021-11-07".parse().unwrap()),
+                    None,
+                ),
+                "Learning Bevy",
+            ),
+            in_series(
+                new_entry(
+                    "ac3fb543001f4be5a25e4978abd05b1d",
+                    "Day 1: Down the rabbit hole we go",
+                    "Alice starts making games.",
+                    Some("2021-11-08".parse().unwrap()),
+                    None,
+                ),
+                "Learning: Bevy!",
+            ),
+        ],
+    )
+    .await
+    .err()
+    .unwrap();
+
+    assert_eq!(
+        error.to_string(),
+        r#"The series "Learning Bevy" and "Learning: Bevy!" would both be at /series/learning-bevy, rename one of them"#
+    );
+}
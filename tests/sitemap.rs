@@ -0,0 +1,254 @@
+mod utils;
+
+use diary_generator::Generator;
+use notion_generator::response::Page;
+use pretty_assertions::assert_eq;
+use std::fs;
+use time::macros::date;
+use utils::{function, new_article, new_entry, DirEntry, TestDir};
+
+#[tokio::test]
+async fn lastmod_and_changefreq() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"
+            {
+              "name": "Game Dev Diary",
+              "url": "https://gamediary.dev"
+            }
+        "#,
+    )
+    .unwrap();
+
+    let generator = Generator::new(
+        &cwd,
+        vec![
+            new_entry(
+                "cf2bacc9d75c4226aab53601c336f295",
+                "Day 0: Nannou, helping L, and lots of noise",
+                "Every journey starts with 1 O'clock: assistance.",
+                Some("2020-12-31".parse().unwrap()),
+                None,
+            ),
+            Page {
+                last_edited_time: "yesterday-ish".to_string(),
+                ..new_entry(
+                    "ac3fb543001f4be5a25e4978abd05b1d",
+                    "Day 1: Down the rabbit hole we go",
+                    "Alice starts making games.",
+                    Some("2021-11-08".parse().unwrap()),
+                    None,
+                )
+            },
+            new_article(
+                "78abd05b1dac3fb543001f4be5a25e49",
+                "Some article about something",
+                "some really interesting descritpion",
+                "interesting_article",
+                Some(date!(2021 - 12 - 08)),
+            ),
+        ],
+    )
+    .await
+    .unwrap();
+    generator
+        .generate_sitemap()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(
+        fs::read_to_string(cwd.path().join("output").join("sitemap.xml")).unwrap(),
+        concat!(
+            r#"<?xml version="1.0" encoding="utf-8"?>"#,
+            r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+            "<url><loc>https://gamediary.dev/</loc><lastmod>2021-12-06</lastmod><changefreq>daily</changefreq><priority>1.0</priority></url>",
+            "<url><loc>https://gamediary.dev/articles</loc><lastmod>2021-12-06</lastmod><changefreq>weekly</changefreq><priority>0.8</priority></url>",
+            "<url><loc>https://gamediary.dev/2020</loc><lastmod>2021-12-06</lastmod><changefreq>yearly</changefreq><priority>0.3</priority></url>",
+            "<url><loc>https://gamediary.dev/2021</loc><changefreq>monthly</changefreq><priority>0.5</priority></url>",
+            "<url><loc>https://gamediary.dev/2020/12</loc><lastmod>2021-12-06</lastmod><changefreq>yearly</changefreq><priority>0.3</priority></url>",
+            "<url><loc>https://gamediary.dev/2021/11</loc><changefreq>monthly</changefreq><priority>0.5</priority></url>",
+            "<url><loc>https://gamediary.dev/2020/12/31</loc><lastmod>2021-12-06</lastmod><changefreq>monthly</changefreq><priority>0.7</priority></url>",
+            "<url><loc>https://gamediary.dev/2021/11/08</loc><changefreq>monthly</changefreq><priority>0.7</priority></url>",
+            "<url><loc>https://gamediary.dev/interesting_article</loc><lastmod>2021-12-06</lastmod><changefreq>monthly</changefreq><priority>0.7</priority></url>",
+            "<url><loc>https://gamediary.dev/subscribe</loc><changefreq>yearly</changefreq><priority>0.3</priority></url>",
+            "</urlset>",
+        )
+    );
+}
+
+#[tokio::test]
+async fn split_into_sitemap_index() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"
+            {
+              "name": "Game Dev Diary",
+              "url": "https://gamediary.dev",
+              "sitemap_max_urls": 4
+            }
+        "#,
+    )
+    .unwrap();
+
+    let generator = Generator::new(
+        &cwd,
+        vec![
+            new_entry(
+                "cf2bacc9d75c4226aab53601c336f295",
+                "Day 0: Nannou, helping L, and lots of noise",
+                "Every journey starts with 1 O'clock: assistance.",
+                Some("2021-11-07".parse().unwrap()),
+                None,
+            ),
+            new_entry(
+                "ac3fb543001f4be5a25e4978abd05b1d",
+                "Day 1: Down the rabbit hole we go",
+                "Alice starts making games.",
+                Some("2021-11-08".parse().unwrap()),
+                None,
+            ),
+        ],
+    )
+    .await
+    .unwrap();
+    generator
+        .generate_sitemap()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(
+        DirEntry::breakdown(cwd.path().join("output")),
+        DirEntry::dir(
+            "output",
+            [
+                DirEntry::file("sitemap-1.xml"),
+                DirEntry::file("sitemap-2.xml"),
+                DirEntry::file("sitemap.xml"),
+            ]
+        ),
+    );
+    assert_eq!(
+        fs::read_to_string(cwd.path().join("output").join("sitemap.xml")).unwrap(),
+        concat!(
+            r#"<?xml version="1.0" encoding="utf-8"?>"#,
+            r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+            "<sitemap><loc>https://gamediary.dev/sitemap-1.xml</loc><lastmod>2021-12-06</lastmod></sitemap>",
+            "<sitemap><loc>https://gamediary.dev/sitemap-2.xml</loc><lastmod>2021-12-06</lastmod></sitemap>",
+            "</sitemapindex>",
+        )
+    );
+}
+
+#[tokio::test]
+async fn excluded_paths() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"
+            {
+              "url": "https://gamediary.dev",
+              "sitemap_exclude": ["/legal/*"]
+            }
+        "#,
+    )
+    .unwrap();
+
+    let generator = Generator::new(
+        &cwd,
+        vec![
+            new_entry(
+                "cf2bacc9d75c4226aab53601c336f295",
+                "Day 0: Nannou, helping L, and lots of noise",
+                "Every journey starts with 1 O'clock: assistance.",
+                Some("2021-11-07".parse().unwrap()),
+                None,
+            ),
+            new_article(
+                "78abd05b1dac3fb543001f4be5a25e49",
+                "Privacy policy",
+                "What happens to your data.",
+                "legal/privacy",
+                Some(date!(2021 - 12 - 08)),
+            ),
+            new_article(
+                "5f5e3d4b9b7a4a3c8d8e2f1a0b9c8d7e",
+                "Some article about something",
+                "some really interesting descritpion",
+                "interesting_article",
+                Some(date!(2021 - 12 - 08)),
+            ),
+        ],
+    )
+    .await
+    .unwrap();
+    generator
+        .generate_sitemap()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let sitemap = fs::read_to_string(cwd.path().join("output").join("sitemap.xml")).unwrap();
+    assert!(!sitemap.contains("https://gamediary.dev/legal/privacy"));
+    assert!(sitemap.contains("<loc>https://gamediary.dev/interesting_article</loc>"));
+    assert!(sitemap.contains("<loc>https://gamediary.dev/2021/11/07</loc>"));
+}
+
+#[tokio::test]
+async fn independent_pages_are_listed_and_excluded() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"
+            {
+              "url": "https://gamediary.dev",
+              "sitemap_exclude": ["/legal/*"]
+            }
+        "#,
+    )
+    .unwrap();
+    fs::create_dir_all(cwd.path().join("pages/legal")).unwrap();
+    fs::write(cwd.path().join("pages/about.md"), "# About me").unwrap();
+    fs::write(
+        cwd.path().join("pages/legal/privacy.md"),
+        "# Privacy policy",
+    )
+    .unwrap();
+    fs::write(cwd.path().join("pages/legal/terms.html"), "<h1>Terms</h1>").unwrap();
+
+    let generator = Generator::new(
+        &cwd,
+        vec![new_entry(
+            "cf2bacc9d75c4226aab53601c336f295",
+            "Day 0: Nannou, helping L, and lots of noise",
+            "Every journey starts with 1 O'clock: assistance.",
+            Some("2021-11-07".parse().unwrap()),
+            None,
+        )],
+    )
+    .await
+    .unwrap();
+    generator
+        .generate_sitemap()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let sitemap = fs::read_to_string(cwd.path().join("output").join("sitemap.xml")).unwrap();
+    assert!(sitemap.contains(
+        "<url><loc>https://gamediary.dev/about</loc><changefreq>monthly</changefreq><priority>0.3</priority></url>"
+    ));
+    assert!(sitemap.contains("<loc>https://gamediary.dev/subscribe</loc>"));
+    assert!(!sitemap.contains("https://gamediary.dev/legal/"));
+}
@@ -0,0 +1,283 @@
+mod utils;
+
+use diary_generator::{Generator, Options};
+use notion_generator::response::{Block, BlockType, NotionId, Page};
+use std::fs;
+use utils::{function, new_entry, TestDir};
+
+#[tokio::test]
+async fn missing_descriptions_only_fail_strict_builds() {
+    let cwd = TestDir::new(function!());
+
+    let pages = || {
+        vec![new_entry(
+            "cf2bacc9d75c4226aab53601c336f295",
+            "Day 0: Nannou, helping L, and lots of noise",
+            "",
+            Some("2021-11-07".parse().unwrap()),
+            None,
+        )]
+    };
+
+    Generator::new(&cwd, pages()).await.unwrap();
+
+    let error = Generator::with_options(
+        &cwd,
+        pages(),
+        Options {
+            strict: true,
+            ..Default::default()
+        },
+    )
+    .await
+    .err()
+    .unwrap();
+    assert_eq!(
+        error.to_string(),
+        "1 page(s) are missing a description, see the warnings above"
+    );
+}
+
+#[tokio::test]
+async fn empty_titles_are_replaced_unless_strict() {
+    let cwd = TestDir::new(function!());
+
+    let pages = || {
+        vec![new_entry(
+            "cf2bacc9d75c4226aab53601c336f295",
+            " ",
+            "Every journey starts with 1 O'clock: assistance.",
+            Some("2021-11-07".parse().unwrap()),
+            None,
+        )]
+    };
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "untitled": "Untitled entry" }"#,
+    )
+    .unwrap();
+
+    let generator = Generator::new(&cwd, pages()).await.unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+    let html = fs::read_to_string(cwd.path().join("output/2021/11/07.html")).unwrap();
+    assert!(html.contains("<title>Untitled entry - Diary</title>"));
+
+    let error = Generator::with_options(
+        &cwd,
+        pages(),
+        Options {
+            strict: true,
+            ..Default::default()
+        },
+    )
+    .await
+    .err()
+    .unwrap();
+    let id = "cf2bacc9d75c4226aab53601c336f295"
+        .parse::<NotionId>()
+        .unwrap();
+    assert_eq!(error.to_string(), format!("Page {} has an empty title", id));
+}
+
+#[tokio::test]
+async fn far_future_dates_only_fail_strict_builds() {
+    let cwd = TestDir::new(function!());
+
+    let pages = || {
+        vec![new_entry(
+            "cf2bacc9d75c4226aab53601c336f295",
+            "Day 0: Nannou, helping L, and lots of noise",
+            "Every journey starts with 1 O'clock: assistance.",
+            Some("9999-11-07".parse().unwrap()),
+            None,
+        )]
+    };
+
+    Generator::new(&cwd, pages()).await.unwrap();
+
+    let error = Generator::with_options(
+        &cwd,
+        pages(),
+        Options {
+            strict: true,
+            ..Default::default()
+        },
+    )
+    .await
+    .err()
+    .unwrap();
+    assert_eq!(
+        error.to_string(),
+        format!(
+            "Page {} has the date 9999-11-07 which is more than 1 year(s) from now",
+            "cf2bacc9d75c4226aab53601c336f295"
+                .parse::<NotionId>()
+                .unwrap()
+        )
+    );
+}
+
+#[tokio::test]
+async fn malformed_partials_only_fail_strict_builds() {
+    let cwd = TestDir::new(function!());
+
+    fs::create_dir(cwd.path().join("partials")).unwrap();
+    fs::write(
+        cwd.path().join("partials/header.html"),
+        r#"<div class="banner"><a href="/">Home</a>"#,
+    )
+    .unwrap();
+
+    let pages = || {
+        vec![new_entry(
+            "cf2bacc9d75c4226aab53601c336f295",
+            "Day 0: Nannou, helping L, and lots of noise",
+            "Every journey starts with 1 O'clock: assistance.",
+            Some("2021-11-07".parse().unwrap()),
+            None,
+        )]
+    };
+
+    Generator::new(&cwd, pages()).await.unwrap();
+
+    let error = Generator::with_options(
+        &cwd,
+        pages(),
+        Options {
+            strict: true,
+            ..Default::default()
+        },
+    )
+    .await
+    .err()
+    .unwrap();
+    assert_eq!(
+        format!("{:#}", error),
+        "Partial file partials/header.html is malformed: Unclosed tag <div>"
+    );
+}
+
+#[tokio::test]
+async fn broken_pages_are_skipped_when_keeping_going() {
+    let cwd = TestDir::new(function!());
+
+    let pages = || {
+        vec![
+            new_entry(
+                "cf2bacc9d75c4226aab53601c336f295",
+                "Day 0: Nannou, helping L, and lots of noise",
+                "Every journey starts with 1 O'clock: assistance.",
+                Some("2021-11-07".parse().unwrap()),
+                None,
+            ),
+            // Neither a date nor a URL so there's nowhere to put it
+            new_entry(
+                "ac3fb543001f4be5a25e4978abd05b1d",
+                "Day 1: Down the rabbit hole we go",
+                "Alice starts making games.",
+                None,
+                None,
+            ),
+        ]
+    };
+
+    assert_eq!(
+        Generator::new(&cwd, pages())
+            .await
+            .err()
+            .unwrap()
+            .to_string(),
+        "Diary pages must have either a date or a URL"
+    );
+
+    let generator = Generator::with_options(
+        &cwd,
+        pages(),
+        Options {
+            keep_going: true,
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(generator.failures(), 1);
+    assert_eq!(generator.entry_count(), 1);
+
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+    assert!(cwd.path().join("output/2021/11/07.html").exists());
+}
+
+#[tokio::test]
+async fn pages_that_fail_to_render_are_not_linked_to() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "url": "https://gamediary.dev" }"#,
+    )
+    .unwrap();
+    let broken = Page {
+        children: vec![Block {
+            object: "block".to_string(),
+            id: "817c0ca1-721a-4565-ac54-eedbbe471f0b".parse().unwrap(),
+            created_time: "2021-11-15T18:03:00.000Z".to_string(),
+            last_edited_time: "2021-11-16T11:23:00.000Z".to_string(),
+            has_children: false,
+            archived: false,
+            ty: BlockType::Unsupported,
+        }],
+        ..new_entry(
+            "ac3fb543001f4be5a25e4978abd05b1d",
+            "Day 1: Down the rabbit hole we go",
+            "Alice starts making games.",
+            Some("2021-11-08".parse().unwrap()),
+            None,
+        )
+    };
+    let generator = Generator::with_options(
+        &cwd,
+        vec![
+            new_entry(
+                "cf2bacc9d75c4226aab53601c336f295",
+                "Day 0: Nannou, helping L, and lots of noise",
+                "Every journey starts with 1 O'clock: assistance.",
+                Some("2021-11-07".parse().unwrap()),
+                None,
+            ),
+            broken,
+        ],
+        Options {
+            keep_going: true,
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(generator.failures(), 1);
+    assert_eq!(generator.entry_count(), 1);
+
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+    generator
+        .generate_index_page()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+    generator
+        .generate_atom_feed()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert!(!cwd.path().join("output/2021/11/08.html").exists());
+    let day = fs::read_to_string(cwd.path().join("output/2021/11/07.html")).unwrap();
+    assert!(!day.contains("2021/11/08"));
+    let index = fs::read_to_string(cwd.path().join("output/index.html")).unwrap();
+    assert!(index.contains("2021/11/07"));
+    assert!(!index.contains("2021/11/08"));
+    let feed = fs::read_to_string(cwd.path().join("output/feed.xml")).unwrap();
+    assert!(feed.contains("2021/11/07"));
+    assert!(!feed.contains("2021/11/08"));
+}
@@ -0,0 +1,62 @@
+mod utils;
+
+use diary_generator::{Generator, Properties};
+use notion_generator::response::Page;
+use std::fs;
+use utils::{function, new_entry, TestDir};
+
+fn entries() -> Vec<Page<Properties>> {
+    vec![new_entry(
+        "cf2bacc9d75c4226aab53601c336f295",
+        "Day 0: Nannou, helping L, and lots of noise",
+        "Every journey starts with 1 O'clock: assistance.",
+        Some("2021-11-07".parse().unwrap()),
+        None,
+    )]
+}
+
+#[tokio::test]
+async fn lists_feed_and_links() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"
+            {
+              "url": "https://gamediary.dev",
+              "fediverse": "https://mastodon.social/@Mathspy"
+            }
+        "#,
+    )
+    .unwrap();
+
+    let generator = Generator::new(&cwd, entries()).await.unwrap();
+    generator
+        .generate_subscribe_page()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let page = fs::read_to_string(cwd.path().join("output/subscribe.html")).unwrap();
+    assert!(page.contains(concat!(
+        r#"<ul><li><a href="https://gamediary.dev/feed.xml" type="application/atom+xml">Atom feed</a></li>"#,
+        r#"<li><a href="https://gamediary.dev/feed.json" type="application/feed+json">JSON Feed</a></li>"#,
+        r#"<li><a href="https://mastodon.social/@Mathspy" rel="me">Fediverse</a></li></ul>"#
+    )));
+}
+
+#[tokio::test]
+async fn skipped_without_ways_to_subscribe() {
+    let cwd = TestDir::new(function!());
+
+    let generator = Generator::new(&cwd, entries()).await.unwrap();
+    generator
+        .generate_subscribe_page()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert!(!cwd.path().join("output/subscribe.html").exists());
+}
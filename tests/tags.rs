@@ -0,0 +1,350 @@
+mod utils;
+
+use diary_generator::{Generator, MultiSelectProperty, Properties, SelectOption};
+use notion_generator::response::Page;
+use std::fs;
+use time::macros::date;
+use utils::{function, new_article, new_entry, TestDir};
+
+fn tagged(page: Page<Properties>, tags: &[&str]) -> Page<Properties> {
+    Page {
+        properties: Properties {
+            tags: Some(MultiSelectProperty {
+                id: "%3BtNr".to_string(),
+                multi_select: tags
+                    .iter()
+                    .map(|tag| SelectOption {
+                        id: tag.to_string(),
+                        name: tag.to_string(),
+                        color: "default".to_string(),
+                    })
+                    .collect(),
+            }),
+            ..page.properties
+        },
+        ..page
+    }
+}
+
+#[tokio::test]
+async fn tag_pages_and_cloud() {
+    let cwd = TestDir::new(function!());
+
+    let generator = Generator::new(
+        &cwd,
+        vec![
+            tagged(
+                new_entry(
+                    "cf2bacc9d75c4226aab53601c336f295",
+                    "Day 0: Nannou, helping L, and lots of noise",
+                    "Every journey starts with 1 O'clock: assistance.",
+                    Some("2021-11-07".parse().unwrap()),
+                    None,
+                ),
+                &["Rust", "Generative Art"],
+            ),
+            tagged(
+                new_entry(
+                    "ac3fb543001f4be5a25e4978abd05b1d",
+                    "Day 1: Down the rabbit hole we go",
+                    "Alice starts making games.",
+                    Some("2021-11-08".parse().unwrap()),
+                    None,
+                ),
+                &["Rust"],
+            ),
+            tagged(
+                new_article(
+                    "5f5e3d4b9b7a4a3c8d8e2f1a0b9c8d7e",
+                    "Interesting article",
+                    "A very interesting article.",
+                    "interesting_article",
+                    Some(date!(2021 - 11 - 10)),
+                ),
+                &["Rust"],
+            ),
+        ],
+    )
+    .await
+    .unwrap();
+    generator
+        .generate_tag_pages()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let rust = fs::read_to_string(cwd.path().join("output/tags/rust.html")).unwrap();
+    let article = rust.find(r#"href="/interesting_article""#).unwrap();
+    let day_1 = rust.find(r#"href="/2021/11/08""#).unwrap();
+    let day_0 = rust.find(r#"href="/2021/11/07""#).unwrap();
+    assert!(article < day_1 && day_1 < day_0);
+
+    let generative_art =
+        fs::read_to_string(cwd.path().join("output/tags/generative-art.html")).unwrap();
+    assert!(generative_art.contains(r#"href="/2021/11/07""#));
+    assert!(!generative_art.contains(r#"href="/2021/11/08""#));
+
+    let cloud = fs::read_to_string(cwd.path().join("output/tags.html")).unwrap();
+    assert!(cloud.contains(concat!(
+        r#"<span class="tag-weight-1" data-weight="1" style="font-size: 1em">"#,
+        r#"<a href="/tags/generative-art">Generative Art</a> <span class="tag-count">1</span></span>"#
+    )));
+    assert!(cloud.contains(concat!(
+        r#"<span class="tag-weight-5" data-weight="5" style="font-size: 2em">"#,
+        r#"<a href="/tags/rust">Rust</a> <span class="tag-count">3</span></span>"#
+    )));
+}
+
+#[tokio::test]
+async fn entries_list_their_tags() {
+    let cwd = TestDir::new(function!());
+
+    let generator = Generator::new(
+        &cwd,
+        vec![
+            tagged(
+                new_entry(
+                    "cf2bacc9d75c4226aab53601c336f295",
+                    "Day 0: Nannou, helping L, and lots of noise",
+                    "Every journey starts with 1 O'clock: assistance.",
+                    Some("2021-11-07".parse().unwrap()),
+                    None,
+                ),
+                &["Rust", "Generative Art"],
+            ),
+            new_entry(
+                "ac3fb543001f4be5a25e4978abd05b1d",
+                "Day 1: Down the rabbit hole we go",
+                "Alice starts making games.",
+                Some("2021-11-08".parse().unwrap()),
+                None,
+            ),
+        ],
+    )
+    .await
+    .unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+
+    let day_0 = fs::read_to_string(cwd.path().join("output/2021/11/07.html")).unwrap();
+    assert!(day_0.contains(concat!(
+        r#"<ul class="tags">"#,
+        r#"<li><a rel="tag" href="/tags/rust">Rust</a></li>"#,
+        r#"<li><a rel="tag" href="/tags/generative-art">Generative Art</a></li>"#,
+        "</ul>"
+    )));
+
+    let day_1 = fs::read_to_string(cwd.path().join("output/2021/11/08.html")).unwrap();
+    assert!(!day_1.contains(r#"class="tags""#));
+}
+
+#[tokio::test]
+async fn similar_counts_share_a_size() {
+    let cwd = TestDir::new(function!());
+
+    let entries = (1..=9)
+        .map(|day| {
+            let tags: &[&str] = if day == 9 {
+                &["Rust"]
+            } else {
+                &["Rust", "Bevy"]
+            };
+            tagged(
+                new_entry(
+                    &format!("cf2bacc9d75c4226aab53601c336f29{}", day),
+                    &format!("Day {}", day),
+                    "An entry.",
+                    Some(format!("2021-11-0{}", day).parse().unwrap()),
+                    None,
+                ),
+                tags,
+            )
+        })
+        .collect();
+
+    let generator = Generator::new(&cwd, entries).await.unwrap();
+    generator
+        .generate_tag_pages()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let cloud = fs::read_to_string(cwd.path().join("output/tags.html")).unwrap();
+    assert_eq!(cloud.matches(r#"data-weight="1""#).count(), 2);
+}
+
+#[tokio::test]
+async fn emoji_tags_get_a_page() {
+    let cwd = TestDir::new(function!());
+
+    let generator = Generator::new(
+        &cwd,
+        vec![tagged(
+            new_entry(
+                "cf2bacc9d75c4226aab53601c336f295",
+                "Day 0: Nannou, helping L, and lots of noise",
+                "Every journey starts with 1 O'clock: assistance.",
+                Some("2021-11-07".parse().unwrap()),
+                None,
+            ),
+            &["🦀"],
+        )],
+    )
+    .await
+    .unwrap();
+    generator
+        .generate_tag_pages()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let crab = fs::read_to_string(cwd.path().join("output/tags/ff9c5b38758886e6.html")).unwrap();
+    assert!(crab.contains(r#"href="/2021/11/07""#));
+    let cloud = fs::read_to_string(cwd.path().join("output/tags.html")).unwrap();
+    assert!(cloud.contains(r#"<a href="/tags/ff9c5b38758886e6">🦀</a>"#));
+}
+
+#[tokio::test]
+async fn tags_sharing_a_page_fail() {
+    let cwd = TestDir::new(function!());
+
+    for (first, second) in [("C++", "C"), ("Rust", "rust")] {
+        let error = Generator::new(
+            &cwd,
+            vec![
+                tagged(
+                    new_entry(
+                        "cf2bacc9d75c4226aab53601c336f295",
+                        "Day 0: Nannou, helping L, and lots of noise",
+                        "Every journey starts with 1 O'clock: assistance.",
+                        Some("2021-11-07".parse().unwrap()),
+                        None,
+                    ),
+                    &[first],
+                ),
+                tagged(
+                    new_article(
+                        "5f5e3d4b9b7a4a3c8d8e2f1a0b9c8d7e",
+                        "Interesting article",
+                        "A very interesting article.",
+                        "interesting_article",
+                        Some(date!(2021 - 11 - 10)),
+                    ),
+                    &[second],
+                ),
+            ],
+        )
+        .await
+        .err()
+        .unwrap();
+
+        assert!(error.to_string().contains(&format!(
+            "The tags {:?} and {:?} would both be at /tags/",
+            first, second
+        )));
+    }
+}
+
+#[tokio::test]
+async fn og_article_properties() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "author": { "name": "Mathspy", "url": "https://mathspy.dev/" } }"#,
+    )
+    .unwrap();
+
+    let generator = Generator::new(
+        &cwd,
+        vec![tagged(
+            new_entry(
+                "cf2bacc9d75c4226aab53601c336f295",
+                "Day 0: Nannou, helping L, and lots of noise",
+                "Every journey starts with 1 O'clock: assistance.",
+                Some("2021-11-07".parse().unwrap()),
+                None,
+            ),
+            &["Rust", "Generative Art"],
+        )],
+    )
+    .await
+    .unwrap();
+    generator.generate_days().unwrap().await.unwrap().unwrap();
+
+    let day = fs::read_to_string(cwd.path().join("output/2021/11/07.html")).unwrap();
+    assert!(day.contains(concat!(
+        r#"<meta property="og:type" content="article">"#,
+        r#"<meta property="og:site_name" content="Diary">"#,
+        r#"<meta property="article:author" content="https://mathspy.dev/">"#,
+        r#"<meta property="article:section" content="Rust">"#,
+        r#"<meta property="article:tag" content="Rust">"#,
+        r#"<meta property="article:tag" content="Generative Art">"#
+    )));
+}
+
+#[tokio::test]
+async fn feed_categories() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "url": "https://gamediary.dev" }"#,
+    )
+    .unwrap();
+
+    let generator = Generator::new(
+        &cwd,
+        vec![
+            tagged(
+                new_article(
+                    "78abd05b1dac3fb543001f4be5a25e49",
+                    "Some article about something",
+                    "some really interesting descritpion",
+                    "interesting_article",
+                    Some(date!(2021 - 12 - 08)),
+                ),
+                &["Rust", "Bevy & Nannou"],
+            ),
+            new_article(
+                "ac3fb543001f4be5a25e4978abd05b1d",
+                "An untagged article",
+                "some less interesting descritpion",
+                "untagged_article",
+                Some(date!(2021 - 12 - 09)),
+            ),
+        ],
+    )
+    .await
+    .unwrap();
+    generator
+        .generate_atom_feed()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+    generator
+        .generate_json_feed()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let feed = fs::read_to_string(cwd.path().join("output/feed.xml")).unwrap();
+    assert!(feed.contains(concat!(
+        r#"<summary>some really interesting descritpion</summary>"#,
+        r#"<category term="Rust"></category><category term="Bevy &amp; Nannou"></category>"#,
+    )));
+    assert_eq!(feed.matches("<category").count(), 2);
+
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(cwd.path().join("output/feed.json")).unwrap())
+            .unwrap();
+    assert_eq!(json["items"][0].get("tags"), None);
+    assert_eq!(
+        json["items"][1]["tags"],
+        serde_json::json!(["Rust", "Bevy & Nannou"])
+    );
+}
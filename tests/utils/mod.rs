@@ -1,5 +1,6 @@
 #![allow(dead_code)]
 mod page;
+mod server;
 
 use std::{
     collections::HashMap,
@@ -9,8 +10,10 @@ use std::{
 };
 use tempdir::TempDir;
 
-pub use page::new as new_entry;
-pub use page::new_article;
+#[allow(unused_imports)]
+pub use page::{new as new_entry, new_article};
+#[allow(unused_imports)]
+pub use server::Server;
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct DirEntry {
@@ -73,6 +73,14 @@ pub fn new(
                     href: None,
                 }],
             },
+            series: None,
+            lang: None,
+            tags: None,
+            og_image: None,
+            unlisted: None,
+            draft: None,
+            canonical: None,
+            feed_summary: None,
         },
         parent: PageParent::Database {
             id: "4045404e-233a-4278-84f0-b3389887b315".to_string(),
@@ -0,0 +1,69 @@
+use std::{
+    io::{Read, Write},
+    net::TcpListener,
+    sync::{Arc, Mutex},
+};
+
+/// A tiny HTTP server to download from in tests, every request is answered with the status and
+/// body `respond` returns for its method and path (query included)
+pub struct Server {
+    url: String,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl Server {
+    pub fn new<F>(respond: F) -> Self
+    where
+        F: Fn(&str, &str) -> (u16, Vec<u8>) + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let log = Arc::clone(&requests);
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut request = Vec::new();
+                let mut buffer = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    let read = stream.read(&mut buffer).unwrap();
+                    if read == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buffer[..read]);
+                }
+
+                let request = String::from_utf8_lossy(&request);
+                let mut request_line = request.lines().next().unwrap_or_default().split(' ');
+                let method = request_line.next().unwrap_or_default();
+                let path = request_line.next().unwrap_or_default();
+                log.lock().unwrap().push(format!("{} {}", method, path));
+
+                let (status, body) = respond(method, path);
+                write!(
+                    stream,
+                    "HTTP/1.1 {} Status\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    status,
+                    body.len()
+                )
+                .unwrap();
+                if method != "HEAD" {
+                    stream.write_all(&body).unwrap();
+                }
+            }
+        });
+
+        Server { url, requests }
+    }
+
+    /// The URL the server is reachable at, ending with a `/`
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// The method and path of every request made so far, like `GET /cover.png`
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}
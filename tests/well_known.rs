@@ -0,0 +1,93 @@
+mod utils;
+
+use diary_generator::{Generator, Options, DIARY_GENERATOR, VERSION};
+use pretty_assertions::assert_eq;
+use std::fs;
+use time::macros::date;
+use utils::{function, TestDir};
+
+#[tokio::test]
+async fn humans_txt() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{ "humans": true, "author": { "name": "Mathspy", "url": "https://mathspy.me" } }"#,
+    )
+    .unwrap();
+
+    let generator = Generator::new(&cwd, Vec::new()).await.unwrap();
+    generator.generate_humans().await.unwrap().unwrap();
+
+    assert_eq!(
+        fs::read_to_string(cwd.path().join("output/humans.txt")).unwrap(),
+        format!(
+            "/* TEAM */
+Author: Mathspy
+Site: https://mathspy.me/
+
+/* SITE */
+Language: en
+Software: {} {}
+",
+            DIARY_GENERATOR, VERSION
+        )
+    );
+}
+
+#[tokio::test]
+async fn security_txt() {
+    let cwd = TestDir::new(function!());
+
+    fs::write(
+        cwd.path().join("config.json"),
+        r#"{
+            "url": "https://diary.example.com",
+            "security": { "contact": "mailto:me@example.com", "expires_in_days": 30 }
+        }"#,
+    )
+    .unwrap();
+
+    let generator = Generator::with_options(
+        &cwd,
+        Vec::new(),
+        Options {
+            today: Some(date!(2021 - 11 - 07)),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    generator
+        .generate_security_txt()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(
+        fs::read_to_string(cwd.path().join("output/.well-known/security.txt")).unwrap(),
+        "Contact: mailto:me@example.com
+Expires: 2021-12-07T00:00:00Z
+Preferred-Languages: en
+Canonical: https://diary.example.com/.well-known/security.txt
+"
+    );
+}
+
+#[tokio::test]
+async fn nothing_is_written_by_default() {
+    let cwd = TestDir::new(function!());
+
+    let generator = Generator::new(&cwd, Vec::new()).await.unwrap();
+    generator.generate_humans().await.unwrap().unwrap();
+    generator
+        .generate_security_txt()
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert!(!cwd.path().join("output/humans.txt").exists());
+    assert!(!cwd.path().join("output/.well-known").exists());
+}